@@ -0,0 +1,339 @@
+use std::{collections::{HashMap, VecDeque}, time::Duration};
+
+use bytes::Bytes;
+use tokio::{sync::oneshot, time::sleep};
+
+use crate::{commands::CommandError, connection::Connection, resp::RespValues, server::Server, store::Store, types::Value, util::{parse_float, respond}};
+
+/// Runs the operation a blocked client waits for on a key that became ready.
+/// Returns `None` if the key cannot serve the client, e.g. because it holds another type, so the client keeps waiting.
+pub type ServeFn = Box<dyn FnMut(&mut Store, &Bytes) -> Option<Result<RespValues, CommandError>> + Send>;
+
+struct BlockedClient {
+	keys: Vec<Bytes>,
+	serve: ServeFn,
+	sender: oneshot::Sender<Result<RespValues, CommandError>>,
+}
+
+/// The clients of a database that are blocked on keys, like `BLPOP` waiting for a list to be pushed to.
+/// Each key has a queue of the clients waiting for it, so clients are served in the order they blocked.
+/// Keys that may be able to serve clients are collected as ready keys and handled by `serve_blocked_clients`.
+#[derive(Default)]
+pub struct BlockedClients {
+	clients: HashMap<u64, BlockedClient>,
+	queues: HashMap<Bytes, VecDeque<u64>>,
+	ready: VecDeque<Bytes>,
+}
+
+impl BlockedClients {
+	/// Registers `client` as waiting for any of `keys`. The result of `serve` is sent to the returned receiver.
+	pub fn block(&mut self, client: u64, keys: Vec<Bytes>, serve: ServeFn) -> oneshot::Receiver<Result<RespValues, CommandError>> {
+		let (sender, receiver) = oneshot::channel();
+		for key in &keys {
+			let queue = self.queues.entry(key.clone()).or_default();
+			// A key given twice only queues the client once.
+			if !queue.contains(&client) {
+				queue.push_back(client);
+			}
+		}
+		self.clients.insert(client, BlockedClient { keys, serve, sender });
+		receiver
+	}
+
+	/// Removes the registrations of `client`, if it is still blocked.
+	pub fn unblock(&mut self, client: u64) {
+		if let Some(blocked) = self.clients.remove(&client) {
+			self.remove_from_queues(client, &blocked.keys);
+		}
+	}
+
+	/// Marks `key` as ready if clients are blocked on it.
+	pub fn signal_key_as_ready(&mut self, key: &[u8]) {
+		if self.queues.contains_key(key) && !self.ready.iter().any(|k| k == key) {
+			self.ready.push_back(Bytes::copy_from_slice(key));
+		}
+	}
+
+	pub fn next_ready_key(&mut self) -> Option<Bytes> {
+		self.ready.pop_front()
+	}
+
+	/// The clients blocked on `key`, in the order they blocked.
+	pub fn waiting_for(&self, key: &[u8]) -> Vec<u64> {
+		self.queues.get(key).map(|queue| queue.iter().copied().collect()).unwrap_or_default()
+	}
+
+	fn remove_from_queues(&mut self, client: u64, keys: &[Bytes]) {
+		for key in keys {
+			if let Some(queue) = self.queues.get_mut(key) {
+				queue.retain(|&c| c != client);
+				if queue.is_empty() {
+					self.queues.remove(key);
+				}
+			}
+		}
+	}
+}
+
+/// Serves the clients blocked on the keys that became ready, in the order they blocked.
+/// Serving a client may make other keys ready, e.g. the destination of `BLMOVE`, which are served as well.
+pub fn serve_blocked_clients(store: &mut Store) {
+	while let Some(key) = store.blocked_clients().next_ready_key() {
+		for client in store.blocked_clients().waiting_for(&key) {
+			if !store.has(&key) {
+				break;
+			}
+			try_serve(store, client, &key);
+		}
+	}
+}
+
+/// Serves `client` from `key`, unless `key` cannot serve it.
+/// The client is taken out of the registry while `serve` runs, so it can modify the store.
+fn try_serve(store: &mut Store, client: u64, key: &Bytes) {
+	let Some(mut blocked) = store.blocked_clients().clients.remove(&client) else {
+		return;
+	};
+	// The client went away without unblocking, don't consume elements for it.
+	if blocked.sender.is_closed() {
+		store.blocked_clients().remove_from_queues(client, &blocked.keys);
+		return;
+	}
+	match (blocked.serve)(store, key) {
+		Some(result) => {
+			store.blocked_clients().remove_from_queues(client, &blocked.keys);
+			let _ = blocked.sender.send(result);
+		},
+		None => {
+			store.blocked_clients().clients.insert(client, blocked);
+		},
+	}
+}
+
+/// Unblocks the client when dropped, so a client that times out, disconnects or whose task is cancelled does not stay registered.
+struct Registration<'a> {
+	server: &'a Server,
+	db: usize,
+	client: u64,
+}
+
+impl Drop for Registration<'_> {
+	fn drop(&mut self) {
+		self.server.store(self.db).blocked_clients().unblock(self.client);
+	}
+}
+
+/// Serves the client right away from the first of `keys` that can, or blocks it until one of them can.
+/// Keys that exist but fail `is_type` are a `WRONGTYPE` error.
+pub async fn serve_or_block(
+	server: &Server,
+	connection: &mut Connection,
+	keys: Vec<Bytes>,
+	timeout: Option<Duration>,
+	timeout_response: RespValues,
+	is_type: fn(&Value) -> bool,
+	mut serve: ServeFn,
+) -> Result<(), CommandError> {
+	let served = {
+		let mut store = server.store(connection.db());
+		let mut served = None;
+		for key in &keys {
+			if store.get(key).is_some_and(|value| !is_type(value)) {
+				return Err(CommandError::WrongType);
+			}
+			served = serve(&mut store, key);
+			if served.is_some() {
+				break;
+			}
+		}
+		match served {
+			Some(result) => Served::Now(result),
+			None => Served::Later(store.blocked_clients().block(connection.id(), keys, serve)),
+		}
+	};
+	match served {
+		Served::Now(result) => {
+			respond(connection, result?).await;
+			Ok(())
+		},
+		Served::Later(receiver) => wait_until_served(server, connection, receiver, timeout, timeout_response).await,
+	}
+}
+
+enum Served {
+	Now(Result<RespValues, CommandError>),
+	Later(oneshot::Receiver<Result<RespValues, CommandError>>),
+}
+
+/// Waits until the client blocked with `receiver` is served and replies with the result,
+/// or replies with `timeout_response` once `timeout` passed. A `timeout` of `None` waits forever.
+/// Stops waiting if the client disconnects. Requests sent while blocked are buffered and processed afterwards.
+async fn wait_until_served(
+	server: &Server,
+	connection: &mut Connection,
+	receiver: oneshot::Receiver<Result<RespValues, CommandError>>,
+	timeout: Option<Duration>,
+	timeout_response: RespValues,
+) -> Result<(), CommandError> {
+	let registration = Registration { server, db: connection.db(), client: connection.id() };
+	let mut receiver = receiver;
+	let timed_out = async {
+		match timeout {
+			Some(timeout) => sleep(timeout).await,
+			None => std::future::pending().await,
+		}
+	};
+	let response = tokio::select! {
+		result = &mut receiver => result.ok(),
+		_ = timed_out => None,
+		_ = connection.closed() => return Ok(()),
+	};
+	drop(registration);
+	// The client may have been served right before it was unblocked.
+	let response = match response {
+		Some(response) => response?,
+		None => match receiver.try_recv() {
+			Ok(response) => response?,
+			Err(_) => timeout_response,
+		},
+	};
+	respond(connection, response).await;
+	Ok(())
+}
+
+/// Parses the timeout of blocking commands in seconds. 0 blocks forever.
+pub fn parse_timeout(arg: &[u8]) -> Result<Option<Duration>, CommandError> {
+	let timeout = parse_float(arg).map_err(|_| CommandError::Other(String::from("timeout is not a float or out of range")))?;
+	if timeout < 0.0 {
+		return Err(CommandError::Other(String::from("timeout is negative")));
+	}
+	if timeout == 0.0 {
+		return Ok(None);
+	}
+	Duration::try_from_secs_f64(timeout).map(Some).map_err(|_| CommandError::Other(String::from("timeout is out of range")))
+}
+
+#[cfg(test)]
+mod tests {
+	use tokio::sync::oneshot::error::TryRecvError;
+
+	use crate::{server::ServerConfig, test_util::{bytes, connected}, types::list::{List, ListEnd}, util::{bulk_string_response, null_array_response}};
+
+	use super::*;
+
+	fn pop() -> ServeFn {
+		Box::new(|store: &mut Store, key: &Bytes| {
+			let element = store.get_mut(key)?.as_list_mut().ok()?.pop(ListEnd::Head, -2)?;
+			store.delete_if_empty(key);
+			Some(Ok(bulk_string_response(element)))
+		})
+	}
+
+	fn push(store: &mut Store, key: &str, elements: &[&str]) {
+		let list = store.get_or_insert_with(key.as_bytes(), || Value::List(List::new())).as_list_mut().unwrap();
+		for element in elements {
+			list.push(element.as_bytes(), ListEnd::Tail, -2);
+		}
+	}
+
+	fn served(receiver: &mut oneshot::Receiver<Result<RespValues, CommandError>>) -> Option<RespValues> {
+		receiver.try_recv().ok().map(Result::unwrap)
+	}
+
+	/// Like `BLPOP list`.
+	async fn blpop(server: &Server, connection: &mut Connection, timeout: Option<Duration>) -> Result<(), CommandError> {
+		serve_or_block(server, connection, bytes(&["list"]), timeout, null_array_response(), |value| value.as_list().is_ok(), pop()).await
+	}
+
+	#[test]
+	fn serves_clients_in_the_order_they_blocked() {
+		let mut store = Store::init();
+		let mut first = store.blocked_clients().block(1, bytes(&["list"]), pop());
+		let mut second = store.blocked_clients().block(2, bytes(&["other", "list"]), pop());
+		let mut third = store.blocked_clients().block(3, bytes(&["list"]), pop());
+		push(&mut store, "list", &["a", "b"]);
+		serve_blocked_clients(&mut store);
+		assert_eq!(served(&mut first), Some(bulk_string_response("a")));
+		assert_eq!(served(&mut second), Some(bulk_string_response("b")));
+		assert_eq!(third.try_recv().unwrap_err(), TryRecvError::Empty);
+		assert!(!store.has(b"list"));
+		// Served clients are no longer registered for any of their keys.
+		assert_eq!(store.blocked_clients().waiting_for(b"list"), [3]);
+		assert!(store.blocked_clients().waiting_for(b"other").is_empty());
+
+		push(&mut store, "list", &["c"]);
+		serve_blocked_clients(&mut store);
+		assert_eq!(served(&mut third), Some(bulk_string_response("c")));
+		assert!(store.blocked_clients().waiting_for(b"list").is_empty());
+	}
+
+	#[test]
+	fn skips_clients_that_went_away() {
+		let mut store = Store::init();
+		let first = store.blocked_clients().block(1, bytes(&["list"]), pop());
+		let mut second = store.blocked_clients().block(2, bytes(&["list"]), pop());
+		drop(first);
+		push(&mut store, "list", &["a", "b"]);
+		serve_blocked_clients(&mut store);
+		assert_eq!(served(&mut second), Some(bulk_string_response("a")));
+		assert_eq!(store.get_mut(b"list").unwrap().as_list_mut().unwrap().len(), 1);
+		assert!(store.blocked_clients().waiting_for(b"list").is_empty());
+	}
+
+	#[test]
+	fn keeps_clients_blocked_if_the_key_cannot_serve_them() {
+		let mut store = Store::init();
+		let mut client = store.blocked_clients().block(1, bytes(&["key"]), pop());
+		store.set(Bytes::from_static(b"key"), Bytes::from_static(b"string"), None);
+		serve_blocked_clients(&mut store);
+		assert_eq!(client.try_recv().unwrap_err(), TryRecvError::Empty);
+		assert_eq!(store.blocked_clients().waiting_for(b"key"), [1]);
+		store.blocked_clients().unblock(1);
+		assert!(store.blocked_clients().waiting_for(b"key").is_empty());
+	}
+
+	#[tokio::test]
+	async fn serves_blocked_clients_when_the_store_is_released() {
+		let server = Server::new(ServerConfig::default());
+		let (mut connection, client) = connected().await;
+		let push_later = async {
+			sleep(Duration::from_millis(50)).await;
+			push(&mut server.store(0), "list", &["a"]);
+		};
+		let (result, _) = tokio::join!(blpop(&server, &mut connection, None), push_later);
+		assert_eq!(result, Ok(()));
+		assert_eq!(Connection::new(client).read_frame().await.unwrap(), Some(bulk_string_response("a")));
+		assert!(!server.store(0).has(b"list"));
+	}
+
+	#[tokio::test]
+	async fn replies_with_the_timeout_response_and_unblocks_on_timeout() {
+		let server = Server::new(ServerConfig::default());
+		let (mut connection, client) = connected().await;
+		let result = blpop(&server, &mut connection, Some(Duration::from_millis(50))).await;
+		assert_eq!(result, Ok(()));
+		assert_eq!(Connection::new(client).read_frame().await.unwrap(), Some(null_array_response()));
+		assert!(server.store(0).blocked_clients().waiting_for(b"list").is_empty());
+	}
+
+	#[tokio::test]
+	async fn unblocks_clients_that_disconnect() {
+		let server = Server::new(ServerConfig::default());
+		let (mut connection, client) = connected().await;
+		drop(client);
+		let result = blpop(&server, &mut connection, None).await;
+		assert_eq!(result, Ok(()));
+		assert!(server.store(0).blocked_clients().waiting_for(b"list").is_empty());
+		// Elements pushed afterwards are not consumed for the client.
+		push(&mut server.store(0), "list", &["a"]);
+		assert!(server.store(0).has(b"list"));
+	}
+
+	#[test]
+	fn parses_timeouts() {
+		assert_eq!(parse_timeout(b"0").unwrap(), None);
+		assert_eq!(parse_timeout(b"0.5").unwrap(), Some(Duration::from_millis(500)));
+		assert!(parse_timeout(b"-1").is_err());
+		assert!(parse_timeout(b"soon").is_err());
+	}
+}
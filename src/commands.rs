@@ -1,8 +1,11 @@
-use tokio::net::TcpStream;
+use std::{collections::HashMap, sync::OnceLock};
 
-use crate::{commands::replconf::CommandReplconf, resp::RespValues};
+use bytes::Bytes;
+use thiserror::Error;
 
-use self::{echo::CommandEcho, get::CommandGet, info::CommandInfo, ping::CommandPing, psync::CommandPsync, set::CommandSet};
+use crate::{commands::replconf::CommandReplconf, connection::Connection, resp::RespValues, server::Server, util::{error_reply, parse_integer}};
+
+use self::{append::CommandAppend, blmove::CommandBlmove, blmpop::CommandBlmpop, blpop::CommandBlpop, brpop::CommandBrpop, brpoplpush::CommandBrpoplpush, bzmpop::CommandBzmpop, bzpopmax::CommandBzpopmax, bzpopmin::CommandBzpopmin, command::CommandCommand, copy::CommandCopy, decr::CommandDecr, decrby::CommandDecrby, del::CommandDel, echo::CommandEcho, exists::CommandExists, expire::CommandExpire, expireat::CommandExpireat, expiretime::CommandExpiretime, get::CommandGet, getdel::CommandGetdel, getex::CommandGetex, getrange::CommandGetrange, getset::CommandGetset, hdel::CommandHdel, hello::CommandHello, hexists::CommandHexists, hexpire::CommandHexpire, hexpireat::CommandHexpireat, hexpiretime::CommandHexpiretime, hget::CommandHget, hgetall::CommandHgetall, hgetex::CommandHgetex, hincrby::CommandHincrby, hincrbyfloat::CommandHincrbyfloat, hkeys::CommandHkeys, hlen::CommandHlen, hmget::CommandHmget, hpersist::CommandHpersist, hpexpire::CommandHpexpire, hpexpireat::CommandHpexpireat, hpexpiretime::CommandHpexpiretime, hpttl::CommandHpttl, hrandfield::CommandHrandfield, hscan::CommandHscan, hset::CommandHset, hsetex::CommandHsetex, hsetnx::CommandHsetnx, hstrlen::CommandHstrlen, httl::CommandHttl, hvals::CommandHvals, incr::CommandIncr, incrby::CommandIncrby, incrbyfloat::CommandIncrbyfloat, info::CommandInfo, keys::CommandKeys, lcs::CommandLcs, lindex::CommandLindex, linsert::CommandLinsert, llen::CommandLlen, lmove::CommandLmove, lmpop::CommandLmpop, lpop::CommandLpop, lpos::CommandLpos, lpush::CommandLpush, lpushx::CommandLpushx, lrange::CommandLrange, lrem::CommandLrem, lset::CommandLset, ltrim::CommandLtrim, mget::CommandMget, mset::CommandMset, msetnx::CommandMsetnx, object::CommandObject, persist::CommandPersist, pexpire::CommandPexpire, pexpireat::CommandPexpireat, pexpiretime::CommandPexpiretime, ping::CommandPing, psetex::CommandPsetex, psync::CommandPsync, pttl::CommandPttl, r#type::CommandType, randomkey::CommandRandomkey, rename::CommandRename, renamenx::CommandRenamenx, rpop::CommandRpop, rpoplpush::CommandRpoplpush, rpush::CommandRpush, rpushx::CommandRpushx, sadd::CommandSadd, scan::CommandScan, scard::CommandScard, sdiff::CommandSdiff, sdiffstore::CommandSdiffstore, select::CommandSelect, set::CommandSet, setex::CommandSetex, setnx::CommandSetnx, setrange::CommandSetrange, sinter::CommandSinter, sintercard::CommandSintercard, sinterstore::CommandSinterstore, sismember::CommandSismember, smembers::CommandSmembers, smismember::CommandSmismember, smove::CommandSmove, spop::CommandSpop, srandmember::CommandSrandmember, srem::CommandSrem, sscan::CommandSscan, strlen::CommandStrlen, sunion::CommandSunion, sunionstore::CommandSunionstore, touch::CommandTouch, ttl::CommandTtl, unlink::CommandUnlink, xadd::CommandXadd, xlen::CommandXlen, zadd::CommandZadd, zcard::CommandZcard, zcount::CommandZcount, zdiff::CommandZdiff, zdiffstore::CommandZdiffstore, zincrby::CommandZincrby, zinter::CommandZinter, zintercard::CommandZintercard, zinterstore::CommandZinterstore, zmpop::CommandZmpop, zmscore::CommandZmscore, zpopmax::CommandZpopmax, zpopmin::CommandZpopmin, zrandmember::CommandZrandmember, zrange::CommandZrange, zrangestore::CommandZrangestore, zrank::CommandZrank, zrem::CommandZrem, zrevrank::CommandZrevrank, zscan::CommandZscan, zscore::CommandZscore, zunion::CommandZunion, zunionstore::CommandZunionstore};
 
 pub(crate) mod ping;
 pub(crate) mod echo;
@@ -11,8 +14,173 @@ pub(crate) mod get;
 pub(crate) mod info;
 pub(crate) mod replconf;
 pub(crate) mod psync;
+pub(crate) mod hello;
+pub(crate) mod command;
+pub(crate) mod expire;
+pub(crate) mod pexpire;
+pub(crate) mod expireat;
+pub(crate) mod pexpireat;
+pub(crate) mod ttl;
+pub(crate) mod pttl;
+pub(crate) mod expiretime;
+pub(crate) mod pexpiretime;
+pub(crate) mod persist;
+pub(crate) mod del;
+pub(crate) mod unlink;
+pub(crate) mod exists;
+pub(crate) mod r#type;
+pub(crate) mod rename;
+pub(crate) mod renamenx;
+pub(crate) mod copy;
+pub(crate) mod touch;
+pub(crate) mod randomkey;
+pub(crate) mod select;
+pub(crate) mod keys;
+pub(crate) mod scan;
+pub(crate) mod object;
+pub(crate) mod incr;
+pub(crate) mod decr;
+pub(crate) mod incrby;
+pub(crate) mod decrby;
+pub(crate) mod incrbyfloat;
+pub(crate) mod append;
+pub(crate) mod strlen;
+pub(crate) mod getrange;
+pub(crate) mod setrange;
+pub(crate) mod getdel;
+pub(crate) mod getex;
+pub(crate) mod getset;
+pub(crate) mod setnx;
+pub(crate) mod setex;
+pub(crate) mod psetex;
+pub(crate) mod lcs;
+pub(crate) mod mget;
+pub(crate) mod mset;
+pub(crate) mod msetnx;
+pub(crate) mod lpush;
+pub(crate) mod rpush;
+pub(crate) mod lpushx;
+pub(crate) mod rpushx;
+pub(crate) mod lpop;
+pub(crate) mod rpop;
+pub(crate) mod lrange;
+pub(crate) mod llen;
+pub(crate) mod lindex;
+pub(crate) mod lset;
+pub(crate) mod linsert;
+pub(crate) mod lrem;
+pub(crate) mod ltrim;
+pub(crate) mod lpos;
+pub(crate) mod lmove;
+pub(crate) mod rpoplpush;
+pub(crate) mod blpop;
+pub(crate) mod brpop;
+pub(crate) mod blmove;
+pub(crate) mod brpoplpush;
+pub(crate) mod lmpop;
+pub(crate) mod blmpop;
+pub(crate) mod hset;
+pub(crate) mod hsetnx;
+pub(crate) mod hget;
+pub(crate) mod hmget;
+pub(crate) mod hdel;
+pub(crate) mod hexists;
+pub(crate) mod hlen;
+pub(crate) mod hstrlen;
+pub(crate) mod hkeys;
+pub(crate) mod hvals;
+pub(crate) mod hgetall;
+pub(crate) mod hincrby;
+pub(crate) mod hincrbyfloat;
+pub(crate) mod hrandfield;
+pub(crate) mod hexpire;
+pub(crate) mod hpexpire;
+pub(crate) mod hexpireat;
+pub(crate) mod hpexpireat;
+pub(crate) mod httl;
+pub(crate) mod hpttl;
+pub(crate) mod hexpiretime;
+pub(crate) mod hpexpiretime;
+pub(crate) mod hpersist;
+pub(crate) mod hgetex;
+pub(crate) mod hsetex;
+pub(crate) mod hscan;
+pub(crate) mod sadd;
+pub(crate) mod srem;
+pub(crate) mod smembers;
+pub(crate) mod sismember;
+pub(crate) mod smismember;
+pub(crate) mod scard;
+pub(crate) mod spop;
+pub(crate) mod srandmember;
+pub(crate) mod smove;
+pub(crate) mod sinter;
+pub(crate) mod sunion;
+pub(crate) mod sdiff;
+pub(crate) mod sinterstore;
+pub(crate) mod sunionstore;
+pub(crate) mod sdiffstore;
+pub(crate) mod sintercard;
+pub(crate) mod sscan;
+pub(crate) mod zadd;
+pub(crate) mod zincrby;
+pub(crate) mod zrem;
+pub(crate) mod zscore;
+pub(crate) mod zmscore;
+pub(crate) mod zcard;
+pub(crate) mod zcount;
+pub(crate) mod zrank;
+pub(crate) mod zrevrank;
+pub(crate) mod zrange;
+pub(crate) mod zrangestore;
+pub(crate) mod zpopmin;
+pub(crate) mod zpopmax;
+pub(crate) mod zrandmember;
+pub(crate) mod zunion;
+pub(crate) mod zinter;
+pub(crate) mod zdiff;
+pub(crate) mod zunionstore;
+pub(crate) mod zinterstore;
+pub(crate) mod zdiffstore;
+pub(crate) mod zintercard;
+pub(crate) mod zmpop;
+pub(crate) mod bzmpop;
+pub(crate) mod bzpopmin;
+pub(crate) mod bzpopmax;
+pub(crate) mod zscan;
+pub(crate) mod xadd;
+pub(crate) mod xlen;
+
+/// Generates the `Commands` enum together with the functions that map each variant to its command.
+/// Adding a command to the server only requires adding it to the invocation below.
+macro_rules! command_table {
+	($($variant:ident($command:ident)),* $(,)?) => {
+		pub enum Commands {
+			$($variant($command),)*
+		}
+
+		impl Commands {
+			/// All commands known to the server, in the order of the command table.
+			pub fn all() -> impl Iterator<Item = Commands> {
+				[$(Commands::$variant($command {}),)*].into_iter()
+			}
+
+			pub fn spec(&self) -> &'static CommandSpec {
+				match self {
+					$(Commands::$variant(_) => &$command::SPEC,)*
+				}
+			}
 
-pub enum Commands {
+			async fn invoke(&self, server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+				match self {
+					$(Commands::$variant(_) => $command::invoke(server, connection, args).await,)*
+				}
+			}
+		}
+	};
+}
+
+command_table! {
 	Ping(CommandPing),
 	Echo(CommandEcho),
 	Set(CommandSet),
@@ -20,34 +188,509 @@ pub enum Commands {
 	Info(CommandInfo),
 	Replconf(CommandReplconf),
 	Psync(CommandPsync),
+	Hello(CommandHello),
+	Command(CommandCommand),
+	Expire(CommandExpire),
+	Pexpire(CommandPexpire),
+	Expireat(CommandExpireat),
+	Pexpireat(CommandPexpireat),
+	Ttl(CommandTtl),
+	Pttl(CommandPttl),
+	Expiretime(CommandExpiretime),
+	Pexpiretime(CommandPexpiretime),
+	Persist(CommandPersist),
+	Del(CommandDel),
+	Unlink(CommandUnlink),
+	Exists(CommandExists),
+	Type(CommandType),
+	Rename(CommandRename),
+	Renamenx(CommandRenamenx),
+	Copy(CommandCopy),
+	Touch(CommandTouch),
+	Randomkey(CommandRandomkey),
+	Select(CommandSelect),
+	Keys(CommandKeys),
+	Scan(CommandScan),
+	Object(CommandObject),
+	Incr(CommandIncr),
+	Decr(CommandDecr),
+	Incrby(CommandIncrby),
+	Decrby(CommandDecrby),
+	Incrbyfloat(CommandIncrbyfloat),
+	Append(CommandAppend),
+	Strlen(CommandStrlen),
+	Getrange(CommandGetrange),
+	Setrange(CommandSetrange),
+	Getdel(CommandGetdel),
+	Getex(CommandGetex),
+	Getset(CommandGetset),
+	Setnx(CommandSetnx),
+	Setex(CommandSetex),
+	Psetex(CommandPsetex),
+	Lcs(CommandLcs),
+	Mget(CommandMget),
+	Mset(CommandMset),
+	Msetnx(CommandMsetnx),
+	Lpush(CommandLpush),
+	Rpush(CommandRpush),
+	Lpushx(CommandLpushx),
+	Rpushx(CommandRpushx),
+	Lpop(CommandLpop),
+	Rpop(CommandRpop),
+	Lrange(CommandLrange),
+	Llen(CommandLlen),
+	Lindex(CommandLindex),
+	Lset(CommandLset),
+	Linsert(CommandLinsert),
+	Lrem(CommandLrem),
+	Ltrim(CommandLtrim),
+	Lpos(CommandLpos),
+	Lmove(CommandLmove),
+	Rpoplpush(CommandRpoplpush),
+	Blpop(CommandBlpop),
+	Brpop(CommandBrpop),
+	Blmove(CommandBlmove),
+	Brpoplpush(CommandBrpoplpush),
+	Lmpop(CommandLmpop),
+	Blmpop(CommandBlmpop),
+	Hset(CommandHset),
+	Hsetnx(CommandHsetnx),
+	Hget(CommandHget),
+	Hmget(CommandHmget),
+	Hdel(CommandHdel),
+	Hexists(CommandHexists),
+	Hlen(CommandHlen),
+	Hstrlen(CommandHstrlen),
+	Hkeys(CommandHkeys),
+	Hvals(CommandHvals),
+	Hgetall(CommandHgetall),
+	Hincrby(CommandHincrby),
+	Hincrbyfloat(CommandHincrbyfloat),
+	Hrandfield(CommandHrandfield),
+	Hexpire(CommandHexpire),
+	Hpexpire(CommandHpexpire),
+	Hexpireat(CommandHexpireat),
+	Hpexpireat(CommandHpexpireat),
+	Httl(CommandHttl),
+	Hpttl(CommandHpttl),
+	Hexpiretime(CommandHexpiretime),
+	Hpexpiretime(CommandHpexpiretime),
+	Hpersist(CommandHpersist),
+	Hgetex(CommandHgetex),
+	Hsetex(CommandHsetex),
+	Hscan(CommandHscan),
+	Sadd(CommandSadd),
+	Srem(CommandSrem),
+	Smembers(CommandSmembers),
+	Sismember(CommandSismember),
+	Smismember(CommandSmismember),
+	Scard(CommandScard),
+	Spop(CommandSpop),
+	Srandmember(CommandSrandmember),
+	Smove(CommandSmove),
+	Sinter(CommandSinter),
+	Sunion(CommandSunion),
+	Sdiff(CommandSdiff),
+	Sinterstore(CommandSinterstore),
+	Sunionstore(CommandSunionstore),
+	Sdiffstore(CommandSdiffstore),
+	Sintercard(CommandSintercard),
+	Sscan(CommandSscan),
+	Zadd(CommandZadd),
+	Zincrby(CommandZincrby),
+	Zrem(CommandZrem),
+	Zscore(CommandZscore),
+	Zmscore(CommandZmscore),
+	Zcard(CommandZcard),
+	Zcount(CommandZcount),
+	Zrank(CommandZrank),
+	Zrevrank(CommandZrevrank),
+	Zrange(CommandZrange),
+	Zrangestore(CommandZrangestore),
+	Zpopmin(CommandZpopmin),
+	Zpopmax(CommandZpopmax),
+	Zrandmember(CommandZrandmember),
+	Zunion(CommandZunion),
+	Zinter(CommandZinter),
+	Zdiff(CommandZdiff),
+	Zunionstore(CommandZunionstore),
+	Zinterstore(CommandZinterstore),
+	Zdiffstore(CommandZdiffstore),
+	Zintercard(CommandZintercard),
+	Zmpop(CommandZmpop),
+	Bzmpop(CommandBzmpop),
+	Bzpopmin(CommandBzpopmin),
+	Bzpopmax(CommandBzpopmax),
+	Zscan(CommandZscan),
+	Xadd(CommandXadd),
+	Xlen(CommandXlen),
 }
 
-pub trait Command {
-	async fn invoke(stream: &mut TcpStream, data: RespValues);
-}
-
-impl Command for Commands {
-	async fn invoke(stream: &mut TcpStream, data: RespValues) {
-		match &data {
-			RespValues::Array(a) => {
-				assert!(a.len() > 0);
-				match a.get(0).unwrap() {
-					RespValues::BulkString(b) => {
-						match b.as_str() {
-							"ping" => CommandPing::invoke(stream, data).await,
-							"echo" => CommandEcho::invoke(stream, data).await,
-							"set" => CommandSet::invoke(stream, data).await,
-							"get" => CommandGet::invoke(stream, data).await,
-							"info" => CommandInfo::invoke(stream, data).await,
-							"REPLCONF" => CommandReplconf::invoke(stream, data).await,
-							"PSYNC" => CommandPsync::invoke(stream, data).await,
-							command => panic!("Unknown command: '{command}'"),
-						}
-					},
-					_ => panic!("Unknown command structure: '{data}'")
+/// Flags describing how a command behaves, named like their Redis counterparts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandFlag {
+	Write,
+	Readonly,
+	DenyOom,
+	Admin,
+	Noscript,
+	Loading,
+	Stale,
+	Fast,
+	Blocking,
+	NoAuth,
+	AllowBusy,
+	NoMulti,
+}
+
+impl CommandFlag {
+	pub fn name(&self) -> &'static str {
+		match self {
+			CommandFlag::Write => "write",
+			CommandFlag::Readonly => "readonly",
+			CommandFlag::DenyOom => "denyoom",
+			CommandFlag::Admin => "admin",
+			CommandFlag::Noscript => "noscript",
+			CommandFlag::Loading => "loading",
+			CommandFlag::Stale => "stale",
+			CommandFlag::Fast => "fast",
+			CommandFlag::Blocking => "blocking",
+			CommandFlag::NoAuth => "no_auth",
+			CommandFlag::AllowBusy => "allow_busy",
+			CommandFlag::NoMulti => "no_multi",
+		}
+	}
+}
+
+/// ACL categories a command belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AclCategory {
+	Keyspace,
+	Read,
+	Write,
+	Set,
+	SortedSet,
+	List,
+	Hash,
+	Stream,
+	String,
+	Admin,
+	Fast,
+	Slow,
+	Blocking,
+	Dangerous,
+	Connection,
+}
+
+impl AclCategory {
+	pub const ALL: &'static [AclCategory] = &[
+		AclCategory::Keyspace, AclCategory::Read, AclCategory::Write, AclCategory::Set, AclCategory::SortedSet,
+		AclCategory::List, AclCategory::Hash, AclCategory::Stream, AclCategory::String, AclCategory::Admin,
+		AclCategory::Fast, AclCategory::Slow, AclCategory::Blocking, AclCategory::Dangerous, AclCategory::Connection,
+	];
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			AclCategory::Keyspace => "keyspace",
+			AclCategory::Read => "read",
+			AclCategory::Write => "write",
+			AclCategory::Set => "set",
+			AclCategory::SortedSet => "sortedset",
+			AclCategory::List => "list",
+			AclCategory::Hash => "hash",
+			AclCategory::Stream => "stream",
+			AclCategory::String => "string",
+			AclCategory::Admin => "admin",
+			AclCategory::Fast => "fast",
+			AclCategory::Slow => "slow",
+			AclCategory::Blocking => "blocking",
+			AclCategory::Dangerous => "dangerous",
+			AclCategory::Connection => "connection",
+		}
+	}
+}
+
+/// The group a command is listed under in the documentation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandGroup {
+	Generic,
+	String,
+	List,
+	Set,
+	SortedSet,
+	Hash,
+	Stream,
+	Connection,
+	Server,
+}
+
+impl CommandGroup {
+	pub fn name(&self) -> &'static str {
+		match self {
+			CommandGroup::Generic => "generic",
+			CommandGroup::String => "string",
+			CommandGroup::List => "list",
+			CommandGroup::Set => "set",
+			CommandGroup::SortedSet => "sorted-set",
+			CommandGroup::Hash => "hash",
+			CommandGroup::Stream => "stream",
+			CommandGroup::Connection => "connection",
+			CommandGroup::Server => "server",
+		}
+	}
+}
+
+/// Flags describing what a command does with a key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFlag {
+	/// Read only access to the value.
+	Ro,
+	/// Read and write access to the value.
+	Rw,
+	/// Overwrites the value without reading it.
+	Ow,
+	/// Deletes the key.
+	Rm,
+	Access,
+	Update,
+	Insert,
+	Delete,
+}
+
+impl KeyFlag {
+	pub fn name(&self) -> &'static str {
+		match self {
+			KeyFlag::Ro => "RO",
+			KeyFlag::Rw => "RW",
+			KeyFlag::Ow => "OW",
+			KeyFlag::Rm => "RM",
+			KeyFlag::Access => "access",
+			KeyFlag::Update => "update",
+			KeyFlag::Insert => "insert",
+			KeyFlag::Delete => "delete",
+		}
+	}
+}
+
+/// Describes where the keys of a command are located in its arguments.
+pub enum KeySpec {
+	/// Keys at `first`, `first + step`, ... up to `last`. A negative `last` counts from the end of the arguments.
+	Range { first: i64, last: i64, step: i64, flags: &'static [KeyFlag] },
+	/// The argument at `index` holds the number of keys. The first key is located `first` arguments after it,
+	/// the following ones are `step` arguments apart.
+	Keynum { index: i64, first: i64, step: i64, flags: &'static [KeyFlag] },
+}
+
+impl KeySpec {
+	pub fn flags(&self) -> &'static [KeyFlag] {
+		match self {
+			KeySpec::Range { flags, .. } => flags,
+			KeySpec::Keynum { flags, .. } => flags,
+		}
+	}
+
+	/// Returns the positions of the keys in `args` or `None` if `args` do not contain the announced keys.
+	pub fn key_positions(&self, args: &[Bytes]) -> Option<Vec<usize>> {
+		let len = args.len() as i64;
+		let (first, last, step) = match *self {
+			KeySpec::Range { first, last, step, .. } => {
+				let last = if last < 0 { len + last } else { last };
+				(first, last, step)
+			},
+			KeySpec::Keynum { index, first, step, .. } => {
+				let count = parse_integer(args.get(index as usize)?).ok()?;
+				if count < 0 || count > len {
+					return None;
 				}
+				let first = index + first;
+				(first, (count - 1).checked_mul(step)?.checked_add(first)?, step)
 			},
-			_ => panic!("Unknown command structure: '{data}'")
+		};
+		if last < first {
+			return Some(Vec::new());
 		}
+		if first < 1 || last >= len {
+			return None;
+		}
+		Some((first..=last).step_by(step.max(1) as usize).map(|i| i as usize).collect())
+	}
+}
+
+/// Static description of a command, used to validate and route requests before the command runs
+/// and to describe the command to clients in `COMMAND`.
+pub struct CommandSpec {
+	/// Lowercase command name. Subcommands are named `container|subcommand`.
+	pub name: &'static str,
+	pub summary: &'static str,
+	/// Redis version that introduced the command.
+	pub since: &'static str,
+	pub group: CommandGroup,
+	pub complexity: &'static str,
+	/// Number of arguments including the command name. A negative arity `-n` means at least `n` arguments.
+	pub arity: i64,
+	pub flags: &'static [CommandFlag],
+	pub keys: &'static [KeySpec],
+	pub acl_categories: &'static [AclCategory],
+	pub subcommands: &'static [CommandSpec],
+}
+
+impl CommandSpec {
+	/// Checks the number of arguments (including the command name) against the arity of the command.
+	pub fn accepts_argument_count(&self, count: usize) -> bool {
+		let count = count as i64;
+		if self.arity < 0 {
+			count >= -self.arity
+		} else {
+			count == self.arity
+		}
+	}
+
+	/// Whether the position of the keys depends on the arguments, i.e. can not be described by a key range.
+	pub fn has_movable_keys(&self) -> bool {
+		self.keys.iter().any(|k| matches!(k, KeySpec::Keynum { .. }))
+	}
+
+	/// First key, last key and step in the format that predates key specs.
+	/// Key ranges are merged into a single range, keys found through a key count are left out.
+	pub fn legacy_key_range(&self) -> (i64, i64, i64) {
+		let ranges = self.keys.iter().filter_map(|k| match *k {
+			KeySpec::Range { first, last, step, .. } => Some((first, last, step)),
+			KeySpec::Keynum { .. } => None,
+		}).collect::<Vec<_>>();
+		match ranges.as_slice() {
+			[] => (0, 0, 0),
+			[range] => *range,
+			ranges => {
+				let first = ranges.iter().map(|r| r.0).min().unwrap();
+				let last = match ranges.iter().find(|r| r.1 < 0) {
+					Some(r) => r.1,
+					None => ranges.iter().map(|r| r.1).max().unwrap(),
+				};
+				(first, last, 1)
+			},
+		}
+	}
+
+	/// Looks up a subcommand by name, ignoring case.
+	pub fn subcommand(&self, name: &[u8]) -> Option<&'static CommandSpec> {
+		let name = std::str::from_utf8(name).ok()?;
+		self.subcommands.iter()
+			.find(|s| s.name.split_once('|').is_some_and(|(_, sub)| sub.eq_ignore_ascii_case(name)))
+	}
+
+	/// Positions of all key arguments, or `None` if `args` are not valid for this command.
+	pub fn key_positions(&self, args: &[Bytes]) -> Option<Vec<usize>> {
+		if !self.accepts_argument_count(args.len()) {
+			return None;
+		}
+		let mut positions = Vec::new();
+		for spec in self.keys {
+			positions.extend(spec.key_positions(args)?);
+		}
+		Some(positions)
+	}
+}
+
+/// Errors a command replies with instead of its regular reply.
+/// The `Display` output is sent to the client as is, so each message starts with its Redis error code.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CommandError {
+	#[error("ERR unknown command '{name}', with args beginning with: {args}")]
+	UnknownCommand { name: String, args: String },
+	#[error("ERR wrong number of arguments for '{0}' command")]
+	WrongNumberOfArguments(String),
+	#[error("ERR unknown subcommand '{subcommand}'. Try {command} HELP.")]
+	UnknownSubcommand { subcommand: String, command: String },
+	#[error("ERR syntax error")]
+	Syntax,
+	#[error("WRONGTYPE Operation against a key holding the wrong kind of value")]
+	WrongType,
+	#[error("ERR value is not an integer or out of range")]
+	NotAnInteger,
+	#[error("ERR value is not a valid float")]
+	NotAFloat,
+	#[error("NOPROTO unsupported protocol version")]
+	NoProto,
+	#[error("WRONGPASS invalid username-password pair or user is disabled.")]
+	WrongPass,
+	#[error("ERR Protocol error: {0}")]
+	Protocol(String),
+	#[error("ERR {0}")]
+	Other(String),
+}
+
+impl CommandError {
+	pub fn unknown_command(args: &[Bytes]) -> CommandError {
+		let name = String::from_utf8_lossy(&args[0]).chars().take(128).collect();
+		let args = args[1..].iter()
+			.map(|a| format!("'{}' ", String::from_utf8_lossy(a).chars().take(128).collect::<String>()))
+			.collect();
+		CommandError::UnknownCommand { name, args }
+	}
+}
+
+pub trait Command {
+	const SPEC: CommandSpec;
+
+	/// `args` contains the command name followed by its arguments.
+	/// The number of arguments has already been checked against the arity in `SPEC`.
+	/// `server` holds the state shared with all other connections.
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError>;
+}
+
+static COMMAND_TABLE: OnceLock<HashMap<&'static str, Commands>> = OnceLock::new();
+
+impl Commands {
+	/// Looks up a command by name, ignoring case.
+	pub fn lookup(name: &[u8]) -> Option<&'static Commands> {
+		let table = COMMAND_TABLE.get_or_init(|| {
+			Commands::all().map(|c| (c.spec().name, c)).collect()
+		});
+		let name = std::str::from_utf8(name).ok()?.to_ascii_lowercase();
+		table.get(name.as_str())
 	}
-}
\ No newline at end of file
+
+	/// Runs the command described by `data` and replies with an error if the command fails.
+	pub async fn dispatch(server: &Server, connection: &mut Connection, data: RespValues) {
+		let result = match request_arguments(data) {
+			Ok(args) => Commands::execute(server, connection, args).await,
+			Err(e) => Err(e),
+		};
+		if let Err(e) = result {
+			error_reply(connection, e).await;
+		}
+	}
+
+	async fn execute(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let command = match Commands::lookup(&args[0]) {
+			Some(command) => command,
+			None => return Err(CommandError::unknown_command(&args)),
+		};
+		let mut spec = command.spec();
+		if !spec.subcommands.is_empty() && args.len() > 1 {
+			spec = match spec.subcommand(&args[1]) {
+				Some(subcommand) => subcommand,
+				None => return Err(CommandError::UnknownSubcommand {
+					subcommand: String::from_utf8_lossy(&args[1]).chars().take(128).collect(),
+					command: spec.name.to_uppercase(),
+				}),
+			};
+		}
+		if !spec.accepts_argument_count(args.len()) {
+			return Err(CommandError::WrongNumberOfArguments(spec.name.to_string()));
+		}
+		command.invoke(server, connection, args).await
+	}
+}
+
+/// Requests are sent as a non-empty array of bulk strings.
+fn request_arguments(data: RespValues) -> Result<Vec<Bytes>, CommandError> {
+	let values = match data {
+		RespValues::Array(a) if a.len() > 0 => a.into_inner(),
+		_ => return Err(CommandError::Protocol(String::from("expected a non-empty array of bulk strings"))),
+	};
+	values.into_iter().map(|v| match v {
+		RespValues::BulkString(b) => Ok(b.into_inner()),
+		_ => Err(CommandError::Protocol(String::from("expected a non-empty array of bulk strings"))),
+	}).collect()
+}
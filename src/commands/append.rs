@@ -0,0 +1,41 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{string::{check_string_length, RedisString}, Value}, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandAppend {}
+
+impl Command for CommandAppend {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "append",
+		summary: "Appends a string to the value of a key. Creates the key if it doesn't exist.",
+		since: "2.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1). The amortized time complexity is O(1) assuming the appended value is small and the already present value is of any size, since the dynamic string library used by Redis will double the free space available on every reallocation.",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let len = {
+			let mut store = server.store(connection.db());
+			let value = match store.get_string(&args[1])? {
+				Some(current) => {
+					check_string_length(current.len() + args[2].len())?;
+					[current, args[2].clone()].concat().into()
+				},
+				None => args[2].clone(),
+			};
+			let len = value.len();
+			// Strings that are appended to are likely to be appended to again, so they are not stored inline.
+			store.update(&args[1], Value::String(RedisString::raw(value)));
+			len
+		};
+		respond(connection, integer_response(len as i64)).await;
+		Ok(())
+	}
+}
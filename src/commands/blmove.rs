@@ -0,0 +1,29 @@
+use bytes::Bytes;
+
+use crate::{blocking::parse_timeout, connection::Connection, server::Server};
+
+use super::{lmove::{blocking_lmove_generic, parse_list_end}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBlmove {}
+
+impl Command for CommandBlmove {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "blmove",
+		summary: "Pops an element from a list, pushes it to another list and returns it. Blocks until an element is available otherwise. Deletes the list if the last element was moved.",
+		since: "6.2.0",
+		group: CommandGroup::List,
+		complexity: "O(1)",
+		arity: 6,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Blocking],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let from = parse_list_end(&args[3])?;
+		let to = parse_list_end(&args[4])?;
+		let timeout = parse_timeout(&args[5])?;
+		blocking_lmove_generic(server, connection, &args[1], &args[2], from, to, timeout).await
+	}
+}
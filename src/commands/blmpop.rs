@@ -0,0 +1,29 @@
+use bytes::Bytes;
+
+use crate::{blocking::{parse_timeout, serve_or_block}, connection::Connection, server::Server, util::null_array_response};
+
+use super::{lmove::parse_list_end, lmpop::{parse_mpop_arguments, pop_many}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBlmpop {}
+
+impl Command for CommandBlmpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "blmpop",
+		summary: "Pops the first element from one of multiple lists. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+		since: "7.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N+M) where N is the number of provided keys and M is the number of elements returned.",
+		arity: -5,
+		flags: &[CommandFlag::Write, CommandFlag::Blocking],
+		keys: &[KeySpec::Keynum { index: 2, first: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let timeout = parse_timeout(&args[1])?;
+		let (keys, direction, count) = parse_mpop_arguments(&args[2..])?;
+		let serve = pop_many(parse_list_end(direction)?, count, server.config().encoding_limits.list_max_listpack_size);
+		serve_or_block(server, connection, keys, timeout, null_array_response(), |value| value.as_list().is_ok(), serve).await
+	}
+}
@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::{blocking::{parse_timeout, serve_or_block}, connection::Connection, server::Server, store::Store, types::list::ListEnd, util::{array_response, bulk_string_response, null_array_response}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBlpop {}
+
+impl Command for CommandBlpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "blpop",
+		summary: "Removes and returns the first element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+		since: "2.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of provided keys.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Blocking],
+		keys: &[KeySpec::Range { first: 1, last: -2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		blocking_pop_generic(server, connection, args, ListEnd::Head).await
+	}
+}
+
+/// Implements `BLPOP` and `BRPOP`.
+pub async fn blocking_pop_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, end: ListEnd) -> Result<(), CommandError> {
+	let timeout = parse_timeout(&args[args.len() - 1])?;
+	let keys = args[1..args.len() - 1].to_vec();
+	let fill = server.config().encoding_limits.list_max_listpack_size;
+	let serve = Box::new(move |store: &mut Store, key: &Bytes| {
+		let element = store.get_mut(key)?.as_list_mut().ok()?.pop(end, fill)?;
+		store.delete_if_empty(key);
+		Some(Ok(array_response(vec![bulk_string_response(key.clone()), bulk_string_response(element)])))
+	});
+	serve_or_block(server, connection, keys, timeout, null_array_response(), |value| value.as_list().is_ok(), serve).await
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::list::ListEnd};
+
+use super::{blpop::blocking_pop_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBrpop {}
+
+impl Command for CommandBrpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "brpop",
+		summary: "Removes and returns the last element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+		since: "2.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of provided keys.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Blocking],
+		keys: &[KeySpec::Range { first: 1, last: -2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		blocking_pop_generic(server, connection, args, ListEnd::Tail).await
+	}
+}
@@ -0,0 +1,27 @@
+use bytes::Bytes;
+
+use crate::{blocking::parse_timeout, connection::Connection, server::Server, types::list::ListEnd};
+
+use super::{lmove::blocking_lmove_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBrpoplpush {}
+
+impl Command for CommandBrpoplpush {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "brpoplpush",
+		summary: "Pops an element from a list, pushes it to another list and returns it. Block until an element is available otherwise. Deletes the list if the last element was popped.",
+		since: "2.2.0",
+		group: CommandGroup::List,
+		complexity: "O(1)",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Blocking],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let timeout = parse_timeout(&args[3])?;
+		blocking_lmove_generic(server, connection, &args[1], &args[2], ListEnd::Tail, ListEnd::Head, timeout).await
+	}
+}
@@ -0,0 +1,29 @@
+use bytes::Bytes;
+
+use crate::{blocking::{parse_timeout, serve_or_block}, connection::Connection, server::Server, util::null_array_response};
+
+use super::{lmpop::parse_mpop_arguments, zmpop::{parse_min_max, pop_many}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBzmpop {}
+
+impl Command for CommandBzmpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "bzmpop",
+		summary: "Removes and returns a member by score from one or more sorted sets. Blocks until a member is available otherwise. Deletes the sorted set if the last element was popped.",
+		since: "7.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(K) + O(M*log(N)) where K is the number of provided keys, N being the number of elements in the sorted set, and M being the number of elements popped.",
+		arity: -5,
+		flags: &[CommandFlag::Write, CommandFlag::Blocking],
+		keys: &[KeySpec::Keynum { index: 2, first: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Slow, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let timeout = parse_timeout(&args[1])?;
+		let (keys, direction, count) = parse_mpop_arguments(&args[2..])?;
+		let serve = pop_many(parse_min_max(direction)?, count);
+		serve_or_block(server, connection, keys, timeout, null_array_response(), |value| value.as_sorted_set().is_ok(), serve).await
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{bzpopmin::blocking_pop_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBzpopmax {}
+
+impl Command for CommandBzpopmax {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "bzpopmax",
+		summary: "Removes and returns the member with the highest score from one or more sorted sets. Blocks until a member is available otherwise. Deletes the sorted set if the last element was popped.",
+		since: "5.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)) with N being the number of elements in the sorted set.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Blocking, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: -2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Fast, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		blocking_pop_generic(server, connection, args, true).await
+	}
+}
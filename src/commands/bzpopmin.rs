@@ -0,0 +1,38 @@
+use bytes::Bytes;
+
+use crate::{blocking::{parse_timeout, serve_or_block}, connection::Connection, server::Server, store::Store, util::{array_response, bulk_string_response, double_response, null_array_response}};
+
+use super::{zpopmin::pop_members, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandBzpopmin {}
+
+impl Command for CommandBzpopmin {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "bzpopmin",
+		summary: "Removes and returns the member with the lowest score from one or more sorted sets. Blocks until a member is available otherwise. Deletes the sorted set if the last element was popped.",
+		since: "5.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)) with N being the number of elements in the sorted set.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Blocking, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: -2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Fast, AclCategory::Blocking],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		blocking_pop_generic(server, connection, args, false).await
+	}
+}
+
+/// Implements `BZPOPMIN` and, popping the highest score, `BZPOPMAX`.
+pub async fn blocking_pop_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, max: bool) -> Result<(), CommandError> {
+	let timeout = parse_timeout(&args[args.len() - 1])?;
+	let keys = args[1..args.len() - 1].to_vec();
+	let serve = Box::new(move |store: &mut Store, key: &Bytes| {
+		store.get(key)?.as_sorted_set().ok()?;
+		let (member, score) = pop_members(store, key, 1, max).ok()?.pop()?;
+		Some(Ok(array_response(vec![bulk_string_response(key.clone()), bulk_string_response(member), double_response(score)])))
+	});
+	serve_or_block(server, connection, keys, timeout, null_array_response(), |value| value.as_sorted_set().is_ok(), serve).await
+}
@@ -0,0 +1,336 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, glob::glob_match, resp::RespValues, server::Server, util::{array_response, bulk_string_response, integer_response, map_response, null_response, respond, set_response, simple_string_response}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, Commands, KeySpec};
+
+pub struct CommandCommand {}
+
+impl Command for CommandCommand {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "command",
+		summary: "Returns detailed information about all commands.",
+		since: "2.8.13",
+		group: CommandGroup::Server,
+		complexity: "O(N) where N is the total number of Redis commands",
+		arity: -1,
+		flags: &[CommandFlag::Loading, CommandFlag::Stale],
+		keys: &[],
+		acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+		subcommands: &[
+			CommandSpec {
+				name: "command|count",
+				summary: "Returns a count of commands.",
+				since: "2.8.13",
+				group: CommandGroup::Server,
+				complexity: "O(1)",
+				arity: 2,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+				subcommands: &[],
+			},
+			CommandSpec {
+				name: "command|docs",
+				summary: "Returns documentary information about one, multiple or all commands.",
+				since: "7.0.0",
+				group: CommandGroup::Server,
+				complexity: "O(N) where N is the number of commands to look up",
+				arity: -2,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+				subcommands: &[],
+			},
+			CommandSpec {
+				name: "command|getkeys",
+				summary: "Extracts the key names from an arbitrary command.",
+				since: "2.8.13",
+				group: CommandGroup::Server,
+				complexity: "O(N) where N is the number of arguments to the command",
+				arity: -3,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+				subcommands: &[],
+			},
+			CommandSpec {
+				name: "command|getkeysandflags",
+				summary: "Extracts the key names and access flags for an arbitrary command.",
+				since: "7.0.0",
+				group: CommandGroup::Server,
+				complexity: "O(N) where N is the number of arguments to the command",
+				arity: -3,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+				subcommands: &[],
+			},
+			CommandSpec {
+				name: "command|help",
+				summary: "Returns helpful text about the different subcommands.",
+				since: "5.0.0",
+				group: CommandGroup::Server,
+				complexity: "O(1)",
+				arity: 2,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+				subcommands: &[],
+			},
+			CommandSpec {
+				name: "command|info",
+				summary: "Returns information about one, multiple or all commands.",
+				since: "2.8.13",
+				group: CommandGroup::Server,
+				complexity: "O(N) where N is the number of commands to look up",
+				arity: -2,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+				subcommands: &[],
+			},
+			CommandSpec {
+				name: "command|list",
+				summary: "Returns a list of command names.",
+				since: "7.0.0",
+				group: CommandGroup::Server,
+				complexity: "O(N) where N is the total number of Redis commands",
+				arity: -2,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Slow, AclCategory::Connection],
+				subcommands: &[],
+			},
+		],
+	};
+
+	async fn invoke(_server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		if args.len() == 1 {
+			let response = array_response(Commands::all().map(|c| command_info(c.spec())).collect());
+			respond(connection, response).await;
+			return Ok(());
+		}
+
+		let response = match args[1].to_ascii_lowercase().as_slice() {
+			b"count" => integer_response(Commands::all().count() as i64),
+			b"info" => {
+				if args.len() == 2 {
+					array_response(Commands::all().map(|c| command_info(c.spec())).collect())
+				} else {
+					array_response(args[2..].iter().map(|name| match lookup_spec(name) {
+						Some(spec) => command_info(spec),
+						None => null_response(),
+					}).collect())
+				}
+			},
+			b"docs" => {
+				let specs: Vec<&'static CommandSpec> = if args.len() == 2 {
+					Commands::all().map(|c| c.spec()).collect()
+				} else {
+					args[2..].iter().filter_map(|name| lookup_spec(name)).collect()
+				};
+				map_response(specs.into_iter().map(|spec| (bulk_string_response(spec.name), command_docs(spec))).collect())
+			},
+			b"getkeys" => {
+				let (_, positions) = key_positions(&args[2..])?;
+				array_response(positions.into_iter().map(|i| bulk_string_response(args[2 + i].clone())).collect())
+			},
+			b"getkeysandflags" => {
+				let (spec, _) = key_positions(&args[2..])?;
+				let mut responses = Vec::new();
+				for key_spec in spec.keys {
+					let flags = set_response(key_spec.flags().iter().map(|f| simple_string_response(f.name())).collect());
+					for i in key_spec.key_positions(&args[2..]).unwrap_or_default() {
+						responses.push(array_response(vec![bulk_string_response(args[2 + i].clone()), flags.clone()]));
+					}
+				}
+				array_response(responses)
+			},
+			b"list" => array_response(list_commands(&args[2..])?.into_iter().map(bulk_string_response).collect()),
+			b"help" => array_response(HELP.iter().map(|line| simple_string_response(line)).collect()),
+			_ => unreachable!("subcommands are validated before the command is invoked"),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
+
+const HELP: &[&str] = &[
+	"COMMAND <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+	"(no subcommand)",
+	"    Return details about all Redis commands.",
+	"COUNT",
+	"    Return the total number of commands in this Redis server.",
+	"LIST",
+	"    Return a list of all commands in this Redis server.",
+	"INFO [<command-name> ...]",
+	"    Return details about multiple Redis commands.",
+	"    If no command names are given, documentation details for all",
+	"    commands are returned.",
+	"DOCS [<command-name> ...]",
+	"    Return documentation details about multiple Redis commands.",
+	"    If no command names are given, documentation details for all",
+	"    commands are returned.",
+	"GETKEYS <full-command>",
+	"    Return the keys from a full Redis command.",
+	"GETKEYSANDFLAGS <full-command>",
+	"    Return the keys and the access flags from a full Redis command.",
+	"LIST [FILTERBY (MODULE <module-name>|ACLCAT <category>|PATTERN <pattern>)]",
+	"    Return a list of all commands in this Redis server.",
+	"HELP",
+	"    Print this help.",
+];
+
+/// Looks up a command or a subcommand given as `container|subcommand`.
+fn lookup_spec(name: &[u8]) -> Option<&'static CommandSpec> {
+	match name.iter().position(|c| *c == b'|') {
+		Some(i) => Commands::lookup(&name[..i])?.spec().subcommand(&name[i + 1..]),
+		None => Commands::lookup(name).map(|c| c.spec()),
+	}
+}
+
+/// The command and key positions for `GETKEYS`, positions are relative to `command_args`.
+fn key_positions(command_args: &[Bytes]) -> Result<(&'static CommandSpec, Vec<usize>), CommandError> {
+	let mut spec = match Commands::lookup(&command_args[0]) {
+		Some(command) => command.spec(),
+		None => return Err(CommandError::Other(String::from("Invalid command specified"))),
+	};
+	if !spec.subcommands.is_empty() && command_args.len() > 1 {
+		spec = spec.subcommand(&command_args[1]).ok_or(CommandError::Other(String::from("Invalid command specified")))?;
+	}
+	if spec.keys.is_empty() {
+		return Err(CommandError::Other(String::from("The command has no key arguments")));
+	}
+	if !spec.accepts_argument_count(command_args.len()) {
+		return Err(CommandError::Other(String::from("Invalid number of arguments specified for command")));
+	}
+	match spec.key_positions(command_args) {
+		Some(positions) if !positions.is_empty() => Ok((spec, positions)),
+		_ => Err(CommandError::Other(String::from("Invalid arguments specified for command"))),
+	}
+}
+
+fn list_commands(filter: &[Bytes]) -> Result<Vec<String>, CommandError> {
+	let names = Commands::all()
+		.flat_map(|c| std::iter::once(c.spec()).chain(c.spec().subcommands.iter()))
+		.map(|spec| spec.name);
+	match filter {
+		[] => Ok(names.map(String::from).collect()),
+		[filterby, kind, value] if filterby.eq_ignore_ascii_case(b"filterby") => {
+			match kind.to_ascii_lowercase().as_slice() {
+				// Modules are not supported, so no command belongs to one.
+				b"module" => Ok(Vec::new()),
+				b"aclcat" => {
+					let category = AclCategory::ALL.iter().find(|c| c.name().as_bytes().eq_ignore_ascii_case(value));
+					Ok(names.filter(|name| {
+						let spec = lookup_spec(name.as_bytes()).unwrap();
+						category.is_some_and(|c| spec.acl_categories.contains(c))
+					}).map(String::from).collect())
+				},
+				b"pattern" => Ok(names.filter(|name| glob_match(value, name.as_bytes(), true)).map(String::from).collect()),
+				_ => Err(CommandError::Syntax),
+			}
+		},
+		_ => Err(CommandError::Syntax),
+	}
+}
+
+fn command_info(spec: &'static CommandSpec) -> RespValues {
+	let mut flags: Vec<RespValues> = spec.flags.iter().map(|f| simple_string_response(f.name())).collect();
+	if spec.has_movable_keys() {
+		flags.push(simple_string_response("movablekeys"));
+	}
+	let (first_key, last_key, key_step) = spec.legacy_key_range();
+	let acl_categories = spec.acl_categories.iter().map(|c| simple_string_response(&format!("@{}", c.name()))).collect();
+
+	array_response(vec![
+		bulk_string_response(spec.name),
+		integer_response(spec.arity),
+		set_response(flags),
+		integer_response(first_key),
+		integer_response(last_key),
+		integer_response(key_step),
+		set_response(acl_categories),
+		// Tips for clients of a cluster, which are not relevant for a standalone server.
+		set_response(Vec::new()),
+		array_response(spec.keys.iter().map(key_spec_info).collect()),
+		array_response(spec.subcommands.iter().map(command_info).collect()),
+	])
+}
+
+fn key_spec_info(key_spec: &KeySpec) -> RespValues {
+	let flags = set_response(key_spec.flags().iter().map(|f| simple_string_response(f.name())).collect());
+	let (index, find_keys) = match *key_spec {
+		KeySpec::Range { first, last, step, .. } => {
+			let last_key = if last < 0 { last } else { last - first };
+			(first, map_response(vec![
+				(bulk_string_response("type"), bulk_string_response("range")),
+				(bulk_string_response("spec"), map_response(vec![
+					(bulk_string_response("lastkey"), integer_response(last_key)),
+					(bulk_string_response("keystep"), integer_response(step)),
+					(bulk_string_response("limit"), integer_response(0)),
+				])),
+			]))
+		},
+		KeySpec::Keynum { index, first, step, .. } => {
+			(index, map_response(vec![
+				(bulk_string_response("type"), bulk_string_response("keynum")),
+				(bulk_string_response("spec"), map_response(vec![
+					(bulk_string_response("keynumidx"), integer_response(0)),
+					(bulk_string_response("firstkey"), integer_response(first)),
+					(bulk_string_response("keystep"), integer_response(step)),
+				])),
+			]))
+		},
+	};
+	map_response(vec![
+		(bulk_string_response("flags"), flags),
+		(bulk_string_response("begin_search"), map_response(vec![
+			(bulk_string_response("type"), bulk_string_response("index")),
+			(bulk_string_response("spec"), map_response(vec![
+				(bulk_string_response("index"), integer_response(index)),
+			])),
+		])),
+		(bulk_string_response("find_keys"), find_keys),
+	])
+}
+
+fn command_docs(spec: &'static CommandSpec) -> RespValues {
+	let mut docs = vec![
+		(bulk_string_response("summary"), bulk_string_response(spec.summary)),
+		(bulk_string_response("since"), bulk_string_response(spec.since)),
+		(bulk_string_response("group"), bulk_string_response(spec.group.name())),
+		(bulk_string_response("complexity"), bulk_string_response(spec.complexity)),
+	];
+	if !spec.subcommands.is_empty() {
+		let subcommands = spec.subcommands.iter().map(|s| (bulk_string_response(s.name), command_docs(s))).collect();
+		docs.push((bulk_string_response("subcommands"), map_response(subcommands)));
+	}
+	map_response(docs)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::test_util::bytes;
+
+	use super::*;
+
+	#[test]
+	fn finds_keys_announced_by_numkeys() {
+		let (_, positions) = key_positions(&bytes(&["zunion", "2", "a", "b", "WEIGHTS", "1", "2"])).unwrap();
+		assert_eq!(positions, vec![2, 3]);
+	}
+
+	#[test]
+	fn rejects_numkeys_beyond_the_arguments() {
+		assert!(key_positions(&bytes(&["zunion", "9223372036854775807", "a"])).is_err());
+		assert!(key_positions(&bytes(&["zunion", "3", "a", "b"])).is_err());
+	}
+
+	#[test]
+	fn rejects_negative_numkeys() {
+		assert!(key_positions(&bytes(&["zunion", "-1", "a"])).is_err());
+		assert!(key_positions(&bytes(&["zunion", "-9223372036854775808", "a"])).is_err());
+	}
+}
@@ -0,0 +1,60 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandCopy {}
+
+impl Command for CommandCopy {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "copy",
+		summary: "Copies the value of a key to a new key.",
+		since: "6.2.0",
+		group: CommandGroup::Generic,
+		complexity: "O(N) worst case for collections, where N is the number of nested items. O(1) for string values.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Write, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let mut destination_db = connection.db();
+		let mut replace = false;
+		let mut i = 3;
+		while i < args.len() {
+			match args[i].to_ascii_uppercase().as_slice() {
+				b"REPLACE" => replace = true,
+				b"DB" if i + 1 < args.len() => {
+					let index = i32::try_from(parse_integer(&args[i + 1])?).map_err(|_| CommandError::NotAnInteger)?;
+					destination_db = server.db_index(index as i64).ok_or_else(|| CommandError::Other(String::from("DB index is out of range")))?;
+					i += 1;
+				},
+				_ => return Err(CommandError::Syntax),
+			}
+			i += 1;
+		}
+		if args[1] == args[2] && destination_db == connection.db() {
+			return Err(CommandError::Other(String::from("source and destination objects are the same")));
+		}
+
+		// Only one database is locked at a time, the value is copied out of the source first.
+		let value = server.store(connection.db()).entry(&args[1]).cloned();
+		let copied = match value {
+			Some(value) => {
+				let mut store = server.store(destination_db);
+				if !replace && store.has(&args[2]) {
+					false
+				} else {
+					store.insert(args[2].clone(), value);
+					true
+				}
+			},
+			None => false,
+		};
+		respond(connection, integer_response(copied as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{incr::incr_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandDecr {}
+
+impl Command for CommandDecr {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "decr",
+		summary: "Decrements the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		incr_generic(server, connection, &args[1], -1).await
+	}
+}
@@ -0,0 +1,29 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::parse_integer};
+
+use super::{incr::incr_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandDecrby {}
+
+impl Command for CommandDecrby {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "decrby",
+		summary: "Decrements a number from the integer value of a key. Uses 0 as initial value if the key doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let decrement = parse_integer(&args[2])?;
+		// The negation of the smallest integer does not fit.
+		let increment = decrement.checked_neg().ok_or_else(|| CommandError::Other(String::from("decrement would overflow")))?;
+		incr_generic(server, connection, &args[1], increment).await
+	}
+}
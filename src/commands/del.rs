@@ -0,0 +1,49 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::LAZYFREE_THRESHOLD, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandDel {}
+
+impl Command for CommandDel {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "del",
+		summary: "Deletes one or more keys.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(N) where N is the number of keys that will be removed. When a key to remove holds a value other than a string, the individual complexity for this key is O(M) where M is the number of elements in the list, set, sorted set or hash. Removing a single key that holds a string value is O(1).",
+		arity: -2,
+		flags: &[CommandFlag::Write],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 1, flags: &[KeyFlag::Rm, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Write, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		del_generic(server, connection, &args[1..], false).await
+	}
+}
+
+/// Implements `DEL` and `UNLINK`.
+/// With `lazy` set, values that are expensive to free are freed in the background instead of by the caller.
+pub async fn del_generic(server: &Server, connection: &mut Connection, keys: &[Bytes], lazy: bool) -> Result<(), CommandError> {
+	let mut deleted = 0;
+	let mut lazy_free = Vec::new();
+	{
+		let mut store = server.store(connection.db());
+		for key in keys {
+			if let Some(value) = store.remove(key) {
+				deleted += 1;
+				if lazy && value.value().free_effort() > LAZYFREE_THRESHOLD {
+					lazy_free.push(value);
+				}
+			}
+		}
+	}
+	if !lazy_free.is_empty() {
+		tokio::task::spawn_blocking(move || drop(lazy_free));
+	}
+	respond(connection, integer_response(deleted)).await;
+	Ok(())
+}
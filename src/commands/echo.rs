@@ -1,20 +1,27 @@
-use tokio::net::TcpStream;
+use bytes::Bytes;
 
-use crate::{resp::RespValues, util::respond};
+use crate::{connection::Connection, resp::{bulk_string::RespBulkString, RespValues}, server::Server, util::respond};
 
-use super::Command;
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
 
 pub struct CommandEcho {}
 
 impl Command for CommandEcho {
-	async fn invoke(stream: &mut TcpStream, data: RespValues) {
-		match data {
-			RespValues::Array(a) => {
-				assert!(a.len() == 2);
-				let arg = a.get(1).unwrap().clone();
-				respond(stream, arg).await;
-			},
-			_ => eprintln!("Misformed echo command: '{}'", data)
-		}
+	const SPEC: CommandSpec = CommandSpec {
+		name: "echo",
+		summary: "Returns the given string.",
+		since: "1.0.0",
+		group: CommandGroup::Connection,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Fast],
+		keys: &[],
+		acl_categories: &[AclCategory::Fast, AclCategory::Connection],
+		subcommands: &[],
+	};
+
+	async fn invoke(_server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		respond(connection, RespValues::BulkString(RespBulkString::from_raw(args[1].clone()))).await;
+		Ok(())
 	}
-}
\ No newline at end of file
+}
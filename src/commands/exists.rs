@@ -0,0 +1,32 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandExists {}
+
+impl Command for CommandExists {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "exists",
+		summary: "Determines whether one or more keys exist.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(N) where N is the number of keys to check.",
+		arity: -2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		// Keys that are given multiple times are counted multiple times.
+		let count = {
+			let mut store = server.store(connection.db());
+			args[1..].iter().filter(|key| store.has(key)).count()
+		};
+		respond(connection, integer_response(count as i64)).await;
+		Ok(())
+	}
+}
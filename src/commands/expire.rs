@@ -0,0 +1,105 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, parse_integer, respond, unix_time_ms}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandExpire {}
+
+impl Command for CommandExpire {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "expire",
+		summary: "Sets the expiration time of a key in seconds.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Write, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		expire_generic(server, connection, args, ExpireUnit::Seconds, true).await
+	}
+}
+
+#[derive(Clone, Copy)]
+pub enum ExpireUnit {
+	Seconds,
+	Milliseconds,
+}
+
+/// Conditions on the current expiry of the key that have to hold for the expiry to be set.
+#[derive(Default)]
+pub struct ExpireConditions {
+	/// The key has no expiry.
+	nx: bool,
+	/// The key has an expiry.
+	xx: bool,
+	/// The new expiry is later. A key without an expiry counts as never expiring.
+	gt: bool,
+	/// The new expiry is earlier.
+	lt: bool,
+}
+
+impl ExpireConditions {
+	pub fn allow(&self, current: Option<i64>, when: i64) -> bool {
+		match current {
+			Some(current) => !self.nx && (!self.gt || when > current) && (!self.lt || when < current),
+			None => !self.xx && !self.gt,
+		}
+	}
+}
+
+/// Implements `EXPIRE`, `PEXPIRE`, `EXPIREAT` and `PEXPIREAT`.
+/// The time is relative to now if `relative` is set, a Unix timestamp otherwise.
+pub async fn expire_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, unit: ExpireUnit, relative: bool) -> Result<(), CommandError> {
+	let name = String::from_utf8_lossy(&args[0]).to_lowercase();
+	let invalid_expire_time = || CommandError::Other(format!("invalid expire time in '{name}' command"));
+
+	let conditions = parse_conditions(&args[3..])?;
+	let mut when = parse_integer(&args[2])?;
+
+	if let ExpireUnit::Seconds = unit {
+		when = when.checked_mul(1000).ok_or_else(invalid_expire_time)?;
+	}
+	if relative {
+		when = when.checked_add(unix_time_ms()).ok_or_else(invalid_expire_time)?;
+	}
+
+	let updated = {
+		let mut store = server.store(connection.db());
+		let applies = store.has(&args[1]) && conditions.allow(store.expires_at(&args[1]), when);
+		// An expiry in the past deletes the key right away.
+		if applies && when <= unix_time_ms() {
+			store.delete(&args[1]);
+		} else if applies {
+			store.set_expires_at(&args[1], Some(when));
+		}
+		applies
+	};
+	respond(connection, integer_response(updated as i64)).await;
+	Ok(())
+}
+
+pub fn parse_conditions(options: &[Bytes]) -> Result<ExpireConditions, CommandError> {
+	let mut conditions = ExpireConditions::default();
+	for option in options {
+		match option.to_ascii_uppercase().as_slice() {
+			b"NX" => conditions.nx = true,
+			b"XX" => conditions.xx = true,
+			b"GT" => conditions.gt = true,
+			b"LT" => conditions.lt = true,
+			_ => return Err(CommandError::Other(format!("Unsupported option {}", String::from_utf8_lossy(option)))),
+		}
+	}
+	if conditions.nx && (conditions.xx || conditions.gt || conditions.lt) {
+		return Err(CommandError::Other(String::from("NX and XX, GT or LT options at the same time are not compatible")));
+	}
+	if conditions.gt && conditions.lt {
+		return Err(CommandError::Other(String::from("GT and LT options at the same time are not compatible")));
+	}
+	Ok(conditions)
+}
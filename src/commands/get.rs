@@ -1,31 +1,33 @@
-use tokio::net::TcpStream;
+use bytes::Bytes;
 
-use crate::{resp::{bulk_string::RespBulkString, RespValues}, store::global_store, util::{null_reply, respond}};
+use crate::{connection::Connection, resp::{bulk_string::RespBulkString, RespValues}, server::Server, util::{null_reply, respond}};
 
-use super::Command;
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
 
 pub struct CommandGet {}
 
 impl Command for CommandGet {
-	async fn invoke(stream: &mut TcpStream, data: RespValues) {
-		match data {
-			RespValues::Array(a) => {
-				assert!(a.len() == 2);
-				let key = match a.get(1).unwrap() {
-					RespValues::BulkString(b) => {
-						b.as_str().to_string()
-					},
-					d => panic!("set: Expected BulkString for key argument, got: '{}'", d),
-				};
+	const SPEC: CommandSpec = CommandSpec {
+		name: "get",
+		summary: "Returns the string value of a key.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
 
-				if let Some(value) = global_store().get(key) {
-					let response = RespValues::BulkString(RespBulkString::from_raw(value.clone().into_bytes()));
-					respond(stream, response).await;
-				} else {
-					null_reply(stream).await;
-				}
-			},
-			_ => eprintln!("Misformed get command: '{}'", data)
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let value = server.store(connection.db()).get_string(&args[1])?;
+		if let Some(value) = value {
+			let response = RespValues::BulkString(RespBulkString::from_raw(value));
+			respond(connection, response).await;
+		} else {
+			null_reply(connection).await;
 		}
+		Ok(())
 	}
-}
\ No newline at end of file
+}
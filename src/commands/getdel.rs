@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandGetdel {}
+
+impl Command for CommandGetdel {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "getdel",
+		summary: "Returns the string value of a key after deleting the key.",
+		since: "6.2.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let value = {
+			let mut store = server.store(connection.db());
+			let value = store.get_string(&args[1])?;
+			if value.is_some() {
+				store.delete(&args[1]);
+			}
+			value
+		};
+		let response = match value {
+			Some(value) => bulk_string_response(value),
+			None => null_response(),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,63 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_response, respond, unix_time_ms}};
+
+use super::{set::expire_time, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandGetex {}
+
+impl Command for CommandGetex {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "getex",
+		summary: "Returns the string value of a key after setting its expiration time.",
+		since: "6.2.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: -2,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		// `None` leaves the expiry alone, `Some(None)` removes it.
+		let mut expiry: Option<Option<i64>> = None;
+		let mut i = 2;
+		while i < args.len() {
+			let option = args[i].to_ascii_uppercase();
+			match option.as_slice() {
+				b"EX" | b"PX" | b"EXAT" | b"PXAT" if expiry.is_none() && i + 1 < args.len() => {
+					expiry = Some(Some(expire_time(&option, &args[i + 1], "getex")?));
+					i += 1;
+				},
+				b"PERSIST" if expiry.is_none() => expiry = Some(None),
+				_ => return Err(CommandError::Syntax),
+			}
+			i += 1;
+		}
+
+		let value = {
+			let mut store = server.store(connection.db());
+			let value = store.get_string(&args[1])?;
+			match expiry {
+				_ if value.is_none() => {},
+				// An expiry in the past deletes the key right away.
+				Some(Some(when)) if when <= unix_time_ms() => {
+					store.delete(&args[1]);
+				},
+				Some(when) => {
+					store.set_expires_at(&args[1], when);
+				},
+				None => {},
+			}
+			value
+		};
+		let response = match value {
+			Some(value) => bulk_string_response(value),
+			None => null_response(),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
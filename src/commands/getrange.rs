@@ -0,0 +1,44 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandGetrange {}
+
+impl Command for CommandGetrange {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "getrange",
+		summary: "Returns a substring of the string stored at a key.",
+		since: "2.4.0",
+		group: CommandGroup::String,
+		complexity: "O(N) where N is the length of the returned string. The complexity is ultimately determined by the returned length, but because creating a substring from an existing string is very cheap, it can be considered O(1) for small strings.",
+		arity: 4,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let start = parse_integer(&args[2])?;
+		let end = parse_integer(&args[3])?;
+		let value = server.store(connection.db()).get_string(&args[1])?.unwrap_or_default();
+		respond(connection, bulk_string_response(substring(&value, start, end))).await;
+		Ok(())
+	}
+}
+
+/// The bytes from `start` to `end` inclusive. Negative indexes count from the end, indexes out of range are clamped.
+fn substring(value: &Bytes, start: i64, end: i64) -> Bytes {
+	let len = value.len() as i64;
+	if start < 0 && end < 0 && start > end {
+		return Bytes::new();
+	}
+	let start = if start < 0 { len + start } else { start }.max(0);
+	let end = if end < 0 { len + end } else { end }.max(0).min(len - 1);
+	if start > end || len == 0 {
+		return Bytes::new();
+	}
+	value.slice(start as usize..=end as usize)
+}
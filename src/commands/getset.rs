@@ -0,0 +1,37 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandGetset {}
+
+impl Command for CommandGetset {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "getset",
+		summary: "Returns the previous string value of a key after setting it to a new value.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let old_value = {
+			let mut store = server.store(connection.db());
+			let old_value = store.get_string(&args[1])?;
+			store.set(args[1].clone(), args[2].clone(), None);
+			old_value
+		};
+		let response = match old_value {
+			Some(value) => bulk_string_response(value),
+			None => null_response(),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHdel {}
+
+impl Command for CommandHdel {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hdel",
+		summary: "Deletes one or more fields and their values from a hash. Deletes the hash if no fields remain.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of fields to be removed.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let deleted = {
+			let mut store = server.store(connection.db());
+			match store.get_mut(&args[1]) {
+				Some(value) => {
+					let hash = value.as_hash_mut()?;
+					let deleted = args[2..].iter().filter(|field| hash.remove(field)).count();
+					store.delete_if_empty(&args[1]);
+					deleted
+				},
+				None => 0,
+			}
+		};
+		respond(connection, integer_response(deleted as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,81 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, resp::RespProtocol, server::Server, util::{array_response, bulk_string_response, integer_response, map_response, respond}, REDIS_VERSION};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
+
+pub struct CommandHello {}
+
+impl Command for CommandHello {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hello",
+		summary: "Handshakes with the Redis server.",
+		since: "6.0.0",
+		group: CommandGroup::Connection,
+		complexity: "O(1)",
+		arity: -1,
+		flags: &[CommandFlag::Noscript, CommandFlag::Loading, CommandFlag::Stale, CommandFlag::Fast, CommandFlag::NoAuth, CommandFlag::AllowBusy],
+		keys: &[],
+		acl_categories: &[AclCategory::Fast, AclCategory::Connection],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let mut protocol = connection.protocol();
+		let mut name = None;
+
+		if args.len() > 1 {
+			protocol = match String::from_utf8_lossy(&args[1]).parse::<i64>() {
+				Ok(2) => RespProtocol::Resp2,
+				Ok(3) => RespProtocol::Resp3,
+				Ok(_) => return Err(CommandError::NoProto),
+				Err(_) => return Err(CommandError::Other(String::from("Protocol version is not an integer or out of range"))),
+			};
+		}
+
+		let mut i = 2;
+		while i < args.len() {
+			let remaining = args.len() - i - 1;
+			match args[i].to_ascii_lowercase().as_slice() {
+				b"auth" if remaining >= 2 => {
+					// There is no ACL support, so only the default user exists and it does not require a password.
+					if args[i + 1].as_ref() != b"default" {
+						return Err(CommandError::WrongPass);
+					}
+					i += 3;
+				},
+				b"setname" if remaining >= 1 => {
+					let client_name = args[i + 1].clone();
+					if client_name.iter().any(|c| !(b'!'..=b'~').contains(c)) {
+						return Err(CommandError::Other(String::from("Client names cannot contain spaces, newlines or special characters.")));
+					}
+					name = Some(client_name);
+					i += 2;
+				},
+				_ => return Err(CommandError::Other(format!("Syntax error in HELLO option '{}'", String::from_utf8_lossy(&args[i])))),
+			}
+		}
+
+		// Options are only applied once the whole command has been validated.
+		if let Some(name) = name {
+			connection.set_name(if name.is_empty() { None } else { Some(name) });
+		}
+		connection.set_protocol(protocol);
+
+		let protocol_version = match protocol {
+			RespProtocol::Resp2 => 2,
+			RespProtocol::Resp3 => 3,
+		};
+		let response = map_response(vec![
+			(bulk_string_response("server"), bulk_string_response("redis")),
+			(bulk_string_response("version"), bulk_string_response(REDIS_VERSION)),
+			(bulk_string_response("proto"), integer_response(protocol_version)),
+			(bulk_string_response("id"), integer_response(connection.id() as i64)),
+			(bulk_string_response("mode"), bulk_string_response("standalone")),
+			(bulk_string_response("role"), bulk_string_response(server.replication().role.to_string())),
+			(bulk_string_response("modules"), array_response(Vec::new())),
+		]);
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,192 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::Store, util::{array_response, integer_response, parse_integer, respond, unix_time_ms}};
+
+use super::{expire::{parse_conditions, ExpireConditions, ExpireUnit}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHexpire {}
+
+impl Command for CommandHexpire {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hexpire",
+		summary: "Set expiry for hash field using relative time to expire (seconds)",
+		since: "7.4.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of specified fields",
+		arity: -6,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		hexpire_generic(server, connection, args, ExpireUnit::Seconds, true).await
+	}
+}
+
+/// Field expiries are limited to 2^48 milliseconds, like in Redis.
+const HASH_FIELD_MAX_EXPIRE: i64 = (1 << 48) - 1;
+
+/// Implements `HEXPIRE`, `HPEXPIRE`, `HEXPIREAT` and `HPEXPIREAT`.
+/// The time is relative to now if `relative` is set, a Unix timestamp otherwise.
+/// Replies with -2 for fields that do not exist, 0 if the conditions do not hold, 1 if the expiry was set
+/// and 2 if the field was deleted because the time is in the past.
+pub async fn hexpire_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, unit: ExpireUnit, relative: bool) -> Result<(), CommandError> {
+	let name = String::from_utf8_lossy(&args[0]).to_lowercase();
+	let invalid_expire_time = || CommandError::Other(format!("invalid expire time in '{name}' command"));
+	let mut when = parse_integer(&args[2])?;
+	if when < 0 {
+		return Err(invalid_expire_time());
+	}
+	if let ExpireUnit::Seconds = unit {
+		when = when.checked_mul(1000).ok_or_else(invalid_expire_time)?;
+	}
+	if relative {
+		when = when.checked_add(unix_time_ms()).ok_or_else(invalid_expire_time)?;
+	}
+	if when > HASH_FIELD_MAX_EXPIRE {
+		return Err(invalid_expire_time());
+	}
+	let (conditions, fields_at) = match args[3].eq_ignore_ascii_case(b"FIELDS") {
+		true => (ExpireConditions::default(), 3),
+		false => (parse_conditions(&args[3..4])?, 4),
+	};
+	let fields = parse_fields(&args, fields_at, 1)?;
+
+	let replies = expire_fields(&mut server.store(connection.db()), &args[1], fields, when, &conditions)?;
+	respond(connection, array_response(replies.into_iter().map(integer_response).collect())).await;
+	Ok(())
+}
+
+/// Sets the expiry of `fields` of the hash at `key` to `when` if `conditions` hold. Returns the reply for each field.
+fn expire_fields(store: &mut Store, key: &[u8], fields: &[Bytes], when: i64, conditions: &ExpireConditions) -> Result<Vec<i64>, CommandError> {
+	let Some(value) = store.get_mut(key) else {
+		return Ok(vec![-2; fields.len()]);
+	};
+	let hash = value.as_hash_mut()?;
+	let now = unix_time_ms();
+	let replies: Vec<i64> = fields.iter().map(|field| {
+		if !hash.contains(field) {
+			-2
+		} else if !conditions.allow(hash.field_expires_at(field), when) {
+			0
+		} else if when <= now {
+			// An expiry in the past deletes the field right away.
+			hash.remove(field);
+			2
+		} else {
+			hash.set_field_expires_at(field, Some(when));
+			1
+		}
+	}).collect();
+	if replies.contains(&1) {
+		store.track_field_expiry(key, when);
+	}
+	store.delete_if_empty(key);
+	Ok(replies)
+}
+
+/// Parses `FIELDS numfields field ...` at `index`. Each field is followed by `arguments_per_field - 1` arguments.
+/// Returns the arguments after `numfields`.
+pub fn parse_fields(args: &[Bytes], index: usize, arguments_per_field: usize) -> Result<&[Bytes], CommandError> {
+	if !args.get(index).is_some_and(|arg| arg.eq_ignore_ascii_case(b"FIELDS")) {
+		return Err(CommandError::Other(String::from("Mandatory argument FIELDS is missing or not at the right position")));
+	}
+	let numfields = args.get(index + 1).and_then(|arg| parse_integer(arg).ok()).filter(|&numfields| numfields > 0);
+	let Some(numfields) = numfields else {
+		return Err(CommandError::Other(String::from("Parameter `numFields` should be greater than 0")));
+	};
+	let fields = &args[index + 2..];
+	if (numfields as u64).checked_mul(arguments_per_field as u64) != Some(fields.len() as u64) {
+		return Err(CommandError::Other(String::from("The `numfields` parameter must match the number of arguments")));
+	}
+	Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{test_util::bytes, types::{hash::Hash, EncodingLimits, Value}, util::freeze_time_ms};
+
+	use super::*;
+
+	fn hash_with(store: &mut Store, key: &[u8], fields: &[&str]) {
+		let hash = store.get_or_insert_with(key, || Value::Hash(Hash::new())).as_hash_mut().unwrap();
+		for field in bytes(fields) {
+			hash.insert(field, Bytes::from_static(b"value"), &EncodingLimits::default());
+		}
+	}
+
+	fn expires_at(store: &mut Store, field: &str) -> Option<i64> {
+		store.get(b"hash").unwrap().as_hash().unwrap().field_expires_at(field.as_bytes())
+	}
+
+	fn conditions(options: &[&str]) -> ExpireConditions {
+		parse_conditions(&bytes(options)).unwrap()
+	}
+
+	#[test]
+	fn replies_for_missing_keys_and_fields() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		assert_eq!(expire_fields(&mut store, b"hash", &bytes(&["a", "b"]), 5000, &conditions(&[])), Ok(vec![-2, -2]));
+		hash_with(&mut store, b"hash", &["a"]);
+		assert_eq!(expire_fields(&mut store, b"hash", &bytes(&["a", "b"]), 5000, &conditions(&[])), Ok(vec![1, -2]));
+		assert_eq!(expires_at(&mut store, "a"), Some(5000));
+		store.set(Bytes::from_static(b"string"), Bytes::from_static(b"value"), None);
+		assert_eq!(expire_fields(&mut store, b"string", &bytes(&["a"]), 5000, &conditions(&[])), Err(CommandError::WrongType));
+	}
+
+	#[test]
+	fn sets_expiries_only_if_the_conditions_hold() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		hash_with(&mut store, b"hash", &["volatile", "persistent"]);
+		expire_fields(&mut store, b"hash", &bytes(&["volatile"]), 5000, &conditions(&[])).unwrap();
+		let fields = bytes(&["volatile", "persistent"]);
+		assert_eq!(expire_fields(&mut store, b"hash", &fields, 6000, &conditions(&["XX"])), Ok(vec![1, 0]));
+		assert_eq!(expire_fields(&mut store, b"hash", &fields, 4000, &conditions(&["GT"])), Ok(vec![0, 0]));
+		assert_eq!(expire_fields(&mut store, b"hash", &fields, 4000, &conditions(&["LT"])), Ok(vec![1, 1]));
+		assert_eq!(expire_fields(&mut store, b"hash", &fields, 7000, &conditions(&["GT"])), Ok(vec![1, 1]));
+		assert_eq!(expire_fields(&mut store, b"hash", &fields, 8000, &conditions(&["NX"])), Ok(vec![0, 0]));
+		assert_eq!(expires_at(&mut store, "volatile"), Some(7000));
+		assert_eq!(expires_at(&mut store, "persistent"), Some(7000));
+	}
+
+	#[test]
+	fn deletes_fields_with_an_expiry_in_the_past() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		hash_with(&mut store, b"hash", &["a", "b"]);
+		assert_eq!(expire_fields(&mut store, b"hash", &bytes(&["a"]), 1000, &conditions(&[])), Ok(vec![2]));
+		assert!(!store.get(b"hash").unwrap().as_hash().unwrap().contains(b"a"));
+		// The key is deleted with its last field.
+		assert_eq!(expire_fields(&mut store, b"hash", &bytes(&["b", "a"]), 0, &conditions(&[])), Ok(vec![2, -2]));
+		assert!(!store.has(b"hash"));
+	}
+
+	#[test]
+	fn deletes_the_key_once_its_last_field_expired() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		hash_with(&mut store, b"hash", &["a", "b"]);
+		expire_fields(&mut store, b"hash", &bytes(&["a"]), 2000, &conditions(&[])).unwrap();
+		expire_fields(&mut store, b"hash", &bytes(&["b"]), 3000, &conditions(&[])).unwrap();
+		freeze_time_ms(Some(2001));
+		assert_eq!(store.get(b"hash").unwrap().as_hash().unwrap().len(), 1);
+		freeze_time_ms(Some(3001));
+		assert!(!store.has(b"hash"));
+		assert_eq!(store.expired_fields(), 2);
+		assert_eq!(store.expired_keys(), 0);
+	}
+
+	#[test]
+	fn parses_fields() {
+		let args = bytes(&["hexpire", "hash", "10", "FIELDS", "2", "a", "b"]);
+		assert_eq!(parse_fields(&args, 3, 1), Ok(&args[5..]));
+		assert!(parse_fields(&args, 2, 1).is_err());
+		assert!(parse_fields(&args, 3, 2).is_err());
+		assert!(parse_fields(&bytes(&["hexpire", "hash", "10", "FIELDS", "0"]), 3, 1).is_err());
+		assert!(parse_fields(&bytes(&["hexpire", "hash", "10", "FIELDS", "3", "a", "b"]), 3, 1).is_err());
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{expire::ExpireUnit, hexpire::hexpire_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHexpireat {}
+
+impl Command for CommandHexpireat {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hexpireat",
+		summary: "Set expiry for hash field using an absolute Unix timestamp (seconds)",
+		since: "7.4.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of specified fields",
+		arity: -6,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		hexpire_generic(server, connection, args, ExpireUnit::Seconds, false).await
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{expire::ExpireUnit, httl::httl_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHexpiretime {}
+
+impl Command for CommandHexpiretime {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hexpiretime",
+		summary: "Returns the expiration time of a hash field as a Unix timestamp, in seconds.",
+		since: "7.4.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of specified fields",
+		arity: -5,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		httl_generic(server, connection, args, ExpireUnit::Seconds, true).await
+	}
+}
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHget {}
+
+impl Command for CommandHget {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hget",
+		summary: "Returns the value of a field in a hash.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let value = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_hash()?.get(&args[2]),
+			None => None,
+		};
+		respond(connection, value.map_or_else(null_response, bulk_string_response)).await;
+		Ok(())
+	}
+}
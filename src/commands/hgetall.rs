@@ -0,0 +1,32 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, map_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHgetall {}
+
+impl Command for CommandHgetall {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hgetall",
+		summary: "Returns all fields and values in a hash.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the size of the hash.",
+		arity: 2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let entries = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_hash()?.iter().map(|(field, value)| (bulk_string_response(field), bulk_string_response(value))).collect(),
+			None => Vec::new(),
+		};
+		// A map in RESP3, a flat array of fields and values in RESP2.
+		respond(connection, map_response(entries)).await;
+		Ok(())
+	}
+}
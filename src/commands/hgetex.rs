@@ -0,0 +1,127 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::Store, util::{array_response, bulk_string_response, null_response, respond, unix_time_ms}};
+
+use super::{hexpire::parse_fields, set::expire_time, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHgetex {}
+
+impl Command for CommandHgetex {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hgetex",
+		summary: "Get the value of one or more fields of a given hash key, and optionally set their expiration time or time-to-live (TTL).",
+		since: "8.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of specified fields",
+		arity: -5,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		// `Some(None)` stands for `PERSIST`, which removes the expiries.
+		let mut expiry = None;
+		let mut i = 2;
+		while !args[i].eq_ignore_ascii_case(b"FIELDS") {
+			if expiry.is_some() {
+				return Err(CommandError::Syntax);
+			}
+			let option = args[i].to_ascii_uppercase();
+			match option.as_slice() {
+				b"EX" | b"PX" | b"EXAT" | b"PXAT" => {
+					let Some(value) = args.get(i + 1) else {
+						return Err(CommandError::Syntax);
+					};
+					expiry = Some(Some(expire_time(&option, value, "hgetex")?));
+					i += 2;
+				},
+				b"PERSIST" => {
+					expiry = Some(None);
+					i += 1;
+				},
+				_ => return Err(CommandError::Syntax),
+			}
+			if i >= args.len() {
+				break;
+			}
+		}
+		let fields = parse_fields(&args, i, 1)?;
+
+		let values = get_fields(&mut server.store(connection.db()), &args[1], fields, expiry)?;
+		respond(connection, array_response(values.into_iter().map(|value| value.map_or_else(null_response, bulk_string_response)).collect())).await;
+		Ok(())
+	}
+}
+
+/// The values of `fields` of the hash at `key`. Sets the expiry of the fields that exist to `expiry`, if given.
+fn get_fields(store: &mut Store, key: &[u8], fields: &[Bytes], expiry: Option<Option<i64>>) -> Result<Vec<Option<Bytes>>, CommandError> {
+	let Some(value) = store.get_mut(key) else {
+		return Ok(vec![None; fields.len()]);
+	};
+	let hash = value.as_hash_mut()?;
+	let values: Vec<_> = fields.iter().map(|field| hash.get(field)).collect();
+	let now = unix_time_ms();
+	for (field, _) in fields.iter().zip(&values).filter(|(_, value)| value.is_some()) {
+		match expiry {
+			Some(Some(when)) if when <= now => {
+				hash.remove(field);
+			},
+			Some(expires_at) => hash.set_field_expires_at(field, expires_at),
+			None => (),
+		}
+	}
+	if let Some(Some(when)) = expiry {
+		store.track_field_expiry(key, when);
+	}
+	store.delete_if_empty(key);
+	Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{test_util::bytes, types::{hash::Hash, EncodingLimits, Value}, util::freeze_time_ms};
+
+	use super::*;
+
+	fn store_with_hash(fields: &[&str]) -> Store {
+		let mut store = Store::init();
+		let hash = store.get_or_insert_with(b"hash", || Value::Hash(Hash::new())).as_hash_mut().unwrap();
+		for field in bytes(fields) {
+			hash.insert(field.clone(), field, &EncodingLimits::default());
+		}
+		store
+	}
+
+	fn expires_at(store: &mut Store, field: &str) -> Option<i64> {
+		store.get(b"hash").unwrap().as_hash().unwrap().field_expires_at(field.as_bytes())
+	}
+
+	#[test]
+	fn gets_fields_and_sets_the_expiry_of_the_existing_ones() {
+		let mut store = store_with_hash(&["a", "b"]);
+		freeze_time_ms(Some(1000));
+		assert_eq!(get_fields(&mut store, b"missing", &bytes(&["a"]), Some(Some(5000))), Ok(vec![None]));
+		let values = get_fields(&mut store, b"hash", &bytes(&["a", "c"]), Some(Some(5000)));
+		assert_eq!(values, Ok(vec![Some(Bytes::from_static(b"a")), None]));
+		assert_eq!(expires_at(&mut store, "a"), Some(5000));
+		assert_eq!(expires_at(&mut store, "b"), None);
+		assert!(!store.get(b"hash").unwrap().as_hash().unwrap().contains(b"c"));
+		assert_eq!(get_fields(&mut store, b"hash", &bytes(&["a"]), None), Ok(vec![Some(Bytes::from_static(b"a"))]));
+		assert_eq!(expires_at(&mut store, "a"), Some(5000));
+		// PERSIST removes the expiry.
+		get_fields(&mut store, b"hash", &bytes(&["a"]), Some(None)).unwrap();
+		assert_eq!(expires_at(&mut store, "a"), None);
+	}
+
+	#[test]
+	fn deletes_fields_given_an_expiry_in_the_past() {
+		let mut store = store_with_hash(&["a", "b"]);
+		freeze_time_ms(Some(1000));
+		assert_eq!(get_fields(&mut store, b"hash", &bytes(&["a"]), Some(Some(1000))), Ok(vec![Some(Bytes::from_static(b"a"))]));
+		assert!(!store.get(b"hash").unwrap().as_hash().unwrap().contains(b"a"));
+		assert_eq!(get_fields(&mut store, b"hash", &bytes(&["b"]), Some(Some(0))), Ok(vec![Some(Bytes::from_static(b"b"))]));
+		assert!(!store.has(b"hash"));
+	}
+}
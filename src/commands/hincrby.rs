@@ -0,0 +1,42 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{hash::Hash, Value}, util::{integer_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHincrby {}
+
+impl Command for CommandHincrby {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hincrby",
+		summary: "Increments the integer value of a field in a hash by a number. Uses 0 as initial value if the field doesn't exist.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1)",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let increment = parse_integer(&args[3])?;
+		let value = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			let hash = store.get_or_insert_with(&args[1], || Value::Hash(Hash::new())).as_hash_mut()?;
+			let current = match hash.get(&args[2]) {
+				Some(current) => parse_integer(&current).map_err(|_| CommandError::Other(String::from("hash value is not an integer")))?,
+				None => 0,
+			};
+			let Some(value) = current.checked_add(increment) else {
+				return Err(CommandError::Other(String::from("increment or decrement would overflow")));
+			};
+			hash.update(args[2].clone(), Bytes::from(value.to_string()), limits);
+			value
+		};
+		respond(connection, integer_response(value)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,47 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{hash::Hash, Value}, util::{bulk_string_response, format_long_double, parse_float, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHincrbyfloat {}
+
+impl Command for CommandHincrbyfloat {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hincrbyfloat",
+		summary: "Increments the floating point value of a field by a number. Uses 0 as initial value if the field doesn't exist.",
+		since: "2.6.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1)",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let increment = parse_float(&args[3])?;
+		let value = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			let current = match store.get(&args[1]) {
+				Some(value) => match value.as_hash()?.get(&args[2]) {
+					Some(current) => parse_float(&current).map_err(|_| CommandError::Other(String::from("hash value is not a float")))?,
+					None => 0.0,
+				},
+				None => 0.0,
+			};
+			let value = current + increment;
+			if !value.is_finite() {
+				return Err(CommandError::Other(String::from("increment would produce NaN or Infinity")));
+			}
+			let value = Bytes::from(format_long_double(value));
+			let hash = store.get_or_insert_with(&args[1], || Value::Hash(Hash::new())).as_hash_mut()?;
+			hash.update(args[2].clone(), value.clone(), limits);
+			value
+		};
+		respond(connection, bulk_string_response(value)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHlen {}
+
+impl Command for CommandHlen {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hlen",
+		summary: "Returns the number of fields in a hash.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let len = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_hash()?.len(),
+			None => 0,
+		};
+		respond(connection, integer_response(len as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, bulk_string_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHmget {}
+
+impl Command for CommandHmget {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hmget",
+		summary: "Returns the values of all fields in a hash.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of fields being requested.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let values = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let hash = value.as_hash()?;
+				args[2..].iter().map(|field| hash.get(field)).collect()
+			},
+			None => vec![None; args.len() - 2],
+		};
+		respond(connection, array_response(values.into_iter().map(|value| value.map_or_else(null_response, bulk_string_response)).collect())).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,43 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, integer_response, respond}};
+
+use super::{hexpire::parse_fields, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHpersist {}
+
+impl Command for CommandHpersist {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hpersist",
+		summary: "Removes the expiration time for each specified field",
+		since: "7.4.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of specified fields",
+		arity: -5,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let fields = parse_fields(&args, 2, 1)?;
+		let replies = match server.store(connection.db()).get_mut(&args[1]) {
+			Some(value) => {
+				let hash = value.as_hash_mut()?;
+				// -2 if the field does not exist, -1 if it has no expiry, 1 if the expiry was removed.
+				fields.iter().map(|field| match (hash.contains(field), hash.field_expires_at(field)) {
+					(false, _) => -2,
+					(true, None) => -1,
+					(true, Some(_)) => {
+						hash.set_field_expires_at(field, None);
+						1
+					},
+				}).collect()
+			},
+			None => vec![-2; fields.len()],
+		};
+		respond(connection, array_response(replies.into_iter().map(integer_response).collect())).await;
+		Ok(())
+	}
+}
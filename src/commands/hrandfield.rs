@@ -0,0 +1,70 @@
+use bytes::Bytes;
+use rand::seq::SliceRandom;
+
+use crate::{connection::Connection, resp::RespProtocol, server::Server, util::{array_response, RandomSample, bulk_string_response, null_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHrandfield {}
+
+impl Command for CommandHrandfield {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hrandfield",
+		summary: "Returns one or more random fields from a hash.",
+		since: "6.2.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of fields returned",
+		arity: -2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let count = args.get(2).map(|count| parse_integer(count)).transpose()?;
+		let with_values = match args.get(3) {
+			Some(option) if args.len() == 4 && option.eq_ignore_ascii_case(b"WITHVALUES") => true,
+			None => false,
+			_ => return Err(CommandError::Syntax),
+		};
+		let Some(count) = count else {
+			let field = match server.store(connection.db()).get(&args[1]) {
+				Some(value) => value.as_hash()?.random().map(|(field, _)| field),
+				None => None,
+			};
+			respond(connection, field.map_or_else(null_response, bulk_string_response)).await;
+			return Ok(());
+		};
+		let max = if with_values { i64::MAX / 2 } else { i64::MAX };
+		if count.unsigned_abs() > max as u64 {
+			return Err(CommandError::Other(format!("value is out of range, value must between {} and {}", -max, max)));
+		}
+
+		let sample = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let hash = value.as_hash()?;
+				if count < 0 {
+					// A negative count may return the same field multiple times.
+					RandomSample::new(count.unsigned_abs() as usize, hash.len(), || hash.random(), || hash.iter().collect())
+				} else {
+					let mut entries: Vec<(Bytes, Bytes)> = hash.iter().collect();
+					entries.shuffle(&mut rand::thread_rng());
+					entries.truncate(count as usize);
+					RandomSample::Picked(entries)
+				}
+			},
+			None => RandomSample::Picked(Vec::new()),
+		};
+		let entries = sample.draw();
+		let resp3 = connection.protocol() == RespProtocol::Resp3;
+		let response = match with_values {
+			// RESP3 replies with an array of field and value pairs, RESP2 with a flat array.
+			true if resp3 => array_response(entries.into_iter().map(|(field, value)| array_response(vec![bulk_string_response(field), bulk_string_response(value)])).collect()),
+			true => array_response(entries.into_iter().flat_map(|(field, value)| [bulk_string_response(field), bulk_string_response(value)]).collect()),
+			false => array_response(entries.into_iter().map(|(field, _)| bulk_string_response(field)).collect()),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,50 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, respond}};
+
+use super::{scan::{parse_cursor, scan_response, ScanOptions}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHscan {}
+
+impl Command for CommandHscan {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hscan",
+		summary: "Iterates over fields and values of a hash.",
+		since: "2.8.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1) for every call. O(N) for a complete iteration, including enough command calls for the cursor to return back to 0. N is the number of elements inside the collection.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let cursor = parse_cursor(&args[2])?;
+		let options = ScanOptions::parse(&args[3..], false, true)?;
+
+		let mut elements = Vec::new();
+		let cursor = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let hash = value.as_hash()?;
+				options.scan(cursor, |cursor| {
+					let mut visited = 0;
+					let cursor = hash.scan(cursor, |field, value| {
+						visited += 1;
+						if options.matches(&field) {
+							elements.push(bulk_string_response(field));
+							if !options.novalues {
+								elements.push(bulk_string_response(value));
+							}
+						}
+					});
+					(cursor, visited)
+				})
+			},
+			None => 0,
+		};
+		respond(connection, scan_response(cursor, elements)).await;
+		Ok(())
+	}
+}
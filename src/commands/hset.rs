@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{hash::Hash, Value}, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHset {}
+
+impl Command for CommandHset {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hset",
+		summary: "Creates or modifies the value of a field in a hash.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1) for each field/value pair added, so O(N) to add N field/value pairs when the command is called with multiple field/value pairs.",
+		arity: -4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		if !(args.len() - 2).is_multiple_of(2) {
+			return Err(CommandError::WrongNumberOfArguments(Self::SPEC.name.to_string()));
+		}
+		let added = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			let hash = store.get_or_insert_with(&args[1], || Value::Hash(Hash::new())).as_hash_mut()?;
+			args[2..].chunks(2).filter(|pair| hash.insert(pair[0].clone(), pair[1].clone(), limits)).count()
+		};
+		respond(connection, integer_response(added as i64)).await;
+		Ok(())
+	}
+}
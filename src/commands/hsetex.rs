@@ -0,0 +1,147 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::Store, types::{hash::Hash, EncodingLimits, Value}, util::{integer_response, respond, unix_time_ms}};
+
+use super::{hexpire::parse_fields, set::{expire_time, Expiry}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHsetex {}
+
+impl Command for CommandHsetex {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hsetex",
+		summary: "Set the value of one or more fields of a given hash key, and optionally set their expiration time.",
+		since: "8.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of fields being set.",
+		arity: -6,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let mut only_new = false;
+		let mut only_existing = false;
+		let mut expiry = Expiry::None;
+		let mut i = 2;
+		while i < args.len() && !args[i].eq_ignore_ascii_case(b"FIELDS") {
+			let option = args[i].to_ascii_uppercase();
+			match option.as_slice() {
+				b"FNX" if !only_existing => only_new = true,
+				b"FXX" if !only_new => only_existing = true,
+				b"EX" | b"PX" | b"EXAT" | b"PXAT" if matches!(expiry, Expiry::None) => {
+					let Some(value) = args.get(i + 1) else {
+						return Err(CommandError::Syntax);
+					};
+					expiry = Expiry::At(expire_time(&option, value, "hsetex")?);
+					i += 1;
+				},
+				b"KEEPTTL" if matches!(expiry, Expiry::None) => expiry = Expiry::KeepTtl,
+				_ => return Err(CommandError::Syntax),
+			}
+			i += 1;
+		}
+		let pairs = parse_fields(&args, i, 2)?;
+
+		let set = set_fields(&mut server.store(connection.db()), &args[1], pairs, only_new, only_existing, expiry, &server.config().encoding_limits)?;
+		respond(connection, integer_response(set as i64)).await;
+		Ok(())
+	}
+}
+
+/// Sets the field value `pairs` of the hash at `key` with `expiry`. Returns whether they were set,
+/// which `only_new` limits to none of the fields existing and `only_existing` to all of them existing.
+fn set_fields(store: &mut Store, key: &[u8], pairs: &[Bytes], only_new: bool, only_existing: bool, expiry: Expiry, limits: &EncodingLimits) -> Result<bool, CommandError> {
+	let exists = match store.get(key) {
+		Some(value) => {
+			let hash = value.as_hash()?;
+			pairs.chunks(2).map(|pair| hash.contains(&pair[0])).collect()
+		},
+		None => vec![false; pairs.len() / 2],
+	};
+	if only_new && exists.contains(&true) || only_existing && exists.contains(&false) {
+		return Ok(false);
+	}
+	let hash = store.get_or_insert_with(key, || Value::Hash(Hash::new())).as_hash_mut()?;
+	let now = unix_time_ms();
+	for pair in pairs.chunks(2) {
+		match expiry {
+			Expiry::At(when) if when <= now => {
+				hash.remove(&pair[0]);
+			},
+			Expiry::At(when) => {
+				hash.insert(pair[0].clone(), pair[1].clone(), limits);
+				hash.set_field_expires_at(&pair[0], Some(when));
+			},
+			Expiry::KeepTtl => {
+				hash.update(pair[0].clone(), pair[1].clone(), limits);
+			},
+			Expiry::None => {
+				hash.insert(pair[0].clone(), pair[1].clone(), limits);
+			},
+		}
+	}
+	if let Expiry::At(when) = expiry {
+		store.track_field_expiry(key, when);
+	}
+	store.delete_if_empty(key);
+	Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{test_util::bytes, util::freeze_time_ms};
+
+	use super::*;
+
+	fn hsetex(store: &mut Store, pairs: &[&str], only_new: bool, only_existing: bool, expiry: Expiry) -> Result<bool, CommandError> {
+		set_fields(store, b"hash", &bytes(pairs), only_new, only_existing, expiry, &EncodingLimits::default())
+	}
+
+	fn field(store: &mut Store, field: &str) -> Option<(Bytes, Option<i64>)> {
+		let hash = store.get(b"hash")?.as_hash().unwrap();
+		hash.get(field.as_bytes()).map(|value| (value, hash.field_expires_at(field.as_bytes())))
+	}
+
+	#[test]
+	fn sets_fields_with_an_expiry() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		assert_eq!(hsetex(&mut store, &["a", "1", "b", "2"], false, false, Expiry::At(5000)), Ok(true));
+		assert_eq!(field(&mut store, "a"), Some((Bytes::from_static(b"1"), Some(5000))));
+		assert_eq!(field(&mut store, "b"), Some((Bytes::from_static(b"2"), Some(5000))));
+		assert_eq!(hsetex(&mut store, &["a", "3"], false, false, Expiry::KeepTtl), Ok(true));
+		assert_eq!(field(&mut store, "a"), Some((Bytes::from_static(b"3"), Some(5000))));
+		assert_eq!(hsetex(&mut store, &["a", "4"], false, false, Expiry::None), Ok(true));
+		assert_eq!(field(&mut store, "a"), Some((Bytes::from_static(b"4"), None)));
+		freeze_time_ms(Some(5001));
+		assert_eq!(field(&mut store, "b"), None);
+		assert_eq!(field(&mut store, "a"), Some((Bytes::from_static(b"4"), None)));
+	}
+
+	#[test]
+	fn sets_fields_only_if_none_or_all_of_them_exist() {
+		let mut store = Store::init();
+		assert_eq!(hsetex(&mut store, &["a", "1"], false, true, Expiry::None), Ok(false));
+		assert!(!store.has(b"hash"));
+		assert_eq!(hsetex(&mut store, &["a", "1"], true, false, Expiry::None), Ok(true));
+		assert_eq!(hsetex(&mut store, &["a", "2", "b", "2"], true, false, Expiry::None), Ok(false));
+		assert_eq!(hsetex(&mut store, &["a", "2", "b", "2"], false, true, Expiry::None), Ok(false));
+		assert_eq!(field(&mut store, "a"), Some((Bytes::from_static(b"1"), None)));
+		assert_eq!(hsetex(&mut store, &["a", "3"], false, true, Expiry::None), Ok(true));
+		assert_eq!(field(&mut store, "a"), Some((Bytes::from_static(b"3"), None)));
+		assert_eq!(field(&mut store, "b"), None);
+	}
+
+	#[test]
+	fn deletes_fields_set_with_an_expiry_in_the_past() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		hsetex(&mut store, &["a", "1", "b", "2"], false, false, Expiry::None).unwrap();
+		assert_eq!(hsetex(&mut store, &["a", "3"], false, false, Expiry::At(1000)), Ok(true));
+		assert_eq!(field(&mut store, "a"), None);
+		assert_eq!(hsetex(&mut store, &["b", "3"], false, false, Expiry::At(500)), Ok(true));
+		assert!(!store.has(b"hash"));
+	}
+}
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{hash::Hash, Value}, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHsetnx {}
+
+impl Command for CommandHsetnx {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hsetnx",
+		summary: "Sets the value of a field in a hash only when the field doesn't exist.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1)",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let added = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			let hash = store.get_or_insert_with(&args[1], || Value::Hash(Hash::new())).as_hash_mut()?;
+			!hash.contains(&args[2]) && hash.insert(args[2].clone(), args[3].clone(), limits)
+		};
+		respond(connection, integer_response(added as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHstrlen {}
+
+impl Command for CommandHstrlen {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hstrlen",
+		summary: "Returns the length of the value of a field.",
+		since: "3.2.0",
+		group: CommandGroup::Hash,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let len = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_hash()?.get(&args[2]).map_or(0, |value| value.len()),
+			None => 0,
+		};
+		respond(connection, integer_response(len as i64)).await;
+		Ok(())
+	}
+}
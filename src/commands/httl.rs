@@ -0,0 +1,59 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, integer_response, respond, unix_time_ms}};
+
+use super::{expire::ExpireUnit, hexpire::parse_fields, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHttl {}
+
+impl Command for CommandHttl {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "httl",
+		summary: "Returns the TTL in seconds of a hash field.",
+		since: "7.4.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the number of specified fields",
+		arity: -5,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		httl_generic(server, connection, args, ExpireUnit::Seconds, false).await
+	}
+}
+
+/// Implements `HTTL`, `HPTTL`, `HEXPIRETIME` and `HPEXPIRETIME`.
+/// Replies with the remaining time to live of each field, or with the Unix timestamp it expires at if `absolute` is set.
+/// Replies with -2 for fields that do not exist and with -1 for fields without an expiry.
+pub async fn httl_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, unit: ExpireUnit, absolute: bool) -> Result<(), CommandError> {
+	let fields = parse_fields(&args, 2, 1)?;
+	let expiries = match server.store(connection.db()).get(&args[1]) {
+		Some(value) => {
+			let hash = value.as_hash()?;
+			fields.iter().map(|field| hash.contains(field).then(|| hash.field_expires_at(field))).collect()
+		},
+		None => vec![None; fields.len()],
+	};
+	let now = unix_time_ms();
+	let replies = expiries.into_iter().map(|expires_at| match expires_at {
+		None => -2,
+		Some(None) => -1,
+		Some(Some(expires_at)) => {
+			let milliseconds = match absolute {
+				true => expires_at,
+				false => (expires_at - now).max(0),
+			};
+			match (&unit, absolute) {
+				(ExpireUnit::Milliseconds, _) => milliseconds,
+				(ExpireUnit::Seconds, true) => milliseconds / 1000,
+				// Unlike the TTL of keys, the remaining time of fields is rounded up.
+				(ExpireUnit::Seconds, false) => (milliseconds + 999) / 1000,
+			}
+		},
+	});
+	respond(connection, array_response(replies.map(integer_response).collect())).await;
+	Ok(())
+}
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, bulk_string_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandHvals {}
+
+impl Command for CommandHvals {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "hvals",
+		summary: "Returns all values in a hash.",
+		since: "2.0.0",
+		group: CommandGroup::Hash,
+		complexity: "O(N) where N is the size of the hash.",
+		arity: 2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Hash, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let values = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_hash()?.iter().map(|(_, value)| bulk_string_response(value)).collect(),
+			None => Vec::new(),
+		};
+		respond(connection, array_response(values)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,77 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::Store, types::{string::RedisString, Value}, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandIncr {}
+
+impl Command for CommandIncr {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "incr",
+		summary: "Increments the integer value of a key by one. Uses 0 as initial value if the key doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		incr_generic(server, connection, &args[1], 1).await
+	}
+}
+
+/// Implements `INCR`, `DECR`, `INCRBY` and `DECRBY`.
+pub async fn incr_generic(server: &Server, connection: &mut Connection, key: &[u8], increment: i64) -> Result<(), CommandError> {
+	let value = increment_integer(&mut server.store(connection.db()), key, increment)?;
+	respond(connection, integer_response(value)).await;
+	Ok(())
+}
+
+/// Adds `increment` to the integer at `key` and returns the result.
+/// The expiry of the key is kept, and a missing key counts as 0.
+fn increment_integer(store: &mut Store, key: &[u8], increment: i64) -> Result<i64, CommandError> {
+	let current = match store.get(key) {
+		Some(value) => value.as_string()?.as_int().ok_or(CommandError::NotAnInteger)?,
+		None => 0,
+	};
+	let value = current.checked_add(increment)
+		.ok_or_else(|| CommandError::Other(String::from("increment or decrement would overflow")))?;
+	store.update(key, Value::String(RedisString::from_int(value)));
+	Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn increments_missing_keys_from_zero() {
+		let mut store = Store::init();
+		assert_eq!(increment_integer(&mut store, b"k", 5).unwrap(), 5);
+		assert_eq!(increment_integer(&mut store, b"k", -7).unwrap(), -2);
+	}
+
+	#[test]
+	fn rejects_overflow_and_keeps_the_value() {
+		let mut store = Store::init();
+		store.set(Bytes::from("max"), Bytes::from(i64::MAX.to_string()), None);
+		assert!(matches!(increment_integer(&mut store, b"max", 1), Err(CommandError::Other(_))));
+		assert_eq!(store.get_string(b"max").unwrap(), Some(Bytes::from(i64::MAX.to_string())));
+
+		store.set(Bytes::from("min"), Bytes::from(i64::MIN.to_string()), None);
+		assert!(matches!(increment_integer(&mut store, b"min", -1), Err(CommandError::Other(_))));
+		assert_eq!(increment_integer(&mut store, b"min", i64::MAX).unwrap(), -1);
+	}
+
+	#[test]
+	fn rejects_values_that_are_not_integers() {
+		let mut store = Store::init();
+		store.set(Bytes::from("k"), Bytes::from("1.5"), None);
+		assert!(matches!(increment_integer(&mut store, b"k", 1), Err(CommandError::NotAnInteger)));
+	}
+}
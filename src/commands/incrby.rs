@@ -0,0 +1,27 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::parse_integer};
+
+use super::{incr::incr_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandIncrby {}
+
+impl Command for CommandIncrby {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "incrby",
+		summary: "Increments the integer value of a key by a number. Uses 0 as initial value if the key doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let increment = parse_integer(&args[2])?;
+		incr_generic(server, connection, &args[1], increment).await
+	}
+}
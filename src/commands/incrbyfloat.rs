@@ -0,0 +1,43 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{string::RedisString, Value}, util::{bulk_string_response, format_long_double, parse_float, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandIncrbyfloat {}
+
+impl Command for CommandIncrbyfloat {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "incrbyfloat",
+		summary: "Increment the floating point value of a key by a number. Uses 0 as initial value if the key doesn't exist.",
+		since: "2.6.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let increment = parse_float(&args[2])?;
+		let value = {
+			let mut store = server.store(connection.db());
+			let current = match store.get(&args[1]) {
+				Some(value) => parse_float(&value.as_string()?.to_bytes())?,
+				None => 0.0,
+			};
+			let value = current + increment;
+			if !value.is_finite() {
+				return Err(CommandError::Other(String::from("increment would produce NaN or Infinity")));
+			}
+			let value = Bytes::from(format_long_double(value));
+			// The result is stored as a string even if it is an integer, like Redis does.
+			store.update(&args[1], Value::String(RedisString::plain(value.clone())));
+			value
+		};
+		respond(connection, bulk_string_response(value)).await;
+		Ok(())
+	}
+}
@@ -1,20 +1,44 @@
-use tokio::net::TcpStream;
+use bytes::Bytes;
 
-use crate::{replication::replication_state, resp::{bulk_string::RespBulkString, RespValues, RESP_TERMINATOR}, util::respond};
+use crate::{connection::Connection, resp::{verbatim_string::RespVerbatimString, RespValues, RESP_TERMINATOR}, server::Server, util::respond};
 
-use super::Command;
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
 
 pub struct CommandInfo {}
 
 impl Command for CommandInfo {
-	async fn invoke(stream: &mut TcpStream, _data: RespValues) {
-		let mut info = Vec::new();
+	const SPEC: CommandSpec = CommandSpec {
+		name: "info",
+		summary: "Returns information and statistics about the server.",
+		since: "1.0.0",
+		group: CommandGroup::Server,
+		complexity: "O(1)",
+		arity: -1,
+		flags: &[CommandFlag::Loading, CommandFlag::Stale],
+		keys: &[],
+		acl_categories: &[AclCategory::Slow, AclCategory::Dangerous],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let requested = args[1..].iter().map(|a| a.to_ascii_lowercase()).collect::<Vec<_>>();
+		let all = requested.is_empty() || requested.iter().any(|s| matches!(s.as_slice(), b"default" | b"all" | b"everything"));
+		let wants = |section: &str| all || requested.iter().any(|s| s.as_slice() == section.as_bytes());
 
-		let replication_info = replication_state().to_string().split('\n').collect::<Vec<&str>>().join(RESP_TERMINATOR);
-		info.push(replication_info);
+		let mut info = Vec::new();
+		if wants("stats") {
+			info.push(server.expire_stats().to_string());
+		}
+		if wants("replication") {
+			info.push(server.replication().to_string());
+		}
 
+		// Sections are separated by an empty line.
+		let info = info.iter().map(|section| section.split('\n').collect::<Vec<&str>>().join(RESP_TERMINATOR)).collect::<Vec<String>>();
 		let info = info.join(RESP_TERMINATOR);
-		let response = RespValues::BulkString(RespBulkString::from_raw(info.into_bytes()));
-		respond(stream, response).await;
+		// A verbatim text string in RESP3, a bulk string in RESP2.
+		let response = RespValues::VerbatimString(RespVerbatimString::from_raw(*b"txt", info.into_bytes()));
+		respond(connection, response).await;
+		Ok(())
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, glob::glob_match, server::Server, util::{array_response, bulk_string_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
+
+pub struct CommandKeys {}
+
+impl Command for CommandKeys {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "keys",
+		summary: "Returns all key names that match a pattern.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(N) with N being the number of keys in the database, under the assumption that the key names in the database and the given pattern have limited length.",
+		arity: 2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Slow, AclCategory::Dangerous],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let pattern = &args[1];
+		let match_all = pattern.as_ref() == b"*";
+		let keys = server.store(connection.db()).keys()
+			.filter(|key| match_all || glob_match(pattern, key, false))
+			.map(|key| bulk_string_response(key.clone()))
+			.collect();
+		respond(connection, array_response(keys)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,261 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, resp::RespValues, server::Server, util::{array_response, bulk_string_response, integer_response, map_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLcs {}
+
+impl Command for CommandLcs {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lcs",
+		summary: "Finds the longest common substring.",
+		since: "7.0.0",
+		group: CommandGroup::String,
+		complexity: "O(N*M) where N and M are the lengths of s1 and s2, respectively",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 2, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let options = LcsOptions::parse(&args[3..])?;
+
+		let (a, b) = {
+			let mut store = server.store(connection.db());
+			let mut string = |key: &Bytes| match store.get(key) {
+				Some(value) => value.as_string().map(|s| s.to_bytes())
+					.map_err(|_| CommandError::Other(String::from("The specified keys must contain string values"))),
+				None => Ok(Bytes::new()),
+			};
+			(string(&args[1])?, string(&args[2])?)
+		};
+		if (a.len() + 1).checked_mul(b.len() + 1).is_none_or(|cells| cells > u32::MAX as usize / 4) {
+			return Err(CommandError::Other(String::from("String too long for LCS")));
+		}
+
+		let response = lcs_response(&a, &b, &options);
+		respond(connection, response).await;
+		Ok(())
+	}
+}
+
+/// The options of `LCS` that follow the keys.
+#[derive(Default)]
+struct LcsOptions {
+	len_only: bool,
+	idx: bool,
+	min_match_len: usize,
+	with_match_len: bool,
+}
+
+impl LcsOptions {
+	fn parse(args: &[Bytes]) -> Result<LcsOptions, CommandError> {
+		let mut options = LcsOptions::default();
+		let mut i = 0;
+		while i < args.len() {
+			match args[i].to_ascii_uppercase().as_slice() {
+				b"LEN" => options.len_only = true,
+				b"IDX" => options.idx = true,
+				b"WITHMATCHLEN" => options.with_match_len = true,
+				b"MINMATCHLEN" if i + 1 < args.len() => {
+					options.min_match_len = parse_integer(&args[i + 1])?.max(0) as usize;
+					i += 1;
+				},
+				_ => return Err(CommandError::Syntax),
+			}
+			i += 1;
+		}
+		if options.len_only && options.idx {
+			return Err(CommandError::Other(String::from("If you want both the length and indexes, please just use IDX.")));
+		}
+		Ok(options)
+	}
+}
+
+/// The reply to `LCS` for the strings `a` and `b`: the subsequence itself, its length with `LEN`,
+/// or the ranges it is made of with `IDX`.
+fn lcs_response(a: &[u8], b: &[u8], options: &LcsOptions) -> RespValues {
+	let lcs = Lcs::new(a, b);
+	if options.len_only {
+		integer_response(lcs.len() as i64)
+	} else if options.idx {
+		let matches = lcs.matches(options.min_match_len).into_iter().map(|m| {
+			let mut entry = vec![
+				array_response(vec![integer_response(m.a.0 as i64), integer_response(m.a.1 as i64)]),
+				array_response(vec![integer_response(m.b.0 as i64), integer_response(m.b.1 as i64)]),
+			];
+			if options.with_match_len {
+				entry.push(integer_response(m.len() as i64));
+			}
+			array_response(entry)
+		}).collect();
+		map_response(vec![
+			(bulk_string_response("matches"), array_response(matches)),
+			(bulk_string_response("len"), integer_response(lcs.len() as i64)),
+		])
+	} else {
+		bulk_string_response(lcs.subsequence())
+	}
+}
+
+/// Ranges of a common subsequence that are contiguous in both strings, inclusive.
+struct Match {
+	a: (usize, usize),
+	b: (usize, usize),
+}
+
+impl Match {
+	fn len(&self) -> usize {
+		self.a.1 - self.a.0 + 1
+	}
+}
+
+/// The dynamic programming table of the longest common subsequence of two strings.
+struct Lcs<'a> {
+	a: &'a [u8],
+	b: &'a [u8],
+	/// The length of the longest common subsequence of the first `i` bytes of `a` and the first `j` bytes of `b`
+	/// at `i * (b.len() + 1) + j`.
+	table: Vec<u32>,
+}
+
+impl<'a> Lcs<'a> {
+	fn new(a: &'a [u8], b: &'a [u8]) -> Lcs<'a> {
+		let width = b.len() + 1;
+		let mut table = vec![0; (a.len() + 1) * width];
+		for i in 1..=a.len() {
+			for j in 1..=b.len() {
+				table[i * width + j] = if a[i - 1] == b[j - 1] {
+					table[(i - 1) * width + j - 1] + 1
+				} else {
+					table[(i - 1) * width + j].max(table[i * width + j - 1])
+				};
+			}
+		}
+		Lcs { a, b, table }
+	}
+
+	fn get(&self, i: usize, j: usize) -> usize {
+		self.table[i * (self.b.len() + 1) + j] as usize
+	}
+
+	fn len(&self) -> usize {
+		self.get(self.a.len(), self.b.len())
+	}
+
+	/// Walks the table back from the end. Calls `f` with the positions of each byte of the subsequence, last byte first.
+	fn walk(&self, mut f: impl FnMut(usize, usize)) {
+		let (mut i, mut j) = (self.a.len(), self.b.len());
+		while i > 0 && j > 0 {
+			if self.a[i - 1] == self.b[j - 1] {
+				f(i - 1, j - 1);
+				i -= 1;
+				j -= 1;
+			} else if self.get(i - 1, j) > self.get(i, j - 1) {
+				i -= 1;
+			} else {
+				j -= 1;
+			}
+		}
+	}
+
+	fn subsequence(&self) -> Bytes {
+		let mut subsequence = Vec::with_capacity(self.len());
+		self.walk(|i, _| subsequence.push(self.a[i]));
+		subsequence.reverse();
+		subsequence.into()
+	}
+
+	/// The ranges of the subsequence that are contiguous in both strings, last range first like Redis reports them,
+	/// leaving out ranges shorter than `min_len`.
+	fn matches(&self, min_len: usize) -> Vec<Match> {
+		let mut matches = Vec::new();
+		let mut current: Option<Match> = None;
+		self.walk(|i, j| {
+			match &mut current {
+				Some(m) if m.a.0 == i + 1 && m.b.0 == j + 1 => {
+					m.a.0 = i;
+					m.b.0 = j;
+				},
+				_ => {
+					matches.extend(current.take());
+					current = Some(Match { a: (i, i), b: (j, j) });
+				},
+			}
+		});
+		matches.extend(current);
+		matches.retain(|m| m.len() >= min_len);
+		matches
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::test_util::bytes;
+
+	use super::*;
+
+	fn options(args: &[&str]) -> Result<LcsOptions, CommandError> {
+		LcsOptions::parse(&bytes(args))
+	}
+
+	fn lcs(a: &str, b: &str, args: &[&'static str]) -> RespValues {
+		lcs_response(a.as_bytes(), b.as_bytes(), &options(args).unwrap())
+	}
+
+	/// A match of `IDX` as `((start, end), (start, end))`, followed by its length with `WITHMATCHLEN`.
+	fn idx_match(a: (i64, i64), b: (i64, i64), len: Option<i64>) -> RespValues {
+		let mut entry = vec![
+			array_response(vec![integer_response(a.0), integer_response(a.1)]),
+			array_response(vec![integer_response(b.0), integer_response(b.1)]),
+		];
+		entry.extend(len.map(integer_response));
+		array_response(entry)
+	}
+
+	fn idx_response(matches: Vec<RespValues>, len: i64) -> RespValues {
+		map_response(vec![
+			(bulk_string_response("matches"), array_response(matches)),
+			(bulk_string_response("len"), integer_response(len)),
+		])
+	}
+
+	#[test]
+	fn replies_with_the_subsequence_or_its_length() {
+		assert_eq!(lcs("ohmytext", "mynewtext", &[]), bulk_string_response("mytext"));
+		assert_eq!(lcs("ohmytext", "mynewtext", &["LEN"]), integer_response(6));
+		assert_eq!(lcs("abc", "xyz", &[]), bulk_string_response(""));
+	}
+
+	#[test]
+	fn replies_with_the_matching_ranges() {
+		let matches = vec![idx_match((4, 7), (5, 8), None), idx_match((2, 3), (0, 1), None)];
+		assert_eq!(lcs("ohmytext", "mynewtext", &["IDX"]), idx_response(matches, 6));
+		let matches = vec![idx_match((4, 7), (5, 8), Some(4)), idx_match((2, 3), (0, 1), Some(2))];
+		assert_eq!(lcs("ohmytext", "mynewtext", &["IDX", "WITHMATCHLEN"]), idx_response(matches, 6));
+		// Shorter matches are left out, but still count towards the length.
+		let matches = vec![idx_match((4, 7), (5, 8), Some(4))];
+		assert_eq!(lcs("ohmytext", "mynewtext", &["IDX", "MINMATCHLEN", "4", "WITHMATCHLEN"]), idx_response(matches, 6));
+	}
+
+	#[test]
+	fn handles_empty_strings() {
+		for (a, b) in [("", ""), ("", "abc"), ("abc", "")] {
+			assert_eq!(lcs(a, b, &[]), bulk_string_response(""));
+			assert_eq!(lcs(a, b, &["LEN"]), integer_response(0));
+			assert_eq!(lcs(a, b, &["IDX"]), idx_response(Vec::new(), 0));
+		}
+	}
+
+	#[test]
+	fn rejects_invalid_options() {
+		assert!(matches!(options(&["LEN", "IDX"]), Err(CommandError::Other(_))));
+		assert_eq!(options(&["MINMATCHLEN"]).err(), Some(CommandError::Syntax));
+		assert_eq!(options(&["WITHLEN"]).err(), Some(CommandError::Syntax));
+		assert!(options(&["MINMATCHLEN", "many"]).is_err());
+		assert_eq!(options(&["idx", "minmatchlen", "-3"]).unwrap().min_match_len, 0);
+	}
+}
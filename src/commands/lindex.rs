@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLindex {}
+
+impl Command for CommandLindex {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lindex",
+		summary: "Returns an element from a list by its index.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of elements to traverse to get to the element at index. This makes asking for the first or the last element of the list O(1).",
+		arity: 3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let index = parse_integer(&args[2])?;
+		let element = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_list()?.get(index),
+			None => None,
+		};
+		let response = match element {
+			Some(element) => bulk_string_response(element),
+			None => null_response(),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,44 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLinsert {}
+
+impl Command for CommandLinsert {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "linsert",
+		summary: "Inserts an element before or after another element in a list.",
+		since: "2.2.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of elements to traverse before seeing the value pivot. This means that inserting somewhere on the left end on the list (head) can be considered O(1) and inserting somewhere on the right end (tail) is O(N).",
+		arity: 5,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let after = match args[2].to_ascii_uppercase().as_slice() {
+			b"BEFORE" => false,
+			b"AFTER" => true,
+			_ => return Err(CommandError::Syntax),
+		};
+		let len = {
+			let mut store = server.store(connection.db());
+			let fill = server.config().encoding_limits.list_max_listpack_size;
+			match store.get_mut(&args[1]) {
+				Some(value) => {
+					let list = value.as_list_mut()?;
+					// -1 if the pivot was not found.
+					if list.insert(&args[3], &args[4], after, fill) { list.len() as i64 } else { -1 }
+				},
+				None => 0,
+			}
+		};
+		respond(connection, integer_response(len)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,92 @@
+use std::time::Duration;
+
+use bytes::Bytes;
+
+use crate::{blocking::serve_or_block, connection::Connection, server::Server, store::Store, types::{list::{List, ListEnd}, Value}, util::{bulk_string_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLmove {}
+
+impl Command for CommandLmove {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lmove",
+		summary: "Returns an element after popping it from one list and pushing it to another. Deletes the list if the last element was moved.",
+		since: "6.2.0",
+		group: CommandGroup::List,
+		complexity: "O(1)",
+		arity: 5,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let from = parse_list_end(&args[3])?;
+		let to = parse_list_end(&args[4])?;
+		lmove_generic(server, connection, &args[1], &args[2], from, to).await
+	}
+}
+
+/// Parses `LEFT` or `RIGHT`.
+pub fn parse_list_end(arg: &[u8]) -> Result<ListEnd, CommandError> {
+	match arg.to_ascii_uppercase().as_slice() {
+		b"LEFT" => Ok(ListEnd::Head),
+		b"RIGHT" => Ok(ListEnd::Tail),
+		_ => Err(CommandError::Syntax),
+	}
+}
+
+/// Implements `LMOVE` and `RPOPLPUSH`.
+pub async fn lmove_generic(server: &Server, connection: &mut Connection, source: &Bytes, destination: &Bytes, from: ListEnd, to: ListEnd) -> Result<(), CommandError> {
+	let element = {
+		let mut store = server.store(connection.db());
+		move_element(&mut store, source, destination, from, to, server.config().encoding_limits.list_max_listpack_size)?
+	};
+	let response = match element {
+		Some(element) => bulk_string_response(element),
+		None => null_response(),
+	};
+	respond(connection, response).await;
+	Ok(())
+}
+
+/// Implements `BLMOVE` and `BRPOPLPUSH`.
+pub async fn blocking_lmove_generic(
+	server: &Server,
+	connection: &mut Connection,
+	source: &Bytes,
+	destination: &Bytes,
+	from: ListEnd,
+	to: ListEnd,
+	timeout: Option<Duration>,
+) -> Result<(), CommandError> {
+	let fill = server.config().encoding_limits.list_max_listpack_size;
+	let destination = destination.clone();
+	let serve = Box::new(move |store: &mut Store, key: &Bytes| {
+		store.get(key)?.as_list().ok()?;
+		Some(move_element(store, key, &destination, from, to, fill).map(|element| element.map_or_else(null_response, bulk_string_response)))
+	});
+	serve_or_block(server, connection, vec![source.clone()], timeout, null_response(), |value| value.as_list().is_ok(), serve).await
+}
+
+/// Pops an element from the `from` end of the list at `source` and pushes it to the `to` end of the list at `destination`.
+/// Returns `None` if `source` does not exist.
+pub fn move_element(store: &mut Store, source: &Bytes, destination: &Bytes, from: ListEnd, to: ListEnd, fill: i64) -> Result<Option<Bytes>, CommandError> {
+	let Some(value) = store.get(source) else {
+		return Ok(None);
+	};
+	value.as_list()?;
+	// Check the destination before popping, so a wrong type does not lose the element.
+	if let Some(value) = store.get(destination) {
+		value.as_list()?;
+	}
+	let element = store.get_mut(source).and_then(|v| v.as_list_mut().ok()?.pop(from, fill));
+	let Some(element) = element else {
+		return Ok(None);
+	};
+	store.delete_if_empty(source);
+	store.get_or_insert_with(destination, || Value::List(List::new())).as_list_mut()?.push(&element, to, fill);
+	Ok(Some(element))
+}
@@ -0,0 +1,82 @@
+use bytes::Bytes;
+
+use crate::{blocking::ServeFn, connection::Connection, server::Server, store::Store, types::list::ListEnd, util::{array_response, bulk_string_response, null_array_response, parse_integer, respond}};
+
+use super::{lmove::parse_list_end, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLmpop {}
+
+impl Command for CommandLmpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lmpop",
+		summary: "Returns multiple elements from a list after removing them. Deletes the list if the last element was popped.",
+		since: "7.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N+M) where N is the number of provided keys and M is the number of elements returned.",
+		arity: -4,
+		flags: &[CommandFlag::Write],
+		keys: &[KeySpec::Keynum { index: 1, first: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let (keys, direction, count) = parse_mpop_arguments(&args[1..])?;
+		let mut serve = pop_many(parse_list_end(direction)?, count, server.config().encoding_limits.list_max_listpack_size);
+		let mut response = None;
+		{
+			let mut store = server.store(connection.db());
+			for key in &keys {
+				if let Some(value) = store.get(key) {
+					value.as_list()?;
+				}
+				response = serve(&mut store, key);
+				if response.is_some() {
+					break;
+				}
+			}
+		}
+		respond(connection, response.unwrap_or(Ok(null_array_response()))?).await;
+		Ok(())
+	}
+}
+
+/// Parses `numkeys key [key ...] direction [COUNT count]` of `LMPOP` and friends. Returns the keys, the direction and the count.
+pub fn parse_mpop_arguments(args: &[Bytes]) -> Result<(Vec<Bytes>, &Bytes, usize), CommandError> {
+	let numkeys = parse_integer(&args[0])?;
+	if numkeys <= 0 {
+		return Err(CommandError::Other(String::from("numkeys should be greater than 0")));
+	}
+	let numkeys = numkeys as usize;
+	let Some(direction) = args.get(numkeys + 1) else {
+		return Err(CommandError::Syntax);
+	};
+	let count = match &args[numkeys + 2..] {
+		[] => 1,
+		[option, count] if option.eq_ignore_ascii_case(b"COUNT") => {
+			let count = parse_integer(count)?;
+			if count <= 0 {
+				return Err(CommandError::Other(String::from("count should be greater than 0")));
+			}
+			count as usize
+		},
+		_ => return Err(CommandError::Syntax),
+	};
+	Ok((args[1..=numkeys].to_vec(), direction, count))
+}
+
+/// Pops up to `count` elements from the `end` of a list and replies with the key and the elements.
+pub fn pop_many(end: ListEnd, count: usize, fill: i64) -> ServeFn {
+	Box::new(move |store: &mut Store, key: &Bytes| {
+		let list = store.get_mut(key)?.as_list_mut().ok()?;
+		let elements: Vec<Bytes> = (0..count).map_while(|_| list.pop(end, fill)).collect();
+		if elements.is_empty() {
+			return None;
+		}
+		store.delete_if_empty(key);
+		Some(Ok(array_response(vec![
+			bulk_string_response(key.clone()),
+			array_response(elements.into_iter().map(bulk_string_response).collect()),
+		])))
+	})
+}
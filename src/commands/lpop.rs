@@ -0,0 +1,62 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::list::ListEnd, util::{array_response, bulk_string_response, null_array_response, null_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLpop {}
+
+impl Command for CommandLpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lpop",
+		summary: "Returns the first elements in a list after removing it. Deletes the list if the last element was popped.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of elements returned",
+		arity: -2,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		pop_generic(server, connection, args, ListEnd::Head).await
+	}
+}
+
+/// Implements `LPOP` and `RPOP`. Without a count a single element is returned instead of an array.
+pub async fn pop_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, end: ListEnd) -> Result<(), CommandError> {
+	let count = match args.get(2) {
+		Some(count) => {
+			let count = parse_integer(count)?;
+			if count < 0 {
+				return Err(CommandError::Other(String::from("value is out of range, must be positive")));
+			}
+			Some(count as usize)
+		},
+		None => None,
+	};
+
+	let elements = {
+		let mut store = server.store(connection.db());
+		let fill = server.config().encoding_limits.list_max_listpack_size;
+		match store.get_mut(&args[1]) {
+			Some(value) => {
+				let list = value.as_list_mut()?;
+				let elements: Vec<Bytes> = (0..count.unwrap_or(1)).map_while(|_| list.pop(end, fill)).collect();
+				store.delete_if_empty(&args[1]);
+				Some(elements)
+			},
+			None => None,
+		}
+	};
+	let response = match (elements, count) {
+		(Some(elements), Some(_)) => array_response(elements.into_iter().map(bulk_string_response).collect()),
+		(Some(mut elements), None) => bulk_string_response(elements.remove(0)),
+		(None, Some(_)) => null_array_response(),
+		(None, None) => null_response(),
+	};
+	respond(connection, response).await;
+	Ok(())
+}
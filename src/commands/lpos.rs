@@ -0,0 +1,92 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, integer_response, null_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLpos {}
+
+impl Command for CommandLpos {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lpos",
+		summary: "Returns the index of matching elements in a list.",
+		since: "6.0.6",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of elements in the list, for the average case. When searching for elements near the head or the tail of the list, or when the MAXLEN option is provided, the command may run in constant time.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Read, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let mut rank = 1;
+		let mut count = None;
+		let mut max_len = 0;
+		let mut i = 3;
+		while i < args.len() {
+			let Some(value) = args.get(i + 1) else {
+				return Err(CommandError::Syntax);
+			};
+			match args[i].to_ascii_uppercase().as_slice() {
+				b"RANK" => {
+					rank = parse_integer(value)?;
+					if rank == 0 {
+						return Err(CommandError::Other(String::from(
+							"RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list"
+						)));
+					}
+					if rank == i64::MIN {
+						return Err(CommandError::Other(format!("value is out of range, value must between {} and {}", -i64::MAX, i64::MAX)));
+					}
+				},
+				b"COUNT" => {
+					let value = parse_integer(value)?;
+					if value < 0 {
+						return Err(CommandError::Other(String::from("COUNT can't be negative")));
+					}
+					count = Some(value as usize);
+				},
+				b"MAXLEN" => {
+					let value = parse_integer(value)?;
+					if value < 0 {
+						return Err(CommandError::Other(String::from("MAXLEN can't be negative")));
+					}
+					max_len = value as usize;
+				},
+				_ => return Err(CommandError::Syntax),
+			}
+			i += 2;
+		}
+
+		let positions = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let list = value.as_list()?;
+				// A count of 0 returns all matches, MAXLEN 0 compares all elements.
+				let limit = match count {
+					Some(0) => usize::MAX,
+					Some(count) => count,
+					None => 1,
+				};
+				let compared = if max_len == 0 { list.len() } else { max_len.min(list.len()) };
+				let entries: Box<dyn Iterator<Item = _>> = if rank > 0 { Box::new(list.iter().enumerate()) } else {
+					Box::new(list.iter().rev().enumerate().map(|(i, entry)| (list.len() - 1 - i, entry)))
+				};
+				entries.take(compared)
+					.filter(|(_, entry)| entry.matches(&args[2]))
+					.skip(rank.unsigned_abs() as usize - 1)
+					.take(limit)
+					.map(|(i, _)| i)
+					.collect()
+			},
+			None => Vec::new(),
+		};
+		let response = match count {
+			Some(_) => array_response(positions.into_iter().map(|i| integer_response(i as i64)).collect()),
+			None => positions.first().map_or_else(null_response, |i| integer_response(*i as i64)),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
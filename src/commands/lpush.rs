@@ -0,0 +1,45 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{list::{List, ListEnd}, Value}, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLpush {}
+
+impl Command for CommandLpush {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lpush",
+		summary: "Prepends one or more elements to a list. Creates the key if it doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		push_generic(server, connection, args, ListEnd::Head, false).await
+	}
+}
+
+/// Implements `LPUSH`, `RPUSH`, `LPUSHX` and `RPUSHX`. With `existing` set, the list is not created if it does not exist.
+pub async fn push_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, end: ListEnd, existing: bool) -> Result<(), CommandError> {
+	let len = {
+		let mut store = server.store(connection.db());
+		let fill = server.config().encoding_limits.list_max_listpack_size;
+		if existing && !store.has(&args[1]) {
+			0
+		} else {
+			let list = store.get_or_insert_with(&args[1], || Value::List(List::new())).as_list_mut()?;
+			for element in &args[2..] {
+				list.push(element, end, fill);
+			}
+			list.len()
+		}
+	};
+	respond(connection, integer_response(len as i64)).await;
+	Ok(())
+}
@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, bulk_string_response, normalize_range, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLrange {}
+
+impl Command for CommandLrange {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lrange",
+		summary: "Returns a range of elements from a list.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(S+N) where S is the distance of start offset from HEAD for small lists, from nearest end (HEAD or TAIL) for large lists; and N is the number of elements in the specified range.",
+		arity: 4,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let start = parse_integer(&args[2])?;
+		let end = parse_integer(&args[3])?;
+		let elements = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let list = value.as_list()?;
+				match normalize_range(start, end, list.len()) {
+					Some((start, count)) => list.range(start, count),
+					None => Vec::new(),
+				}
+			},
+			None => Vec::new(),
+		};
+		respond(connection, array_response(elements.into_iter().map(bulk_string_response).collect())).await;
+		Ok(())
+	}
+}
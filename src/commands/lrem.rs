@@ -0,0 +1,40 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLrem {}
+
+impl Command for CommandLrem {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lrem",
+		summary: "Removes elements from a list. Deletes the list if the last element was removed.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N+M) where N is the length of the list and M is the number of elements removed.",
+		arity: 4,
+		flags: &[CommandFlag::Write],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let count = parse_integer(&args[2])?;
+		let removed = {
+			let mut store = server.store(connection.db());
+			let fill = server.config().encoding_limits.list_max_listpack_size;
+			match store.get_mut(&args[1]) {
+				Some(value) => {
+					let removed = value.as_list_mut()?.remove(&args[3], count, fill);
+					store.delete_if_empty(&args[1]);
+					removed
+				},
+				None => 0,
+			}
+		};
+		respond(connection, integer_response(removed as i64)).await;
+		Ok(())
+	}
+}
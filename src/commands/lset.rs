@@ -0,0 +1,38 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{ok_reply, parse_integer}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLset {}
+
+impl Command for CommandLset {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "lset",
+		summary: "Sets the value of an element in a list by its index.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the length of the list. Setting either the first or the last element of the list is O(1).",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let index = parse_integer(&args[2])?;
+		{
+			let mut store = server.store(connection.db());
+			let fill = server.config().encoding_limits.list_max_listpack_size;
+			let Some(value) = store.get_mut(&args[1]) else {
+				return Err(CommandError::Other(String::from("no such key")));
+			};
+			if !value.as_list_mut()?.set(index, &args[3], fill) {
+				return Err(CommandError::Other(String::from("index out of range")));
+			}
+		}
+		ok_reply(connection).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,40 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{normalize_range, ok_reply, parse_integer}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandLtrim {}
+
+impl Command for CommandLtrim {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "ltrim",
+		summary: "Removes elements from both ends a list. Deletes the list if all elements were trimmed.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of elements to be removed by the operation.",
+		arity: 4,
+		flags: &[CommandFlag::Write],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let start = parse_integer(&args[2])?;
+		let end = parse_integer(&args[3])?;
+		{
+			let mut store = server.store(connection.db());
+			let fill = server.config().encoding_limits.list_max_listpack_size;
+			if let Some(value) = store.get_mut(&args[1]) {
+				let list = value.as_list_mut()?;
+				// An empty range removes all elements.
+				let (start, count) = normalize_range(start, end, list.len()).unwrap_or((0, 0));
+				list.trim(start, count, fill);
+				store.delete_if_empty(&args[1]);
+			}
+		}
+		ok_reply(connection).await;
+		Ok(())
+	}
+}
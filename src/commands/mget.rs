@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, bulk_string_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandMget {}
+
+impl Command for CommandMget {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "mget",
+		summary: "Atomically returns the string values of one or more keys.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(N) where N is the number of keys to retrieve.",
+		arity: -2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let values = {
+			let mut store = server.store(connection.db());
+			// Keys of other types are reported as missing instead of failing the whole command.
+			args[1..].iter().map(|key| store.get_string(key).ok().flatten()).collect::<Vec<_>>()
+		};
+		let response = array_response(values.into_iter().map(|value| match value {
+			Some(value) => bulk_string_response(value),
+			None => null_response(),
+		}).collect());
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::ok_reply};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandMset {}
+
+impl Command for CommandMset {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "mset",
+		summary: "Atomically creates or modifies the string values of one or more keys.",
+		since: "1.0.1",
+		group: CommandGroup::String,
+		complexity: "O(N) where N is the number of keys to set.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 2, flags: &[KeyFlag::Ow, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		if args.len().is_multiple_of(2) {
+			return Err(CommandError::WrongNumberOfArguments(String::from("mset")));
+		}
+		{
+			let mut store = server.store(connection.db());
+			for pair in args[1..].chunks(2) {
+				store.set(pair[0].clone(), pair[1].clone(), None);
+			}
+		}
+		ok_reply(connection).await;
+		Ok(())
+	}
+}
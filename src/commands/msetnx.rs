@@ -0,0 +1,41 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandMsetnx {}
+
+impl Command for CommandMsetnx {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "msetnx",
+		summary: "Atomically modifies the string values of one or more keys only when all keys don't exist.",
+		since: "1.0.1",
+		group: CommandGroup::String,
+		complexity: "O(N) where N is the number of keys to set.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 2, flags: &[KeyFlag::Ow, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		if args.len().is_multiple_of(2) {
+			return Err(CommandError::WrongNumberOfArguments(String::from("msetnx")));
+		}
+		let set = {
+			let mut store = server.store(connection.db());
+			// Either all keys are set or none is.
+			let set = !args[1..].iter().step_by(2).any(|key| store.has(key));
+			if set {
+				for pair in args[1..].chunks(2) {
+					store.set(pair[0].clone(), pair[1].clone(), None);
+				}
+			}
+			set
+		};
+		respond(connection, integer_response(set as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,69 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, bulk_string_response, null_response, respond, simple_string_response}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandObject {}
+
+impl Command for CommandObject {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "object",
+		summary: "A container for object introspection commands.",
+		since: "2.2.3",
+		group: CommandGroup::Generic,
+		complexity: "Depends on subcommand.",
+		arity: -2,
+		flags: &[],
+		keys: &[],
+		acl_categories: &[AclCategory::Slow],
+		subcommands: &[
+			CommandSpec {
+				name: "object|encoding",
+				summary: "Returns the internal encoding of a Redis object.",
+				since: "2.2.3",
+				group: CommandGroup::Generic,
+				complexity: "O(1)",
+				arity: 3,
+				flags: &[CommandFlag::Readonly],
+				keys: &[KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Ro] }],
+				acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Slow],
+				subcommands: &[],
+			},
+			CommandSpec {
+				name: "object|help",
+				summary: "Returns helpful text about the different subcommands.",
+				since: "6.2.0",
+				group: CommandGroup::Generic,
+				complexity: "O(1)",
+				arity: 2,
+				flags: &[CommandFlag::Loading, CommandFlag::Stale],
+				keys: &[],
+				acl_categories: &[AclCategory::Keyspace, AclCategory::Slow],
+				subcommands: &[],
+			},
+		],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let response = match args[1].to_ascii_lowercase().as_slice() {
+			b"encoding" => match server.store(connection.db()).get(&args[2]) {
+				Some(value) => bulk_string_response(value.encoding()),
+				None => null_response(),
+			},
+			b"help" => array_response(HELP.iter().map(|line| simple_string_response(line)).collect()),
+			_ => unreachable!("subcommands are validated before the command is invoked"),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
+
+const HELP: &[&str] = &[
+	"OBJECT <subcommand> [<arg> [value] [opt] ...]. Subcommands are:",
+	"ENCODING <key>",
+	"    Return the kind of internal representation used in order to store the value",
+	"    associated with a <key>.",
+	"HELP",
+	"    Print this help.",
+];
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandPersist {}
+
+impl Command for CommandPersist {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "persist",
+		summary: "Removes the expiration time of a key.",
+		since: "2.2.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Write, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let persisted = {
+			let mut store = server.store(connection.db());
+			store.expires_at(&args[1]).is_some() && store.set_expires_at(&args[1], None)
+		};
+		respond(connection, integer_response(persisted as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{expire::{expire_generic, ExpireUnit}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandPexpire {}
+
+impl Command for CommandPexpire {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "pexpire",
+		summary: "Sets the expiration time of a key in milliseconds.",
+		since: "2.6.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Write, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		expire_generic(server, connection, args, ExpireUnit::Milliseconds, true).await
+	}
+}
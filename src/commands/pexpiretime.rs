@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{expire::ExpireUnit, ttl::ttl_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandPexpiretime {}
+
+impl Command for CommandPexpiretime {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "pexpiretime",
+		summary: "Returns the expiration time of a key as a Unix milliseconds timestamp.",
+		since: "7.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		ttl_generic(server, connection, &args[1], ExpireUnit::Milliseconds, true).await
+	}
+}
@@ -1,13 +1,31 @@
-use tokio::net::TcpStream;
+use bytes::Bytes;
 
-use crate::{resp::RespValues, util::{ping_response, respond}};
+use crate::{connection::Connection, resp::{bulk_string::RespBulkString, RespValues}, server::Server, util::{ping_response, respond}};
 
-use super::Command;
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
 
 pub struct CommandPing {}
 
 impl Command for CommandPing {
-	async fn invoke(stream: &mut TcpStream, _data: RespValues) {
-		respond(stream, ping_response()).await;
+	const SPEC: CommandSpec = CommandSpec {
+		name: "ping",
+		summary: "Returns the server's liveliness response.",
+		since: "1.0.0",
+		group: CommandGroup::Connection,
+		complexity: "O(1)",
+		arity: -1,
+		flags: &[CommandFlag::Fast],
+		keys: &[],
+		acl_categories: &[AclCategory::Fast, AclCategory::Connection],
+		subcommands: &[],
+	};
+
+	async fn invoke(_server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		match args.len() {
+			1 => respond(connection, ping_response()).await,
+			2 => respond(connection, RespValues::BulkString(RespBulkString::from_raw(args[1].clone()))).await,
+			_ => return Err(CommandError::WrongNumberOfArguments(Self::SPEC.name.to_string())),
+		}
+		Ok(())
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,29 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::ok_reply};
+
+use super::{set::expire_time, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandPsetex {}
+
+impl Command for CommandPsetex {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "psetex",
+		summary: "Sets both string value and expiration time in milliseconds of a key. The key is created if it doesn't exist.",
+		since: "2.6.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let expires_at = expire_time(b"PX", &args[2], "psetex")?;
+		server.store(connection.db()).set(args[1].clone(), args[3].clone(), Some(expires_at));
+		ok_reply(connection).await;
+		Ok(())
+	}
+}
@@ -1,50 +1,37 @@
-use core::panic;
+use bytes::Bytes;
 
-use tokio::net::TcpStream;
+use crate::{connection::Connection, resp::{simple_string::RespSimpleString, RespValues}, server::Server, util::{parse_integer, respond}};
 
-use crate::{replication::REPLICATION_STATE, resp::{simple_string::RespSimpleString, RespValues}, util::respond};
-
-use super::Command;
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
 
 pub struct CommandPsync {}
 
 impl Command for CommandPsync {
-	async fn invoke(stream: &mut TcpStream, data: RespValues) {
-		match data {
-			RespValues::Array(a) => {
-				assert!(a.len() == 3);
-				let master_replication_id = match a.get(1).unwrap() {
-					RespValues::BulkString(b) => {
-						b.as_str().to_string()
-					},
-					d => panic!("PSYNC: Expected BulkString for master replication id argument, got: '{}'", d),
-				};
-				let offset = match a.get(2).unwrap() {
-					RespValues::BulkString(b) => {
-						match b.as_str().to_string().parse::<isize>() {
-							Ok(v) => v,
-							Err(e) => panic!("Could not parse offset from PSYNC, got: '{}'", e)
-						}
-					},
-					d => panic!("PSYNC: Expected BulkString for offset argument, got: '{}'", d),
-				};
-				reply_to_psync(stream, master_replication_id, offset).await;
-			},
-			_ => eprintln!("Misformed get command: '{}'", data)
-		}
+	const SPEC: CommandSpec = CommandSpec {
+		name: "psync",
+		summary: "An internal command used in replication.",
+		since: "2.8.0",
+		group: CommandGroup::Server,
+		complexity: "",
+		arity: -3,
+		flags: &[CommandFlag::Admin, CommandFlag::Noscript, CommandFlag::NoMulti],
+		keys: &[],
+		acl_categories: &[AclCategory::Admin, AclCategory::Slow, AclCategory::Dangerous],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let master_replication_id = String::from_utf8_lossy(&args[1]).to_string();
+		let offset = parse_integer(&args[2])?;
+		reply_to_psync(server, connection, master_replication_id, offset).await
 	}
 }
 
-async fn reply_to_psync(stream: &mut TcpStream, master_replication_id: String, offset: isize) {
+async fn reply_to_psync(server: &Server, connection: &mut Connection, master_replication_id: String, offset: i64) -> Result<(), CommandError> {
 	if master_replication_id == "?" && offset == -1 {
-		let repl_id = unsafe {
-			if let Some(v) = &REPLICATION_STATE {
-				&v.master_replid
-			} else {
-				panic!("Received PSYNC but REPLICATION_STATE has not been initialized!");
-			}
-		};
+		let repl_id = server.replication().master_replid.clone();
 		let response = RespValues::SimpleString(RespSimpleString::from_str(&format!("FULLRESYNC {} 0", repl_id)));
-		respond(stream, response).await;
+		respond(connection, response).await;
 	}
-}
\ No newline at end of file
+	Ok(())
+}
@@ -0,0 +1,32 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
+
+pub struct CommandRandomkey {}
+
+impl Command for CommandRandomkey {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "randomkey",
+		summary: "Returns a random key name from the database.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: 1,
+		flags: &[CommandFlag::Readonly],
+		keys: &[],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, _args: Vec<Bytes>) -> Result<(), CommandError> {
+		let key = server.store(connection.db()).random_key();
+		let response = match key {
+			Some(key) => bulk_string_response(key),
+			None => null_response(),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
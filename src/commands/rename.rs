@@ -0,0 +1,50 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, ok_reply, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandRename {}
+
+impl Command for CommandRename {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "rename",
+		summary: "Renames a key and overwrites the destination.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Write, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		rename_generic(server, connection, args, false).await
+	}
+}
+
+/// Implements `RENAME` and `RENAMENX`. The expiry of the key is renamed with it.
+/// With `nx` set, an existing destination is not overwritten.
+pub async fn rename_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, nx: bool) -> Result<(), CommandError> {
+	let renamed = {
+		let mut store = server.store(connection.db());
+		if !store.has(&args[1]) {
+			return Err(CommandError::Other(String::from("no such key")));
+		}
+		if args[1] == args[2] {
+			!nx
+		} else if nx && store.has(&args[2]) {
+			false
+		} else {
+			store.rename(&args[1], args[2].clone())
+		}
+	};
+	if nx {
+		respond(connection, integer_response(renamed as i64)).await;
+	} else {
+		ok_reply(connection).await;
+	}
+	Ok(())
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{rename::rename_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandRenamenx {}
+
+impl Command for CommandRenamenx {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "renamenx",
+		summary: "Renames a key only when the target key name doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Write, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		rename_generic(server, connection, args, true).await
+	}
+}
@@ -1,75 +1,91 @@
-use tokio::net::TcpStream;
+use bytes::Bytes;
 
-use crate::{replication::{add_replication_slave, ReplicationSlave}, resp::RespValues, util::ok_reply};
+use crate::{connection::Connection, replication::{ReplicationRole, ReplicationSlave}, server::Server, util::ok_reply};
 
-use super::Command;
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
 
 pub struct CommandReplconf {}
 
-static mut NEXT_SLAVE: ReplicationSlave = ReplicationSlave {
-    port: 0,
-    capabilities: Vec::new(),
-};
-
 impl Command for CommandReplconf {
-	async fn invoke(stream: &mut TcpStream, data: RespValues) {
-		match data {
-			RespValues::Array(a) => {
-				assert!(a.len() >= 3);
-				let stage = match a.get(1).unwrap() {
-					RespValues::BulkString(b) => {
-						b.as_str().to_string()
-					},
-					d => panic!("REPLCONF: Expected BulkString as 2nd argument, got: '{}'", d),
+	const SPEC: CommandSpec = CommandSpec {
+		name: "replconf",
+		summary: "An internal command for configuring the replication stream.",
+		since: "3.0.0",
+		group: CommandGroup::Server,
+		complexity: "O(1)",
+		arity: -1,
+		flags: &[CommandFlag::Admin, CommandFlag::Noscript, CommandFlag::Loading, CommandFlag::Stale, CommandFlag::AllowBusy],
+		keys: &[],
+		acl_categories: &[AclCategory::Admin, AclCategory::Slow, AclCategory::Dangerous],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		// Options are sent as name value pairs, at least one of them.
+		if args.len() < 3 || args.len().is_multiple_of(2) {
+			return Err(CommandError::Syntax);
+		}
+		let stage = String::from_utf8_lossy(&args[1]).to_string();
+		match stage.as_str() {
+			"listening-port" => {
+				let slave_port = match String::from_utf8_lossy(&args[2]).parse::<u16>() {
+					Ok(v) if v > 0 => v,
+					_ => return Err(CommandError::Other(String::from("invalid listening-port"))),
 				};
-				match stage.as_str() {
-					"listening-port" => {
-						let slave_port = match a.get(2).unwrap() {
-							RespValues::BulkString(b) => {
-								match b.as_str().parse::<u16>() {
-									Ok(v) => v,
-									Err(e) => panic!("REPLCONF: Could not parse slave port, got: '{}'", e),
-								}
-							},
-							d => panic!("REPLCONF: Expected BulkString as 3rd argument for listening-port, got: '{}'", d),
-						};
-						unsafe {
-							assert!(NEXT_SLAVE.capabilities.is_empty());
-							NEXT_SLAVE.port = slave_port;
-						}
-						ok_reply(stream).await;
-					},
-					"capa" => {
-						let mut capabilities = Vec::new();
-						for i in (1..a.len()).step_by(2) {
-							let key = match a.get(i).unwrap() {
-								RespValues::BulkString(b) => {
-									b.as_str().to_string()
-								},
-								d => panic!("REPLCONF: Expected BulkString as argument, got: '{}'", d),
-							};
-							assert_eq!(key, String::from("capa"));
-							let value = match a.get(i + 1).unwrap() {
-								RespValues::BulkString(b) => {
-									b.as_str().to_string()
-								},
-								d => panic!("REPLCONF: Expected BulkString as argument, got: '{}'", d),
-							};
-							capabilities.push(value);
-						}
-						unsafe {
-							assert!(NEXT_SLAVE.port > 0);
-							NEXT_SLAVE.capabilities = capabilities;
-							add_replication_slave(NEXT_SLAVE.clone());
-							NEXT_SLAVE.port = 0;
-							NEXT_SLAVE.capabilities.clear();
-						}
-						ok_reply(stream).await;
-					},
-					_ => panic!("REPLCONF: Unknown handshake stage: '{}'", stage)
+				connection.set_replica_listening_port(Some(slave_port));
+				ok_reply(connection).await;
+			},
+			"capa" => {
+				let mut capabilities = Vec::new();
+				for i in (1..args.len()).step_by(2) {
+					if args[i].as_ref() != b"capa" {
+						return Err(CommandError::Syntax);
+					}
+					capabilities.push(String::from_utf8_lossy(&args[i + 1]).to_string());
+				}
+				{
+					let mut replication = server.replication_mut();
+					if replication.role != ReplicationRole::Master {
+						return Err(CommandError::Other(String::from("REPLCONF capa is only accepted by a master")));
+					}
+					let port = match connection.replica_listening_port() {
+						Some(port) => port,
+						None => return Err(CommandError::Other(String::from("REPLCONF listening-port has to be sent before capa"))),
+					};
+					replication.add_slave(ReplicationSlave { port, capabilities });
 				}
+				connection.set_replica_listening_port(None);
+				ok_reply(connection).await;
 			},
-			_ => eprintln!("Misformed get command: '{}'", data)
+			_ => return Err(CommandError::Other(format!("Unrecognized REPLCONF option: {stage}"))),
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{server::ServerConfig, test_util::{bytes, connected}};
+
+	use super::*;
+
+	#[tokio::test]
+	async fn rejects_missing_or_unpaired_options() {
+		let server = Server::new(ServerConfig::default());
+		let (mut connection, _client) = connected().await;
+		for args in [&["REPLCONF"][..], &["REPLCONF", "listening-port"], &["REPLCONF", "capa", "psync2", "capa"]] {
+			assert_eq!(CommandReplconf::invoke(&server, &mut connection, bytes(args)).await, Err(CommandError::Syntax));
 		}
+		assert_eq!(connection.replica_listening_port(), None);
+	}
+
+	#[tokio::test]
+	async fn remembers_the_listening_port() {
+		let server = Server::new(ServerConfig::default());
+		let (mut connection, _client) = connected().await;
+		CommandReplconf::invoke(&server, &mut connection, bytes(&["REPLCONF", "listening-port", "6380"])).await.unwrap();
+		assert_eq!(connection.replica_listening_port(), Some(6380));
+		let result = CommandReplconf::invoke(&server, &mut connection, bytes(&["REPLCONF", "listening-port", "0"])).await;
+		assert!(matches!(result, Err(CommandError::Other(_))));
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::list::ListEnd};
+
+use super::{lpop::pop_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandRpop {}
+
+impl Command for CommandRpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "rpop",
+		summary: "Returns and removes the last elements of a list. Deletes the list if the last element was popped.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(N) where N is the number of elements returned",
+		arity: -2,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		pop_generic(server, connection, args, ListEnd::Tail).await
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::list::ListEnd};
+
+use super::{lmove::lmove_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandRpoplpush {}
+
+impl Command for CommandRpoplpush {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "rpoplpush",
+		summary: "Returns the last element of a list after removing and pushing it to another list. Deletes the list if the last element was popped.",
+		since: "1.2.0",
+		group: CommandGroup::List,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		lmove_generic(server, connection, &args[1], &args[2], ListEnd::Tail, ListEnd::Head).await
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::list::ListEnd};
+
+use super::{lpush::push_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandRpush {}
+
+impl Command for CommandRpush {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "rpush",
+		summary: "Appends one or more elements to a list. Creates the key if it doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::List,
+		complexity: "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::List, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		push_generic(server, connection, args, ListEnd::Tail, false).await
+	}
+}
@@ -0,0 +1,33 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{set::Set, Value}, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSadd {}
+
+impl Command for CommandSadd {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "sadd",
+		summary: "Adds one or more members to a set. Creates the key if it doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(1) for each element added, so O(N) to add N elements when the command is called with multiple arguments.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Set, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let added = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			let set = store.get_or_insert_with(&args[1], || Value::Set(Set::new(&args[2], args.len() - 2, limits))).as_set_mut()?;
+			args[2..].iter().filter(|member| set.insert(member, limits)).count()
+		};
+		respond(connection, integer_response(added as i64)).await;
+		Ok(())
+	}
+}
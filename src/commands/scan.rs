@@ -0,0 +1,184 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, glob::glob_match, resp::RespValues, server::Server, util::{array_response, bulk_string_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
+
+pub struct CommandScan {}
+
+impl Command for CommandScan {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "scan",
+		summary: "Iterates over the key names in the database.",
+		since: "2.8.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1) for every call. O(N) for a complete iteration, including enough command calls for the cursor to return back to 0. N is the number of elements inside the collection.",
+		arity: -2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let cursor = parse_cursor(&args[1])?;
+		let options = ScanOptions::parse(&args[2..], true, false)?;
+
+		let mut keys = Vec::new();
+		let cursor = {
+			let store = server.store(connection.db());
+			options.scan(cursor, |cursor| {
+				let mut visited = 0;
+				let cursor = store.scan(cursor, |key, value| {
+					visited += 1;
+					if options.matches(key) && options.type_name.as_ref().is_none_or(|t| t.as_slice() == value.value().type_name().as_bytes()) {
+						keys.push(bulk_string_response(key.clone()));
+					}
+				});
+				(cursor, visited)
+			})
+		};
+		respond(connection, scan_response(cursor, keys)).await;
+		Ok(())
+	}
+}
+
+/// Type names accepted by the `TYPE` option of `SCAN`, the names `TYPE` reports.
+const TYPE_NAMES: &[&str] = &["string", "list", "set", "zset", "hash", "stream"];
+
+/// Options shared by `SCAN`, `HSCAN`, `SSCAN` and `ZSCAN`.
+pub struct ScanOptions {
+	/// Number of elements to visit per call. More or fewer elements may be returned.
+	pub count: usize,
+	pub pattern: Option<Bytes>,
+	pub type_name: Option<Vec<u8>>,
+	/// Only return the fields of a hash, without their values.
+	pub novalues: bool,
+}
+
+impl ScanOptions {
+	/// `options` are the arguments after the cursor. `TYPE` is only accepted if `allow_type` is set,
+	/// `NOVALUES` only if `allow_novalues` is set.
+	pub fn parse(options: &[Bytes], allow_type: bool, allow_novalues: bool) -> Result<ScanOptions, CommandError> {
+		let mut scan_options = ScanOptions {
+			count: 10,
+			pattern: None,
+			type_name: None,
+			novalues: false,
+		};
+		let mut i = 0;
+		while i < options.len() {
+			let option = options[i].to_ascii_uppercase();
+			if allow_novalues && option == b"NOVALUES" {
+				scan_options.novalues = true;
+				i += 1;
+				continue;
+			}
+			if i + 1 >= options.len() {
+				return Err(CommandError::Syntax);
+			}
+			let value = &options[i + 1];
+			match option.as_slice() {
+				b"COUNT" => {
+					let count = parse_integer(value)?;
+					if count < 1 {
+						return Err(CommandError::Syntax);
+					}
+					scan_options.count = count as usize;
+				},
+				// A pattern matching everything does not need to be checked.
+				b"MATCH" if value.as_ref() == b"*" => scan_options.pattern = None,
+				b"MATCH" => scan_options.pattern = Some(value.clone()),
+				b"TYPE" if allow_type => {
+					let type_name = value.to_ascii_lowercase();
+					if !TYPE_NAMES.iter().any(|t| t.as_bytes() == type_name) {
+						return Err(CommandError::Other(format!("unknown type name '{}'", String::from_utf8_lossy(value))));
+					}
+					scan_options.type_name = Some(type_name);
+				},
+				_ => return Err(CommandError::Syntax),
+			}
+			i += 2;
+		}
+		Ok(scan_options)
+	}
+
+	/// Calls `step` with the cursor to continue with until about `count` elements were visited or the iteration is done.
+	/// `step` returns the next cursor and the number of elements it visited. Returns the cursor for the next call.
+	pub fn scan(&self, mut cursor: u64, mut step: impl FnMut(u64) -> (u64, usize)) -> u64 {
+		let mut visited = 0;
+		// Sparse tables may have many empty buckets in a row, bound the work for them as well.
+		let mut iterations = self.count.saturating_mul(10);
+		loop {
+			let (next, count) = step(cursor);
+			cursor = next;
+			visited += count;
+			iterations -= 1;
+			if cursor == 0 || iterations == 0 || visited >= self.count {
+				return cursor;
+			}
+		}
+	}
+
+	pub fn matches(&self, element: &[u8]) -> bool {
+		self.pattern.as_ref().is_none_or(|pattern| glob_match(pattern, element, false))
+	}
+}
+
+/// Cursors are unsigned 64 bit integers.
+pub fn parse_cursor(arg: &[u8]) -> Result<u64, CommandError> {
+	std::str::from_utf8(arg).ok()
+		.filter(|s| s.bytes().all(|c| c.is_ascii_digit()))
+		.and_then(|s| s.parse::<u64>().ok())
+		.ok_or_else(|| CommandError::Other(String::from("invalid cursor")))
+}
+
+/// The reply to a scan is the cursor to continue with, followed by the elements found.
+pub fn scan_response(cursor: u64, elements: Vec<RespValues>) -> RespValues {
+	array_response(vec![
+		bulk_string_response(cursor.to_string()),
+		array_response(elements),
+	])
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::test_util::bytes;
+
+	use super::*;
+
+	#[test]
+	fn parses_options() {
+		let options = ScanOptions::parse(&bytes(&["count", "5", "MATCH", "a*", "TYPE", "Hash"]), true, false).unwrap();
+		assert_eq!(options.count, 5);
+		assert_eq!(options.pattern, Some(Bytes::from_static(b"a*")));
+		assert_eq!(options.type_name, Some(b"hash".to_vec()));
+		assert!(ScanOptions::parse(&bytes(&["NOVALUES"]), true, true).unwrap().novalues);
+		for options in [&["COUNT", "0"][..], &["COUNT"], &["TYPE", "hash"], &["NOVALUES"]] {
+			assert!(ScanOptions::parse(&bytes(options), false, false).is_err());
+		}
+		assert!(ScanOptions::parse(&bytes(&["TYPE", "stack"]), true, false).is_err());
+	}
+
+	#[test]
+	fn scans_until_count_elements_were_visited() {
+		let options = ScanOptions::parse(&bytes(&["COUNT", "3"]), false, false).unwrap();
+		// Each step visits the bucket at the cursor, which holds one element in every other bucket.
+		let mut steps = Vec::new();
+		let cursor = options.scan(0, |cursor| {
+			steps.push(cursor);
+			(cursor + 1, (cursor % 2) as usize)
+		});
+		assert_eq!((cursor, steps), (6, vec![0, 1, 2, 3, 4, 5]));
+		assert_eq!(options.scan(7, |_| (0, 0)), 0);
+	}
+
+	#[test]
+	fn bounds_the_work_for_empty_buckets() {
+		let options = ScanOptions::parse(&bytes(&["COUNT", "2"]), false, false).unwrap();
+		assert_eq!(options.scan(0, |cursor| (cursor + 1, 0)), 20);
+		// The bound saturates instead of overflowing for huge counts.
+		let options = ScanOptions::parse(&bytes(&["COUNT", &i64::MAX.to_string()]), false, false).unwrap();
+		assert_eq!(options.scan(0, |cursor| (if cursor == 100 { 0 } else { cursor + 1 }, 1)), 0);
+	}
+}
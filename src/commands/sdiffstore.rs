@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{sinter::{set_operation_generic, SetOperation}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSdiffstore {}
+
+impl Command for CommandSdiffstore {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "sdiffstore",
+		summary: "Stores the difference of multiple sets in a key.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(N) where N is the total number of elements in all given sets.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }, KeySpec::Range { first: 2, last: -1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Set, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		set_operation_generic(server, connection, &args[2..], Some(&args[1]), SetOperation::Difference).await
+	}
+}
@@ -0,0 +1,35 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{ok_reply, parse_integer}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec};
+
+pub struct CommandSelect {}
+
+impl Command for CommandSelect {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "select",
+		summary: "Changes the selected database.",
+		since: "1.0.0",
+		group: CommandGroup::Connection,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Loading, CommandFlag::Stale, CommandFlag::Fast],
+		keys: &[],
+		acl_categories: &[AclCategory::Connection, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let index = match parse_integer(&args[1]).ok().and_then(|v| i32::try_from(v).ok()) {
+			Some(index) => index,
+			None => return Err(CommandError::Other(String::from("invalid DB index"))),
+		};
+		match server.db_index(index as i64) {
+			Some(db) => connection.select_db(db),
+			None => return Err(CommandError::Other(String::from("DB index is out of range"))),
+		}
+		ok_reply(connection).await;
+		Ok(())
+	}
+}
@@ -1,64 +1,118 @@
-use core::panic;
-use std::collections::HashMap;
+use bytes::Bytes;
 
-use tokio::net::TcpStream;
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_reply, null_response, ok_reply, parse_integer, respond, unix_time_ms}};
 
-use crate::{resp::RespValues, store::global_store, util::ok_reply};
-
-use super::Command;
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
 
 pub struct CommandSet {}
 
+#[derive(PartialEq)]
+enum Condition {
+	Always,
+	/// Only set the key if it does not exist yet.
+	Nx,
+	/// Only set the key if it already exists.
+	Xx,
+}
+
+pub enum Expiry {
+	/// The key does not expire, removing any expiry the key had before.
+	None,
+	/// Unix timestamp in milliseconds.
+	At(i64),
+	/// Retain the expiry the key had before.
+	KeepTtl,
+}
+
 impl Command for CommandSet {
-	async fn invoke(stream: &mut TcpStream, data: RespValues) {
-		match data {
-			RespValues::Array(a) => {
-				assert!(a.len() >= 3);
-				let key = match a.get(1).unwrap() {
-					RespValues::BulkString(b) => {
-						b.as_str().to_string()
-					},
-					d => panic!("set: Expected BulkString for key argument, got: '{}'", d),
-				};
-				let value = match a.get(2).unwrap() {
-					RespValues::BulkString(b) => {
-						b.as_str().to_string()
-					},
-					d => panic!("set: Expected BulkString for value argument, got: '{}'", d),
-				};
+	const SPEC: CommandSpec = CommandSpec {
+		name: "set",
+		summary: "Sets the string value of a key, ignoring its type. The key is created if it doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
 
-				let mut args = HashMap::new();
-				if a.len() >= 5 {
-					for i in (3..a.len()).step_by(2) {
-						let option_name = match a.get(i).unwrap() {
-							RespValues::BulkString(b) => {
-								b.as_str().to_string()
-							},
-							d => panic!("set: Expected BulkString for option name argument, got: '{}'", d),
-						};
-						let option_value = match a.get(i + 1).unwrap() {
-							RespValues::BulkString(b) => {
-								b.as_str().to_string()
-							},
-							d => panic!("set: Expected BulkString for option value argument, got: '{}'", d),
-						};
-						args.insert(option_name, option_value);
-					}
-				}
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let key = args[1].clone();
+		let value = args[2].clone();
+
+		let mut condition = Condition::Always;
+		let mut expiry = Expiry::None;
+		let mut get = false;
+		let mut i = 3;
+		while i < args.len() {
+			let option = args[i].to_ascii_uppercase();
+			let has_expiry = !matches!(expiry, Expiry::None);
+			match option.as_slice() {
+				b"NX" if condition != Condition::Xx => condition = Condition::Nx,
+				b"XX" if condition != Condition::Nx => condition = Condition::Xx,
+				b"GET" => get = true,
+				b"KEEPTTL" if !has_expiry => expiry = Expiry::KeepTtl,
+				b"EX" | b"PX" | b"EXAT" | b"PXAT" if !has_expiry && i + 1 < args.len() => {
+					expiry = Expiry::At(expire_time(&option, &args[i + 1], "set")?);
+					i += 1;
+				},
+				_ => return Err(CommandError::Syntax),
+			}
+			i += 1;
+		}
 
-				let expiry_time = if let Some(v) = args.get("px") {
-					match v.parse::<u64>() {
-						Ok(v) => Some(v),
-						Err(e) => panic!("set: Could not parse value ('{v}') for argument px, got: '{e}'"),
-					}
-				} else {
-					None
-				};
+		let (old_value, applies) = {
+			let mut store = server.store(connection.db());
+			let expires_at = match expiry {
+				Expiry::None => None,
+				Expiry::KeepTtl => store.expires_at(&key),
+				// Timestamps in the past expire the key right away.
+				Expiry::At(timestamp) => Some(timestamp),
+			};
 
-				global_store().set(key, value, expiry_time);
-				ok_reply(stream).await;
-			},
-			_ => eprintln!("Misformed set command: '{}'", data)
+			// SET overwrites values of any type, but only strings can be returned by GET.
+			let old_value = if get { store.get_string(&key)? } else { None };
+			let applies = match condition {
+				Condition::Always => true,
+				Condition::Nx => !store.has(&key),
+				Condition::Xx => store.has(&key),
+			};
+			if applies {
+				store.set(key, value, expires_at);
+			}
+			(old_value, applies)
+		};
+
+		if get {
+			let response = match old_value {
+				Some(v) => bulk_string_response(v),
+				None => null_response(),
+			};
+			respond(connection, response).await;
+		} else if applies {
+			ok_reply(connection).await;
+		} else {
+			null_reply(connection).await;
 		}
+		Ok(())
+	}
+}
+
+/// The Unix timestamp in milliseconds given by the value of an `EX`, `PX`, `EXAT` or `PXAT` option of `command`.
+pub fn expire_time(option: &[u8], value: &[u8], command: &str) -> Result<i64, CommandError> {
+	let invalid_expire_time = || CommandError::Other(format!("invalid expire time in '{command}' command"));
+	let value = parse_integer(value)?;
+	if value <= 0 {
+		return Err(invalid_expire_time());
+	}
+	let milliseconds = match option.to_ascii_uppercase().as_slice() {
+		b"EX" | b"EXAT" => value.checked_mul(1000).ok_or_else(invalid_expire_time)?,
+		_ => value,
+	};
+	match option.to_ascii_uppercase().as_slice() {
+		b"EX" | b"PX" => unix_time_ms().checked_add(milliseconds).ok_or_else(invalid_expire_time),
+		_ => Ok(milliseconds),
 	}
-}
\ No newline at end of file
+}
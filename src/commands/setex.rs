@@ -0,0 +1,29 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::ok_reply};
+
+use super::{set::expire_time, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSetex {}
+
+impl Command for CommandSetex {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "setex",
+		summary: "Sets the string value and expiration time of a key. Creates the key if it doesn't exist.",
+		since: "2.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let expires_at = expire_time(b"EX", &args[2], "setex")?;
+		server.store(connection.db()).set(args[1].clone(), args[3].clone(), Some(expires_at));
+		ok_reply(connection).await;
+		Ok(())
+	}
+}
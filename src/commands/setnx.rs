@@ -0,0 +1,35 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSetnx {}
+
+impl Command for CommandSetnx {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "setnx",
+		summary: "Set the string value of a key only when the key doesn't exist.",
+		since: "1.0.0",
+		group: CommandGroup::String,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let set = {
+			let mut store = server.store(connection.db());
+			let set = !store.has(&args[1]);
+			if set {
+				store.set(args[1].clone(), args[2].clone(), None);
+			}
+			set
+		};
+		respond(connection, integer_response(set as i64)).await;
+		Ok(())
+	}
+}
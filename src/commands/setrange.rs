@@ -0,0 +1,52 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{string::{check_string_length, RedisString}, Value}, util::{integer_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSetrange {}
+
+impl Command for CommandSetrange {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "setrange",
+		summary: "Overwrites a part of a string value with another by an offset. Creates the key if it doesn't exist.",
+		since: "2.2.0",
+		group: CommandGroup::String,
+		complexity: "O(1), not counting the time taken to copy the new string in place. Usually, this string is very small so the amortized complexity is O(1). Otherwise, complexity is O(M) with M being the length of the value argument.",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::String, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let offset = parse_integer(&args[2])?;
+		if offset < 0 {
+			return Err(CommandError::Other(String::from("offset is out of range")));
+		}
+		let offset = offset as usize;
+		let patch = &args[3];
+
+		let len = {
+			let mut store = server.store(connection.db());
+			let current = store.get_string(&args[1])?;
+			// An empty patch changes nothing, it does not even create the key.
+			if patch.is_empty() {
+				current.map_or(0, |v| v.len())
+			} else {
+				check_string_length(offset + patch.len())?;
+				let mut value = current.map(Vec::from).unwrap_or_default();
+				if value.len() < offset + patch.len() {
+					value.resize(offset + patch.len(), 0);
+				}
+				value[offset..offset + patch.len()].copy_from_slice(patch);
+				let len = value.len();
+				store.update(&args[1], Value::String(RedisString::raw(value.into())));
+				len
+			}
+		};
+		respond(connection, integer_response(len as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use bytes::Bytes;
+
+use crate::{connection::Connection, dict::Dict, server::Server, store::{Store, StoreValue}, types::{set::Set, Value}, util::{bulk_string_response, integer_response, respond, set_response}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSinter {}
+
+impl Command for CommandSinter {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "sinter",
+		summary: "Returns the intersect of multiple sets.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(N*M) worst case where N is the cardinality of the smallest set and M is the number of sets.",
+		arity: -2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Set, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		set_operation_generic(server, connection, &args[1..], None, SetOperation::Intersection).await
+	}
+}
+
+pub enum SetOperation {
+	Intersection,
+	Union,
+	Difference,
+}
+
+/// Implements `SINTER`, `SUNION`, `SDIFF` and their `STORE` variants, which store the result at `destination`.
+pub async fn set_operation_generic(server: &Server, connection: &mut Connection, keys: &[Bytes], destination: Option<&Bytes>, operation: SetOperation) -> Result<(), CommandError> {
+	let response = {
+		let mut store = server.store(connection.db());
+		let members = set_operation(&mut store, keys, operation, usize::MAX)?;
+		match destination {
+			Some(destination) => {
+				let len = members.len();
+				if members.is_empty() {
+					store.delete(destination);
+				} else {
+					let set = Set::from_members(&members, &server.config().encoding_limits);
+					store.insert(destination.clone(), StoreValue::new(Value::Set(set), None));
+				}
+				integer_response(len as i64)
+			},
+			None => set_response(members.into_iter().map(bulk_string_response).collect()),
+		}
+	};
+	respond(connection, response).await;
+	Ok(())
+}
+
+/// The members of the result of `operation` on the sets at `keys`, stopping after `limit` members.
+/// Missing keys count as empty sets.
+pub fn set_operation(store: &mut Store, keys: &[Bytes], operation: SetOperation, limit: usize) -> Result<Vec<Bytes>, CommandError> {
+	for key in keys {
+		if let Some(value) = store.get(key) {
+			value.as_set()?;
+		}
+	}
+	let empty = Set::Hashtable(Dict::new());
+	let sets: Vec<&Set> = keys.iter().map(|key| store.peek(key).and_then(|value| value.as_set().ok()).unwrap_or(&empty)).collect();
+	let members = match operation {
+		SetOperation::Intersection => {
+			// Walk the smallest set and look its members up in the others, starting with the next smallest.
+			let mut sets = sets;
+			sets.sort_by_key(|set| set.len());
+			sets[0].iter().filter(|member| sets[1..].iter().all(|set| set.contains(member))).take(limit).collect()
+		},
+		SetOperation::Union => {
+			let mut seen = HashSet::new();
+			sets.iter().flat_map(|set| set.iter()).filter(|member| seen.insert(member.clone())).take(limit).collect()
+		},
+		SetOperation::Difference => {
+			sets[0].iter().filter(|member| !sets[1..].iter().any(|set| set.contains(member))).take(limit).collect()
+		},
+	};
+	Ok(members)
+}
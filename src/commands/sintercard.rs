@@ -0,0 +1,48 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, parse_integer, respond}};
+
+use super::{sinter::{set_operation, SetOperation}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSintercard {}
+
+impl Command for CommandSintercard {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "sintercard",
+		summary: "Returns the number of members of the intersect of multiple sets.",
+		since: "7.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(N*M) worst case where N is the cardinality of the smallest set and M is the number of sets.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Keynum { index: 1, first: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Set, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let numkeys = parse_integer(&args[1])?;
+		if numkeys <= 0 {
+			return Err(CommandError::Other(String::from("numkeys should be greater than 0")));
+		}
+		let numkeys = numkeys as usize;
+		if numkeys > args.len() - 2 {
+			return Err(CommandError::Other(String::from("Number of keys can't be greater than number of args")));
+		}
+		// A limit of 0 means no limit.
+		let limit = match &args[numkeys + 2..] {
+			[] => usize::MAX,
+			[option, limit] if option.eq_ignore_ascii_case(b"LIMIT") => {
+				let limit = parse_integer(limit)?;
+				if limit < 0 {
+					return Err(CommandError::Other(String::from("LIMIT can't be negative")));
+				}
+				if limit == 0 { usize::MAX } else { limit as usize }
+			},
+			_ => return Err(CommandError::Syntax),
+		};
+		let len = set_operation(&mut server.store(connection.db()), &args[2..numkeys + 2], SetOperation::Intersection, limit)?.len();
+		respond(connection, integer_response(len as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSismember {}
+
+impl Command for CommandSismember {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "sismember",
+		summary: "Determines whether a member belongs to a set.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Set, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let is_member = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_set()?.contains(&args[2]),
+			None => false,
+		};
+		respond(connection, integer_response(is_member as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, respond, set_response}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSmembers {}
+
+impl Command for CommandSmembers {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "smembers",
+		summary: "Returns all members of a set.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(N) where N is the set cardinality.",
+		arity: 2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Set, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let members = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_set()?.iter().map(bulk_string_response).collect(),
+			None => Vec::new(),
+		};
+		respond(connection, set_response(members)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,34 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSmismember {}
+
+impl Command for CommandSmismember {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "smismember",
+		summary: "Determines whether multiple members belong to a set.",
+		since: "6.2.0",
+		group: CommandGroup::Set,
+		complexity: "O(N) where N is the number of elements being checked for membership",
+		arity: -3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Set, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let members = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let set = value.as_set()?;
+				args[2..].iter().map(|member| set.contains(member)).collect()
+			},
+			None => vec![false; args.len() - 2],
+		};
+		respond(connection, array_response(members.into_iter().map(|is_member| integer_response(is_member as i64)).collect())).await;
+		Ok(())
+	}
+}
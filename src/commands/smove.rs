@@ -0,0 +1,50 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{set::Set, Value}, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSmove {}
+
+impl Command for CommandSmove {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "smove",
+		summary: "Moves a member from one set to another.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(1)",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Insert] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Set, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let moved = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			// Check both types before modifying anything.
+			if let Some(value) = store.get(&args[2]) {
+				value.as_set()?;
+			}
+			match store.get_mut(&args[1]) {
+				Some(value) => {
+					let source = value.as_set_mut()?;
+					if args[1] == args[2] {
+						source.contains(&args[3])
+					} else if source.remove(&args[3]) {
+						store.delete_if_empty(&args[1]);
+						store.get_or_insert_with(&args[2], || Value::Set(Set::new(&args[3], 1, limits))).as_set_mut()?.insert(&args[3], limits);
+						true
+					} else {
+						false
+					}
+				},
+				None => false,
+			}
+		};
+		respond(connection, integer_response(moved as i64)).await;
+		Ok(())
+	}
+}
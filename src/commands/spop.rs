@@ -0,0 +1,68 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, null_response, parse_integer, respond, set_response}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSpop {}
+
+impl Command for CommandSpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "spop",
+		summary: "Returns one or more random members from a set after removing them. Deletes the set if the last member was popped.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "Without the count argument O(1), otherwise O(N) where N is the value of the passed count.",
+		arity: -2,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Set, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		if args.len() > 3 {
+			return Err(CommandError::Syntax);
+		}
+		let count = match args.get(2) {
+			Some(count) => {
+				let count = parse_integer(count)?;
+				if count < 0 {
+					return Err(CommandError::Other(String::from("value is out of range, must be positive")));
+				}
+				Some(count as usize)
+			},
+			None => None,
+		};
+
+		let members = {
+			let mut store = server.store(connection.db());
+			match store.get_mut(&args[1]) {
+				Some(value) => {
+					let set = value.as_set_mut()?;
+					let all = count.unwrap_or(1) >= set.len();
+					let members = if all {
+						set.iter().collect()
+					} else {
+						(0..count.unwrap_or(1)).filter_map(|_| {
+							let member = set.random()?;
+							set.remove(&member);
+							Some(member)
+						}).collect()
+					};
+					if all {
+						store.delete(&args[1]);
+					}
+					members
+				},
+				None => Vec::new(),
+			}
+		};
+		let response = match count {
+			Some(_) => set_response(members.into_iter().map(bulk_string_response).collect()),
+			None => members.into_iter().next().map_or_else(null_response, bulk_string_response),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,57 @@
+use bytes::Bytes;
+use rand::seq::SliceRandom;
+
+use crate::{connection::Connection, server::Server, util::{array_response, RandomSample, bulk_string_response, null_response, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSrandmember {}
+
+impl Command for CommandSrandmember {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "srandmember",
+		summary: "Get one or multiple random members from a set",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "Without the count argument O(1), otherwise O(N) where N is the absolute value of the passed count.",
+		arity: -2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Set, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		if args.len() > 3 {
+			return Err(CommandError::Syntax);
+		}
+		let count = args.get(2).map(|count| parse_integer(count)).transpose()?;
+		if count == Some(i64::MIN) {
+			return Err(CommandError::Other(format!("value is out of range, value must between {} and {}", -i64::MAX, i64::MAX)));
+		}
+		let sample = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let set = value.as_set()?;
+				match count {
+					None => RandomSample::Picked(set.random().into_iter().collect()),
+					// A negative count may return the same member multiple times.
+					Some(count) if count < 0 => RandomSample::new(count.unsigned_abs() as usize, set.len(), || set.random(), || set.iter().collect()),
+					Some(count) => {
+						let mut members: Vec<Bytes> = set.iter().collect();
+						members.shuffle(&mut rand::thread_rng());
+						members.truncate(count as usize);
+						RandomSample::Picked(members)
+					},
+				}
+			},
+			None => RandomSample::Picked(Vec::new()),
+		};
+		let members = sample.draw();
+		let response = match count {
+			Some(_) => array_response(members.into_iter().map(bulk_string_response).collect()),
+			None => members.into_iter().next().map_or_else(null_response, bulk_string_response),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSrem {}
+
+impl Command for CommandSrem {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "srem",
+		summary: "Removes one or more members from a set. Deletes the set if the last member was removed.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(N) where N is the number of members to be removed.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Set, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let removed = {
+			let mut store = server.store(connection.db());
+			match store.get_mut(&args[1]) {
+				Some(value) => {
+					let set = value.as_set_mut()?;
+					let removed = args[2..].iter().filter(|member| set.remove(member)).count();
+					store.delete_if_empty(&args[1]);
+					removed
+				},
+				None => 0,
+			}
+		};
+		respond(connection, integer_response(removed as i64)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{sinter::{set_operation_generic, SetOperation}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandSunion {}
+
+impl Command for CommandSunion {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "sunion",
+		summary: "Returns the union of multiple sets.",
+		since: "1.0.0",
+		group: CommandGroup::Set,
+		complexity: "O(N) where N is the total number of elements in all given sets.",
+		arity: -2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::Set, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		set_operation_generic(server, connection, &args[1..], None, SetOperation::Union).await
+	}
+}
@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandTouch {}
+
+impl Command for CommandTouch {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "touch",
+		summary: "Returns the number of existing keys out of those specified after updating the time they were last accessed.",
+		since: "3.2.1",
+		group: CommandGroup::Generic,
+		complexity: "O(N) where N is the number of keys that will be touched.",
+		arity: -2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: -1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let count = {
+			let mut store = server.store(connection.db());
+			args[1..].iter().filter(|key| store.has(key)).count()
+		};
+		respond(connection, integer_response(count as i64)).await;
+		Ok(())
+	}
+}
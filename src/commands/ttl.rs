@@ -0,0 +1,85 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::Store, util::{integer_response, respond, unix_time_ms}};
+
+use super::{expire::ExpireUnit, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandTtl {}
+
+impl Command for CommandTtl {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "ttl",
+		summary: "Returns the expiration time in seconds of a key.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		ttl_generic(server, connection, &args[1], ExpireUnit::Seconds, false).await
+	}
+}
+
+/// Implements `TTL`, `PTTL`, `EXPIRETIME` and `PEXPIRETIME`.
+pub async fn ttl_generic(server: &Server, connection: &mut Connection, key: &[u8], unit: ExpireUnit, absolute: bool) -> Result<(), CommandError> {
+	let reply = ttl(&mut server.store(connection.db()), key, unit, absolute);
+	respond(connection, integer_response(reply)).await;
+	Ok(())
+}
+
+/// The remaining time to live of `key`, or the Unix timestamp it expires at if `absolute` is set.
+/// -2 if the key does not exist and -1 if it has no expiry.
+fn ttl(store: &mut Store, key: &[u8], unit: ExpireUnit, absolute: bool) -> i64 {
+	if !store.has(key) {
+		return -2;
+	}
+	let Some(expires_at) = store.expires_at(key) else {
+		return -1;
+	};
+	let milliseconds = match absolute {
+		true => expires_at,
+		false => (expires_at - unix_time_ms()).max(0),
+	};
+	match unit {
+		ExpireUnit::Milliseconds => milliseconds,
+		// Both the remaining time and the timestamp are rounded to the closest second.
+		ExpireUnit::Seconds => (milliseconds + 500) / 1000,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::util::freeze_time_ms;
+
+	use super::*;
+
+	#[test]
+	fn replies_for_missing_keys_and_keys_without_expiry() {
+		let mut store = Store::init();
+		store.set(Bytes::from_static(b"persistent"), Bytes::from_static(b"value"), None);
+		for (unit, absolute) in [(ExpireUnit::Seconds, false), (ExpireUnit::Milliseconds, false), (ExpireUnit::Seconds, true), (ExpireUnit::Milliseconds, true)] {
+			assert_eq!(ttl(&mut store, b"missing", unit, absolute), -2);
+			assert_eq!(ttl(&mut store, b"persistent", unit, absolute), -1);
+		}
+	}
+
+	#[test]
+	fn replies_with_the_remaining_time_or_the_expire_time() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(10_000));
+		store.set(Bytes::from_static(b"key"), Bytes::from_static(b"value"), Some(12_600));
+		assert_eq!(ttl(&mut store, b"key", ExpireUnit::Milliseconds, false), 2600);
+		assert_eq!(ttl(&mut store, b"key", ExpireUnit::Seconds, false), 3);
+		assert_eq!(ttl(&mut store, b"key", ExpireUnit::Milliseconds, true), 12_600);
+		assert_eq!(ttl(&mut store, b"key", ExpireUnit::Seconds, true), 13);
+		freeze_time_ms(Some(12_600));
+		assert_eq!(ttl(&mut store, b"key", ExpireUnit::Milliseconds, false), 0);
+		freeze_time_ms(Some(12_601));
+		assert_eq!(ttl(&mut store, b"key", ExpireUnit::Milliseconds, false), -2);
+	}
+}
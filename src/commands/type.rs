@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{respond, simple_string_response}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandType {}
+
+impl Command for CommandType {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "type",
+		summary: "Determines the type of value stored at a key.",
+		since: "1.0.0",
+		group: CommandGroup::Generic,
+		complexity: "O(1)",
+		arity: 2,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro] }],
+		acl_categories: &[AclCategory::Keyspace, AclCategory::Read, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let type_name = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.type_name(),
+			None => "none",
+		};
+		respond(connection, simple_string_response(type_name)).await;
+		Ok(())
+	}
+}
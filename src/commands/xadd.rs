@@ -0,0 +1,224 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::Store, types::{stream::{Stream, StreamId}, Value}, util::{bulk_string_response, null_response, parse_integer, respond, unix_time_ms}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandXadd {}
+
+impl Command for CommandXadd {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "xadd",
+		summary: "Appends a new message to a stream. Creates the key if it doesn't exist.",
+		since: "5.0.0",
+		group: CommandGroup::Stream,
+		complexity: "O(1) when adding a new entry, O(N) when trimming where N being the number of entries evicted.",
+		arity: -5,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::Stream, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let mut create = true;
+		let mut trim = None;
+		let mut approximate = false;
+		let mut limit = None;
+		let mut i = 2;
+		loop {
+			let Some(arg) = args.get(i) else {
+				return Err(CommandError::Syntax);
+			};
+			match arg.to_ascii_uppercase().as_slice() {
+				b"NOMKSTREAM" => create = false,
+				option @ (b"MAXLEN" | b"MINID") if i + 1 < args.len() => {
+					if matches!(args[i + 1].as_ref(), b"=" | b"~") {
+						approximate = args[i + 1].as_ref() == b"~";
+						i += 1;
+					}
+					let Some(threshold) = args.get(i + 1) else {
+						return Err(CommandError::Syntax);
+					};
+					trim = Some(match option {
+						b"MAXLEN" => Trim::MaxLen(parse_integer(threshold)?.try_into()
+							.map_err(|_| CommandError::Other(String::from("The MAXLEN argument must be >= 0.")))?),
+						_ => Trim::MinId(StreamId::parse(threshold, 0).ok_or_else(invalid_stream_id)?),
+					});
+					i += 1;
+				},
+				b"LIMIT" if i + 1 < args.len() => {
+					limit = Some(parse_integer(&args[i + 1])?.try_into()
+						.map_err(|_| CommandError::Other(String::from("The LIMIT argument must be >= 0.")))?);
+					i += 1;
+				},
+				_ => break,
+			}
+			i += 1;
+		}
+		if limit.is_some() && !approximate {
+			return Err(CommandError::Other(String::from("syntax error, LIMIT cannot be used without the special ~ option")));
+		}
+		let id = NewId::parse(&args[i])?;
+		let fields = &args[i + 1..];
+		if fields.is_empty() || !fields.len().is_multiple_of(2) {
+			return Err(CommandError::WrongNumberOfArguments(String::from("xadd")));
+		}
+		let fields = fields.chunks(2).map(|pair| (pair[0].clone(), pair[1].clone())).collect();
+		// Without a limit, trimming approximately may remove any number of entries, which this does exactly.
+		let trim = trim.map(|trim| (trim, limit.unwrap_or(usize::MAX)));
+
+		let added = add_entry(&mut server.store(connection.db()), &args[1], id, fields, create, trim)?;
+		respond(connection, added.map_or_else(null_response, |id| bulk_string_response(id.to_string()))).await;
+		Ok(())
+	}
+}
+
+/// How `XADD` trims the stream after adding the entry.
+#[derive(Clone, Copy)]
+enum Trim {
+	/// Keep at most this many entries.
+	MaxLen(usize),
+	/// Remove the entries with smaller IDs.
+	MinId(StreamId),
+}
+
+/// The ID of the entry `XADD` adds.
+#[derive(Clone, Copy)]
+enum NewId {
+	/// `*`, generated from the clock.
+	Auto,
+	/// `<ms>-*`, with a generated sequence number.
+	AutoSeq(u64),
+	Explicit(StreamId),
+}
+
+impl NewId {
+	fn parse(arg: &[u8]) -> Result<NewId, CommandError> {
+		if arg == b"*" {
+			return Ok(NewId::Auto);
+		}
+		if let Some(ms) = arg.strip_suffix(b"-*") {
+			let id = StreamId::parse(ms, 0).filter(|_| !ms.contains(&b'-')).ok_or_else(invalid_stream_id)?;
+			return Ok(NewId::AutoSeq(id.ms));
+		}
+		StreamId::parse(arg, 0).map(NewId::Explicit).ok_or_else(invalid_stream_id)
+	}
+
+	/// The ID to add to `stream` with.
+	fn resolve(self, stream: &Stream) -> Result<StreamId, CommandError> {
+		let last_id = stream.last_id();
+		let id = match self {
+			NewId::Auto => {
+				let now = unix_time_ms().max(0) as u64;
+				return stream.next_id(now)
+					.ok_or_else(|| CommandError::Other(String::from("The stream has exhausted the last possible ID, unable to add more items")));
+			},
+			NewId::AutoSeq(ms) if ms == last_id.ms => last_id.next().filter(|id| id.ms == ms),
+			NewId::AutoSeq(ms) => Some(StreamId { ms, seq: 0 }),
+			NewId::Explicit(id) if id == StreamId::default() => {
+				return Err(CommandError::Other(String::from("The ID specified in XADD must be greater than 0-0")));
+			},
+			NewId::Explicit(id) => Some(id),
+		};
+		id.filter(|id| *id > last_id)
+			.ok_or_else(|| CommandError::Other(String::from("The ID specified in XADD is equal or smaller than the target stream top item")))
+	}
+}
+
+fn invalid_stream_id() -> CommandError {
+	CommandError::Other(String::from("Invalid stream ID specified as stream command argument"))
+}
+
+/// Adds an entry with `fields` to the stream at `key` and trims it with `trim` and its limit.
+/// Returns the ID of the entry, `None` if the stream does not exist and `create` is not set.
+fn add_entry(store: &mut Store, key: &[u8], id: NewId, fields: Vec<(Bytes, Bytes)>, create: bool, trim: Option<(Trim, usize)>) -> Result<Option<StreamId>, CommandError> {
+	// Check the ID before creating the stream, so a failed XADD does not leave an empty stream behind.
+	let id = match store.get(key) {
+		Some(value) => id.resolve(value.as_stream()?)?,
+		None if !create => return Ok(None),
+		None => id.resolve(&Stream::new())?,
+	};
+	let stream = store.get_or_insert_with(key, || Value::Stream(Stream::new())).as_stream_mut()?;
+	stream.add(id, fields);
+	match trim {
+		Some((Trim::MaxLen(maxlen), limit)) => stream.trim_to_len(maxlen, limit),
+		Some((Trim::MinId(min_id), limit)) => stream.trim_to_min_id(min_id, limit),
+		None => 0,
+	};
+	Ok(Some(id))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::util::freeze_time_ms;
+
+	use super::*;
+
+	fn xadd(store: &mut Store, id: &str) -> Result<Option<StreamId>, CommandError> {
+		add_entry(store, b"stream", NewId::parse(id.as_bytes())?, vec![(Bytes::from_static(b"field"), Bytes::from_static(b"value"))], true, None)
+	}
+
+	fn id(ms: u64, seq: u64) -> Option<StreamId> {
+		Some(StreamId { ms, seq })
+	}
+
+	fn error(message: &str) -> Result<Option<StreamId>, CommandError> {
+		Err(CommandError::Other(String::from(message)))
+	}
+
+	#[test]
+	fn adds_entries_with_explicit_ids() {
+		let mut store = Store::init();
+		assert_eq!(xadd(&mut store, "0-0"), error("The ID specified in XADD must be greater than 0-0"));
+		assert!(!store.has(b"stream"));
+		assert_eq!(xadd(&mut store, "5"), Ok(id(5, 0)));
+		assert_eq!(xadd(&mut store, "5-3"), Ok(id(5, 3)));
+		assert_eq!(xadd(&mut store, "5-3"), error("The ID specified in XADD is equal or smaller than the target stream top item"));
+		assert_eq!(xadd(&mut store, "4-9"), error("The ID specified in XADD is equal or smaller than the target stream top item"));
+		assert_eq!(xadd(&mut store, "5-x"), error("Invalid stream ID specified as stream command argument"));
+		let value = store.get(b"stream").unwrap();
+		assert_eq!((value.type_name(), value.encoding()), ("stream", "stream"));
+		assert_eq!(value.as_stream().unwrap().len(), 2);
+	}
+
+	#[test]
+	fn generates_ids() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		assert_eq!(xadd(&mut store, "*"), Ok(id(1000, 0)));
+		assert_eq!(xadd(&mut store, "*"), Ok(id(1000, 1)));
+		assert_eq!(xadd(&mut store, "1000-*"), Ok(id(1000, 2)));
+		assert_eq!(xadd(&mut store, "2000-*"), Ok(id(2000, 0)));
+		assert_eq!(xadd(&mut store, "1500-*"), error("The ID specified in XADD is equal or smaller than the target stream top item"));
+		// The clock is behind the last ID.
+		assert_eq!(xadd(&mut store, "*"), Ok(id(2000, 1)));
+		assert_eq!(xadd(&mut store, "0-*"), error("The ID specified in XADD is equal or smaller than the target stream top item"));
+		assert!(NewId::parse(b"1-2-*").is_err());
+		assert_eq!(xadd(&mut Store::init(), "0-*"), Ok(id(0, 1)));
+	}
+
+	#[test]
+	fn does_not_create_the_stream_with_nomkstream() {
+		let mut store = Store::init();
+		assert_eq!(add_entry(&mut store, b"stream", NewId::Auto, Vec::new(), false, None), Ok(None));
+		assert!(!store.has(b"stream"));
+		store.set(Bytes::from_static(b"string"), Bytes::from_static(b"value"), None);
+		assert_eq!(add_entry(&mut store, b"string", NewId::Auto, Vec::new(), true, None), Err(CommandError::WrongType));
+	}
+
+	#[test]
+	fn trims_after_adding() {
+		let mut store = Store::init();
+		for ms in 1..=5 {
+			add_entry(&mut store, b"stream", NewId::Explicit(StreamId { ms, seq: 0 }), Vec::new(), true, Some((Trim::MaxLen(3), usize::MAX))).unwrap();
+		}
+		assert_eq!(store.get(b"stream").unwrap().as_stream().unwrap().len(), 3);
+		let trim = Some((Trim::MinId(StreamId { ms: 5, seq: 0 }), 1));
+		add_entry(&mut store, b"stream", NewId::Explicit(StreamId { ms: 6, seq: 0 }), Vec::new(), true, trim).unwrap();
+		assert_eq!(store.get(b"stream").unwrap().as_stream().unwrap().len(), 3);
+		// Trimming all entries keeps the stream.
+		add_entry(&mut store, b"stream", NewId::Explicit(StreamId { ms: 7, seq: 0 }), Vec::new(), true, Some((Trim::MaxLen(0), usize::MAX))).unwrap();
+		assert!(store.has(b"stream"));
+	}
+}
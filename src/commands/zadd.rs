@@ -0,0 +1,137 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{zset::SortedSet, EncodingLimits, Value}, util::{double_response, integer_response, null_response, parse_float, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZadd {}
+
+impl Command for CommandZadd {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zadd",
+		summary: "Adds one or more members to a sorted set, or updates their scores. Creates the key if it doesn't exist.",
+		since: "1.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)) for each item added, where N is the number of elements in the sorted set.",
+		arity: -4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let mut options = ZaddOptions::default();
+		let mut index = 2;
+		while let Some(option) = args.get(index) {
+			match option.to_ascii_uppercase().as_slice() {
+				b"NX" => options.nx = true,
+				b"XX" => options.xx = true,
+				b"GT" => options.gt = true,
+				b"LT" => options.lt = true,
+				b"CH" => options.ch = true,
+				b"INCR" => options.incr = true,
+				_ => break,
+			}
+			index += 1;
+		}
+		let pairs = &args[index..];
+		if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+			return Err(CommandError::Syntax);
+		}
+		if options.nx && options.xx {
+			return Err(CommandError::Other(String::from("XX and NX options at the same time are not compatible")));
+		}
+		if [options.gt, options.lt, options.nx].iter().filter(|&&set| set).count() > 1 {
+			return Err(CommandError::Other(String::from("GT, LT, and/or NX options at the same time are not compatible")));
+		}
+		if options.incr && pairs.len() > 2 {
+			return Err(CommandError::Other(String::from("INCR option supports a single increment-element pair")));
+		}
+		// All scores are checked before any member is added.
+		let entries = pairs.chunks(2).map(|pair| Ok((parse_float(&pair[0])?, &pair[1]))).collect::<Result<Vec<_>, CommandError>>()?;
+
+		let (changed, score) = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			// XX never creates the key.
+			if options.xx && !store.has(&args[1]) {
+				(0, None)
+			} else {
+				let member_len = entries.iter().map(|(_, member)| member.len()).max().unwrap_or(0);
+				let zset = store.get_or_insert_with(&args[1], || Value::SortedSet(SortedSet::new(entries.len(), member_len, limits))).as_sorted_set_mut()?;
+				let mut changed = 0;
+				let mut score = None;
+				for (increment, member) in entries {
+					score = None;
+					if let Some(outcome) = add_member(zset, member, increment, &options, limits)? {
+						if outcome.added || (options.ch && outcome.updated) {
+							changed += 1;
+						}
+						score = Some(outcome.score);
+					}
+				}
+				store.delete_if_empty(&args[1]);
+				(changed, score)
+			}
+		};
+		let response = match options.incr {
+			true => score.map_or_else(null_response, double_response),
+			false => integer_response(changed),
+		};
+		respond(connection, response).await;
+		Ok(())
+	}
+}
+
+#[derive(Default)]
+pub struct ZaddOptions {
+	/// Only add new members.
+	pub nx: bool,
+	/// Only update existing members.
+	pub xx: bool,
+	/// Only update scores to greater ones.
+	pub gt: bool,
+	/// Only update scores to lower ones.
+	pub lt: bool,
+	/// Count updated members in the reply as well.
+	pub ch: bool,
+	/// Increment the score instead of setting it.
+	pub incr: bool,
+}
+
+pub struct AddOutcome {
+	pub added: bool,
+	pub updated: bool,
+	pub score: f64,
+}
+
+/// Adds `member` with `score` to `zset`, or updates its score, as allowed by `options`.
+/// Returns `None` if the options prevented the change.
+pub fn add_member(zset: &mut SortedSet, member: &[u8], score: f64, options: &ZaddOptions, limits: &EncodingLimits) -> Result<Option<AddOutcome>, CommandError> {
+	let Some(current) = zset.score(member) else {
+		if options.xx {
+			return Ok(None);
+		}
+		zset.insert(member, score, limits);
+		return Ok(Some(AddOutcome { added: true, updated: false, score }));
+	};
+	if options.nx {
+		return Ok(None);
+	}
+	let score = match options.incr {
+		true => current + score,
+		false => score,
+	};
+	if score.is_nan() {
+		return Err(CommandError::Other(String::from("resulting score is not a number (NaN)")));
+	}
+	if (options.gt && score <= current) || (options.lt && score >= current) {
+		return Ok(None);
+	}
+	let updated = score != current;
+	if updated {
+		zset.insert(member, score, limits);
+	}
+	Ok(Some(AddOutcome { added: false, updated, score }))
+}
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::zset::ScoreRange, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZcount {}
+
+impl Command for CommandZcount {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zcount",
+		summary: "Returns the count of members in a sorted set that have scores within a range.",
+		since: "2.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)) with N being the number of elements in the sorted set.",
+		arity: 4,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let range = ScoreRange::parse(&args[2], &args[3])?;
+		let count = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let zset = value.as_sorted_set()?;
+				let end = zset.count_before(|score, _| range.not_above(score));
+				end.saturating_sub(zset.count_before(|score, _| range.below(score)))
+			},
+			None => 0,
+		};
+		respond(connection, integer_response(count as i64)).await;
+		Ok(())
+	}
+}
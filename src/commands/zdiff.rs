@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{zunion::{zset_operation_generic, ZsetOperation}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZdiff {}
+
+impl Command for CommandZdiff {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zdiff",
+		summary: "Returns the difference between multiple sorted sets.",
+		since: "6.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(L + (N-K)log(N)) worst case where L is the total number of elements in all the sets, N is the size of the first set, and K is the size of the result set.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Keynum { index: 1, first: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		zset_operation_generic(server, connection, &args[1..], None, ZsetOperation::Difference, Self::SPEC.name).await
+	}
+}
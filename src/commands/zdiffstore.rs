@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{zunion::{zset_operation_generic, ZsetOperation}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZdiffstore {}
+
+impl Command for CommandZdiffstore {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zdiffstore",
+		summary: "Stores the difference of multiple sorted sets in a key.",
+		since: "6.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(L + (N-K)log(N)) worst case where L is the total number of elements in all the sets, N is the size of the first set, and K is the size of the result set.",
+		arity: -4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }, KeySpec::Keynum { index: 2, first: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		zset_operation_generic(server, connection, &args[2..], Some(&args[1]), ZsetOperation::Difference, Self::SPEC.name).await
+	}
+}
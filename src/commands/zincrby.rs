@@ -0,0 +1,38 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, types::{zset::SortedSet, Value}, util::{double_response, parse_float, respond}};
+
+use super::{zadd::{add_member, ZaddOptions}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZincrby {}
+
+impl Command for CommandZincrby {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zincrby",
+		summary: "Increments the score of a member in a sorted set.",
+		since: "1.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)) where N is the number of elements in the sorted set.",
+		arity: 4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Update] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let increment = parse_float(&args[2])?;
+		let score = {
+			let mut store = server.store(connection.db());
+			let limits = &server.config().encoding_limits;
+			let zset = store.get_or_insert_with(&args[1], || Value::SortedSet(SortedSet::new(1, args[3].len(), limits))).as_sorted_set_mut()?;
+			let options = ZaddOptions { incr: true, ..Default::default() };
+			let outcome = add_member(zset, &args[3], increment, &options, limits);
+			store.delete_if_empty(&args[1]);
+			outcome?.map(|outcome| outcome.score)
+		};
+		// Without other options an increment is always applied.
+		respond(connection, double_response(score.unwrap_or_default())).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{zunion::{zset_operation_generic, ZsetOperation}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZinter {}
+
+impl Command for CommandZinter {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zinter",
+		summary: "Returns the intersect of multiple sorted sets.",
+		since: "6.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(N*K)+O(M*log(M)) worst case with N being the smallest input sorted set, K being the number of input sorted sets and M being the number of elements in the resulting sorted set.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Keynum { index: 1, first: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		zset_operation_generic(server, connection, &args[1..], None, ZsetOperation::Intersection, Self::SPEC.name).await
+	}
+}
@@ -0,0 +1,54 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, parse_integer, respond}};
+
+use super::{zunion::{zset_operation, Aggregate, ZsetOperation, ZsetOperationInputs}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZintercard {}
+
+impl Command for CommandZintercard {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zintercard",
+		summary: "Returns the number of members of the intersect of multiple sorted sets.",
+		since: "7.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(N*K) worst case with N being the smallest input sorted set, K being the number of input sorted sets.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Keynum { index: 1, first: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let numkeys = parse_integer(&args[1])?;
+		if numkeys <= 0 {
+			return Err(CommandError::Other(String::from("numkeys should be greater than 0")));
+		}
+		let numkeys = numkeys as usize;
+		if numkeys > args.len() - 2 {
+			return Err(CommandError::Other(String::from("Number of keys can't be greater than number of args")));
+		}
+		// A limit of 0 means no limit.
+		let limit = match &args[numkeys + 2..] {
+			[] => usize::MAX,
+			[option, limit] if option.eq_ignore_ascii_case(b"LIMIT") => {
+				let limit = parse_integer(limit)?;
+				if limit < 0 {
+					return Err(CommandError::Other(String::from("LIMIT can't be negative")));
+				}
+				if limit == 0 { usize::MAX } else { limit as usize }
+			},
+			_ => return Err(CommandError::Syntax),
+		};
+		let inputs = ZsetOperationInputs {
+			keys: args[2..numkeys + 2].to_vec(),
+			weights: vec![1.0; numkeys],
+			aggregate: Aggregate::Sum,
+			with_scores: false,
+		};
+		let len = zset_operation(&mut server.store(connection.db()), &inputs, ZsetOperation::Intersection, limit)?.len();
+		respond(connection, integer_response(len as i64)).await;
+		Ok(())
+	}
+}
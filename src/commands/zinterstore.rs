@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{zunion::{zset_operation_generic, ZsetOperation}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZinterstore {}
+
+impl Command for CommandZinterstore {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zinterstore",
+		summary: "Stores the intersect of multiple sorted sets in a key.",
+		since: "2.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(N*K)+O(M*log(M)) worst case with N being the smallest input sorted set, K being the number of input sorted sets and M being the number of elements in the resulting sorted set.",
+		arity: -4,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }, KeySpec::Keynum { index: 2, first: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		zset_operation_generic(server, connection, &args[2..], Some(&args[1]), ZsetOperation::Intersection, Self::SPEC.name).await
+	}
+}
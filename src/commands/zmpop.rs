@@ -0,0 +1,67 @@
+use bytes::Bytes;
+
+use crate::{blocking::ServeFn, connection::Connection, server::Server, store::Store, util::{array_response, bulk_string_response, double_response, null_array_response, respond}};
+
+use super::{lmpop::parse_mpop_arguments, zpopmin::pop_members, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZmpop {}
+
+impl Command for CommandZmpop {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zmpop",
+		summary: "Returns the highest- or lowest-scoring members from one or more sorted sets after removing them. Deletes the sorted set if the last member was popped.",
+		since: "7.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(K) + O(M*log(N)) where K is the number of provided keys, N being the number of elements in the sorted set, and M being the number of elements popped.",
+		arity: -4,
+		flags: &[CommandFlag::Write],
+		keys: &[KeySpec::Keynum { index: 1, first: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let (keys, direction, count) = parse_mpop_arguments(&args[1..])?;
+		let mut serve = pop_many(parse_min_max(direction)?, count);
+		let mut response = None;
+		{
+			let mut store = server.store(connection.db());
+			for key in &keys {
+				if let Some(value) = store.get(key) {
+					value.as_sorted_set()?;
+				}
+				response = serve(&mut store, key);
+				if response.is_some() {
+					break;
+				}
+			}
+		}
+		respond(connection, response.unwrap_or(Ok(null_array_response()))?).await;
+		Ok(())
+	}
+}
+
+/// Parses the `MIN | MAX` argument of `ZMPOP` and `BZMPOP`. Returns whether the highest scores are popped.
+pub fn parse_min_max(arg: &[u8]) -> Result<bool, CommandError> {
+	match arg.to_ascii_uppercase().as_slice() {
+		b"MIN" => Ok(false),
+		b"MAX" => Ok(true),
+		_ => Err(CommandError::Syntax),
+	}
+}
+
+/// Pops up to `count` members with the lowest or, if `max` is set, the highest scores from a sorted set
+/// and replies with the key and the members and their scores.
+pub fn pop_many(max: bool, count: usize) -> ServeFn {
+	Box::new(move |store: &mut Store, key: &Bytes| {
+		store.get(key)?.as_sorted_set().ok()?;
+		let members = pop_members(store, key, count, max).ok()?;
+		if members.is_empty() {
+			return None;
+		}
+		Some(Ok(array_response(vec![
+			bulk_string_response(key.clone()),
+			array_response(members.into_iter().map(|(member, score)| array_response(vec![bulk_string_response(member), double_response(score)])).collect()),
+		])))
+	})
+}
@@ -0,0 +1,35 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, double_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZmscore {}
+
+impl Command for CommandZmscore {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zmscore",
+		summary: "Returns the score of one or more members in a sorted set.",
+		since: "6.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(N) where N is the number of members being requested.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let scores = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let zset = value.as_sorted_set()?;
+				args[2..].iter().map(|member| zset.score(member)).collect()
+			},
+			None => vec![None; args.len() - 2],
+		};
+		let response = array_response(scores.into_iter().map(|score| score.map_or_else(null_response, double_response)).collect());
+		respond(connection, response).await;
+		Ok(())
+	}
+}
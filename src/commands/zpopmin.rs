@@ -0,0 +1,67 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::Store, util::{array_response, bulk_string_response, double_response, parse_integer, respond}};
+
+use super::{zrange::members_response, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZpopmin {}
+
+impl Command for CommandZpopmin {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zpopmin",
+		summary: "Returns the lowest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+		since: "5.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)*M) with N being the number of elements in the sorted set, and M being the number of elements popped.",
+		arity: -2,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Access, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		pop_generic(server, connection, args, false).await
+	}
+}
+
+/// Implements `ZPOPMIN` and, popping the highest scores, `ZPOPMAX`.
+pub async fn pop_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, max: bool) -> Result<(), CommandError> {
+	if args.len() > 3 {
+		return Err(CommandError::Syntax);
+	}
+	let count = match args.get(2) {
+		Some(count) => {
+			let count = parse_integer(count)?;
+			if count < 0 {
+				return Err(CommandError::Other(String::from("value is out of range, must be positive")));
+			}
+			Some(count as usize)
+		},
+		None => None,
+	};
+	let members = pop_members(&mut server.store(connection.db()), &args[1], count.unwrap_or(1), max)?;
+	let response = match count {
+		Some(_) => members_response(members, true, connection.protocol()),
+		// Without a count, RESP3 replies with a flat array as well.
+		None => array_response(members.into_iter().flat_map(|(member, score)| [bulk_string_response(member), double_response(score)]).collect()),
+	};
+	respond(connection, response).await;
+	Ok(())
+}
+
+/// Removes up to `count` members with the lowest or, if `max` is set, the highest scores from the sorted set at `key`.
+/// Deletes the key once it is empty.
+pub fn pop_members(store: &mut Store, key: &[u8], count: usize, max: bool) -> Result<Vec<(Bytes, f64)>, CommandError> {
+	let Some(value) = store.get_mut(key) else {
+		return Ok(Vec::new());
+	};
+	let zset = value.as_sorted_set_mut()?;
+	let start = if max { zset.len().saturating_sub(1) } else { 0 };
+	let members = zset.range(start, count, max);
+	for (member, _) in &members {
+		zset.remove(member);
+	}
+	store.delete_if_empty(key);
+	Ok(members)
+}
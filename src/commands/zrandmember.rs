@@ -0,0 +1,63 @@
+use bytes::Bytes;
+use rand::seq::SliceRandom;
+
+use crate::{connection::Connection, server::Server, util::{bulk_string_response, RandomSample, null_response, parse_integer, respond}};
+
+use super::{zrange::members_response, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZrandmember {}
+
+impl Command for CommandZrandmember {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zrandmember",
+		summary: "Returns one or more random members from a sorted set.",
+		since: "6.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(N) where N is the number of members returned",
+		arity: -2,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let count = args.get(2).map(|count| parse_integer(count)).transpose()?;
+		let with_scores = match args.get(3) {
+			Some(option) if args.len() == 4 && option.eq_ignore_ascii_case(b"WITHSCORES") => true,
+			None => false,
+			_ => return Err(CommandError::Syntax),
+		};
+		let Some(count) = count else {
+			let member = match server.store(connection.db()).get(&args[1]) {
+				Some(value) => value.as_sorted_set()?.random().map(|(member, _)| member),
+				None => None,
+			};
+			respond(connection, member.map_or_else(null_response, bulk_string_response)).await;
+			return Ok(());
+		};
+		let max = if with_scores { i64::MAX / 2 } else { i64::MAX };
+		if count.unsigned_abs() > max as u64 {
+			return Err(CommandError::Other(format!("value is out of range, value must between {} and {}", -max, max)));
+		}
+
+		let sample = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let zset = value.as_sorted_set()?;
+				if count < 0 {
+					// A negative count may return the same member multiple times.
+					RandomSample::new(count.unsigned_abs() as usize, zset.len(), || zset.random(), || zset.iter().collect())
+				} else {
+					let mut members: Vec<(Bytes, f64)> = zset.iter().collect();
+					members.shuffle(&mut rand::thread_rng());
+					members.truncate(count as usize);
+					RandomSample::Picked(members)
+				}
+			},
+			None => RandomSample::Picked(Vec::new()),
+		};
+		let members = sample.draw();
+		respond(connection, members_response(members, with_scores, connection.protocol())).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,130 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, resp::{RespProtocol, RespValues}, server::Server, types::zset::{LexRange, ScoreRange, SortedSet}, util::{array_response, bulk_string_response, double_response, normalize_range, parse_integer, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZrange {}
+
+impl Command for CommandZrange {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zrange",
+		summary: "Returns members in a sorted set within a range of indexes.",
+		since: "1.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements returned.",
+		arity: -4,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let (query, with_scores) = RangeQuery::parse(&args[2..], true)?;
+		let members = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => query.members(value.as_sorted_set()?),
+			None => Vec::new(),
+		};
+		respond(connection, members_response(members, with_scores, connection.protocol())).await;
+		Ok(())
+	}
+}
+
+enum RangeBy {
+	Rank(i64, i64),
+	Score(ScoreRange),
+	Lex(LexRange),
+}
+
+/// The members selected by the `start stop [BYSCORE | BYLEX] [REV] [LIMIT offset count]` arguments of `ZRANGE` and `ZRANGESTORE`.
+pub struct RangeQuery {
+	by: RangeBy,
+	reverse: bool,
+	/// The offset and the count of `LIMIT`. A negative count means all members from the offset on.
+	limit: Option<(i64, i64)>,
+}
+
+impl RangeQuery {
+	/// Parses the arguments from `start` on. Returns whether `WITHSCORES` was given, which is only accepted if `allow_with_scores` is set.
+	pub fn parse(args: &[Bytes], allow_with_scores: bool) -> Result<(RangeQuery, bool), CommandError> {
+		let mut by_score = false;
+		let mut by_lex = false;
+		let mut reverse = false;
+		let mut limit = None;
+		let mut with_scores = false;
+		let mut index = 2;
+		while let Some(option) = args.get(index) {
+			match option.to_ascii_uppercase().as_slice() {
+				b"BYSCORE" => (by_score, by_lex) = (true, false),
+				b"BYLEX" => (by_score, by_lex) = (false, true),
+				b"REV" => reverse = true,
+				b"WITHSCORES" if allow_with_scores => with_scores = true,
+				b"LIMIT" if index + 2 < args.len() => {
+					limit = Some((parse_integer(&args[index + 1])?, parse_integer(&args[index + 2])?));
+					index += 2;
+				},
+				_ => return Err(CommandError::Syntax),
+			}
+			index += 1;
+		}
+		if limit.is_some() && !by_score && !by_lex {
+			return Err(CommandError::Other(String::from("syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX")));
+		}
+		if with_scores && by_lex {
+			return Err(CommandError::Other(String::from("syntax error, WITHSCORES not supported in combination with BYLEX")));
+		}
+		// Reversed score and lex ranges are given from the highest to the lowest bound.
+		let (min, max) = if reverse && (by_score || by_lex) { (&args[1], &args[0]) } else { (&args[0], &args[1]) };
+		let by = if by_score {
+			RangeBy::Score(ScoreRange::parse(min, max)?)
+		} else if by_lex {
+			RangeBy::Lex(LexRange::parse(min, max)?)
+		} else {
+			RangeBy::Rank(parse_integer(min)?, parse_integer(max)?)
+		};
+		Ok((RangeQuery { by, reverse, limit }, with_scores))
+	}
+
+	/// The selected members of `zset` and their scores, in the order they are returned.
+	pub fn members(&self, zset: &SortedSet) -> Vec<(Bytes, f64)> {
+		let (first, end) = match &self.by {
+			RangeBy::Rank(start, stop) => {
+				let Some((start, count)) = normalize_range(*start, *stop, zset.len()) else {
+					return Vec::new();
+				};
+				// Reversed ranks count from the highest score.
+				return match self.reverse {
+					false => zset.range(start, count, false),
+					true => zset.range(zset.len() - 1 - start, count, true),
+				};
+			},
+			RangeBy::Score(range) => (zset.count_before(|score, _| range.below(score)), zset.count_before(|score, _| range.not_above(score))),
+			RangeBy::Lex(range) => (zset.count_before(|_, member| range.below(member)), zset.count_before(|_, member| range.not_above(member))),
+		};
+		let (offset, count) = match self.limit {
+			Some((offset, _)) if offset < 0 => return Vec::new(),
+			Some((offset, count)) => (offset as usize, usize::try_from(count).unwrap_or(usize::MAX)),
+			None => (0, usize::MAX),
+		};
+		let len = end.saturating_sub(first);
+		if offset >= len {
+			return Vec::new();
+		}
+		let count = count.min(len - offset);
+		match self.reverse {
+			false => zset.range(first + offset, count, false),
+			true => zset.range(end - 1 - offset, count, true),
+		}
+	}
+}
+
+/// Replies with `members`, and with their scores if `with_scores` is set.
+/// RESP3 replies with an array of member and score pairs, RESP2 with a flat array.
+pub fn members_response(members: Vec<(Bytes, f64)>, with_scores: bool, protocol: RespProtocol) -> RespValues {
+	match with_scores {
+		true if protocol == RespProtocol::Resp3 => array_response(members.into_iter().map(|(member, score)| array_response(vec![bulk_string_response(member), double_response(score)])).collect()),
+		true => array_response(members.into_iter().flat_map(|(member, score)| [bulk_string_response(member), double_response(score)]).collect()),
+		false => array_response(members.into_iter().map(|(member, _)| bulk_string_response(member)).collect()),
+	}
+}
@@ -0,0 +1,42 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, store::StoreValue, types::{zset::SortedSet, Value}, util::{integer_response, respond}};
+
+use super::{zrange::RangeQuery, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZrangestore {}
+
+impl Command for CommandZrangestore {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zrangestore",
+		summary: "Stores a range of members from sorted set in a key.",
+		since: "6.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N)+M) with N being the number of elements in the sorted set and M the number of elements stored into the destination key.",
+		arity: -5,
+		flags: &[CommandFlag::Write, CommandFlag::DenyOom],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ow, KeyFlag::Update] }, KeySpec::Range { first: 2, last: 2, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let (query, _) = RangeQuery::parse(&args[3..], false)?;
+		let len = {
+			let mut store = server.store(connection.db());
+			let members = match store.get(&args[2]) {
+				Some(value) => query.members(value.as_sorted_set()?),
+				None => Vec::new(),
+			};
+			if members.is_empty() {
+				store.delete(&args[1]);
+			} else {
+				let zset = SortedSet::from_entries(&members, &server.config().encoding_limits);
+				store.insert(args[1].clone(), StoreValue::new(Value::SortedSet(zset), None));
+			}
+			members.len()
+		};
+		respond(connection, integer_response(len as i64)).await;
+		Ok(())
+	}
+}
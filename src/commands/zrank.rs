@@ -0,0 +1,53 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{array_response, double_response, integer_response, null_array_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZrank {}
+
+impl Command for CommandZrank {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zrank",
+		summary: "Returns the index of a member in a sorted set ordered by ascending scores.",
+		since: "2.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N))",
+		arity: -3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		rank_generic(server, connection, args, false).await
+	}
+}
+
+/// Implements `ZRANK` and, counting from the highest score, `ZREVRANK`.
+pub async fn rank_generic(server: &Server, connection: &mut Connection, args: Vec<Bytes>, reverse: bool) -> Result<(), CommandError> {
+	let with_score = match args.get(3) {
+		Some(option) if args.len() == 4 && option.eq_ignore_ascii_case(b"WITHSCORE") => true,
+		None => false,
+		_ => return Err(CommandError::Syntax),
+	};
+	let ranked = match server.store(connection.db()).get(&args[1]) {
+		Some(value) => {
+			let zset = value.as_sorted_set()?;
+			zset.rank(&args[2]).map(|rank| {
+				let rank = if reverse { zset.len() - 1 - rank } else { rank };
+				(rank, zset.score(&args[2]).unwrap_or_default())
+			})
+		},
+		None => None,
+	};
+	let response = match (ranked, with_score) {
+		(Some((rank, score)), true) => array_response(vec![integer_response(rank as i64), double_response(score)]),
+		(Some((rank, _)), false) => integer_response(rank as i64),
+		(None, true) => null_array_response(),
+		(None, false) => null_response(),
+	};
+	respond(connection, response).await;
+	Ok(())
+}
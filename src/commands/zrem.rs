@@ -0,0 +1,39 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{integer_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZrem {}
+
+impl Command for CommandZrem {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zrem",
+		summary: "Removes one or more members from a sorted set. Deletes the sorted set if all members were removed.",
+		since: "1.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(M*log(N)) with N being the number of elements in the sorted set and M the number of elements to be removed.",
+		arity: -3,
+		flags: &[CommandFlag::Write, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Rw, KeyFlag::Delete] }],
+		acl_categories: &[AclCategory::Write, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let removed = {
+			let mut store = server.store(connection.db());
+			let removed = match store.get_mut(&args[1]) {
+				Some(value) => {
+					let zset = value.as_sorted_set_mut()?;
+					args[2..].iter().filter(|member| zset.remove(member)).count()
+				},
+				None => 0,
+			};
+			store.delete_if_empty(&args[1]);
+			removed
+		};
+		respond(connection, integer_response(removed as i64)).await;
+		Ok(())
+	}
+}
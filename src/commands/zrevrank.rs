@@ -0,0 +1,26 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server};
+
+use super::{zrank::rank_generic, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZrevrank {}
+
+impl Command for CommandZrevrank {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zrevrank",
+		summary: "Returns the index of a member in a sorted set ordered by descending scores.",
+		since: "2.0.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(log(N))",
+		arity: -3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		rank_generic(server, connection, args, true).await
+	}
+}
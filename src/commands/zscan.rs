@@ -0,0 +1,49 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, resp::double::format_double, server::Server, util::{bulk_string_response, respond}};
+
+use super::{scan::{parse_cursor, scan_response, ScanOptions}, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZscan {}
+
+impl Command for CommandZscan {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zscan",
+		summary: "Iterates over members and scores of a sorted set.",
+		since: "2.8.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(1) for every call. O(N) for a complete iteration, including enough command calls for the cursor to return back to 0. N is the number of elements inside the collection.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let cursor = parse_cursor(&args[2])?;
+		let options = ScanOptions::parse(&args[3..], false, false)?;
+
+		let mut elements = Vec::new();
+		let cursor = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => {
+				let zset = value.as_sorted_set()?;
+				options.scan(cursor, |cursor| {
+					let mut visited = 0;
+					let cursor = zset.scan(cursor, |member, score| {
+						visited += 1;
+						if options.matches(&member) {
+							elements.push(bulk_string_response(member));
+							// Scores are bulk strings even in RESP3, like in Redis.
+							elements.push(bulk_string_response(format_double(score)));
+						}
+					});
+					(cursor, visited)
+				})
+			},
+			None => 0,
+		};
+		respond(connection, scan_response(cursor, elements)).await;
+		Ok(())
+	}
+}
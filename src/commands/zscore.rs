@@ -0,0 +1,31 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, server::Server, util::{double_response, null_response, respond}};
+
+use super::{AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZscore {}
+
+impl Command for CommandZscore {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zscore",
+		summary: "Returns the score of a member in a sorted set.",
+		since: "1.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(1)",
+		arity: 3,
+		flags: &[CommandFlag::Readonly, CommandFlag::Fast],
+		keys: &[KeySpec::Range { first: 1, last: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Fast],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		let score = match server.store(connection.db()).get(&args[1]) {
+			Some(value) => value.as_sorted_set()?.score(&args[2]),
+			None => None,
+		};
+		respond(connection, score.map_or_else(null_response, double_response)).await;
+		Ok(())
+	}
+}
@@ -0,0 +1,219 @@
+use bytes::Bytes;
+
+use crate::{connection::Connection, dict::Dict, server::Server, store::{Store, StoreValue}, types::{set::Set, skiplist::compare, zset::SortedSet, Value}, util::{integer_response, parse_float, parse_integer, respond}};
+
+use super::{zrange::members_response, AclCategory, Command, CommandError, CommandFlag, CommandGroup, CommandSpec, KeyFlag, KeySpec};
+
+pub struct CommandZunion {}
+
+impl Command for CommandZunion {
+	const SPEC: CommandSpec = CommandSpec {
+		name: "zunion",
+		summary: "Returns the union of multiple sorted sets.",
+		since: "6.2.0",
+		group: CommandGroup::SortedSet,
+		complexity: "O(N)+O(M*log(M)) with N being the sum of the sizes of the input sorted sets, and M being the number of elements in the resulting sorted set.",
+		arity: -3,
+		flags: &[CommandFlag::Readonly],
+		keys: &[KeySpec::Keynum { index: 1, first: 1, step: 1, flags: &[KeyFlag::Ro, KeyFlag::Access] }],
+		acl_categories: &[AclCategory::Read, AclCategory::SortedSet, AclCategory::Slow],
+		subcommands: &[],
+	};
+
+	async fn invoke(server: &Server, connection: &mut Connection, args: Vec<Bytes>) -> Result<(), CommandError> {
+		zset_operation_generic(server, connection, &args[1..], None, ZsetOperation::Union, Self::SPEC.name).await
+	}
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ZsetOperation {
+	Union,
+	Intersection,
+	Difference,
+}
+
+/// How the scores of a member in several inputs are combined.
+#[derive(Clone, Copy)]
+pub enum Aggregate {
+	Sum,
+	Min,
+	Max,
+}
+
+impl Aggregate {
+	fn apply(self, score: f64, other: f64) -> f64 {
+		match self {
+			// inf and -inf add up to NaN, which is not a valid score.
+			Aggregate::Sum => zero_if_nan(score + other),
+			Aggregate::Min => score.min(other),
+			Aggregate::Max => score.max(other),
+		}
+	}
+}
+
+fn zero_if_nan(score: f64) -> f64 {
+	if score.is_nan() { 0.0 } else { score }
+}
+
+/// The inputs of an operation, parsed from `numkeys key [key ...] [WEIGHTS weight [weight ...]] [AGGREGATE SUM | MIN | MAX] [WITHSCORES]`.
+pub struct ZsetOperationInputs {
+	pub keys: Vec<Bytes>,
+	/// The factor the scores of each input are multiplied with.
+	pub weights: Vec<f64>,
+	pub aggregate: Aggregate,
+	pub with_scores: bool,
+}
+
+impl ZsetOperationInputs {
+	/// Parses the arguments from `numkeys` on. `WEIGHTS` and `AGGREGATE` are not accepted for differences,
+	/// `WITHSCORES` only if `allow_with_scores` is set. `name` is the command, for the error on missing keys.
+	pub fn parse(args: &[Bytes], operation: ZsetOperation, allow_with_scores: bool, name: &str) -> Result<ZsetOperationInputs, CommandError> {
+		let numkeys = parse_integer(&args[0])?;
+		if numkeys <= 0 {
+			return Err(CommandError::Other(format!("at least 1 input key is needed for '{}' command", name)));
+		}
+		let numkeys = numkeys as usize;
+		if numkeys > args.len() - 1 {
+			return Err(CommandError::Syntax);
+		}
+		let mut inputs = ZsetOperationInputs {
+			keys: args[1..=numkeys].to_vec(),
+			weights: vec![1.0; numkeys],
+			aggregate: Aggregate::Sum,
+			with_scores: false,
+		};
+		let mut index = numkeys + 1;
+		while let Some(option) = args.get(index) {
+			let remaining = args.len() - index - 1;
+			match option.to_ascii_uppercase().as_slice() {
+				b"WEIGHTS" if operation != ZsetOperation::Difference && remaining >= numkeys => {
+					for (weight, arg) in inputs.weights.iter_mut().zip(&args[index + 1..]) {
+						*weight = parse_float(arg).map_err(|_| CommandError::Other(String::from("weight value is not a float")))?;
+					}
+					index += numkeys;
+				},
+				b"AGGREGATE" if operation != ZsetOperation::Difference && remaining >= 1 => {
+					inputs.aggregate = match args[index + 1].to_ascii_uppercase().as_slice() {
+						b"SUM" => Aggregate::Sum,
+						b"MIN" => Aggregate::Min,
+						b"MAX" => Aggregate::Max,
+						_ => return Err(CommandError::Syntax),
+					};
+					index += 1;
+				},
+				b"WITHSCORES" if allow_with_scores => inputs.with_scores = true,
+				_ => return Err(CommandError::Syntax),
+			}
+			index += 1;
+		}
+		Ok(inputs)
+	}
+}
+
+/// Implements `ZUNION`, `ZINTER`, `ZDIFF` and their `STORE` variants, which store the result at `destination`.
+/// `args` start at `numkeys`.
+pub async fn zset_operation_generic(server: &Server, connection: &mut Connection, args: &[Bytes], destination: Option<&Bytes>, operation: ZsetOperation, name: &str) -> Result<(), CommandError> {
+	let inputs = ZsetOperationInputs::parse(args, operation, destination.is_none(), name)?;
+	let response = {
+		let mut store = server.store(connection.db());
+		let members = zset_operation(&mut store, &inputs, operation, usize::MAX)?;
+		match destination {
+			Some(destination) => {
+				let len = members.len();
+				if members.is_empty() {
+					store.delete(destination);
+				} else {
+					let zset = SortedSet::from_entries(&members, &server.config().encoding_limits);
+					store.insert(destination.clone(), StoreValue::new(Value::SortedSet(zset), None));
+				}
+				integer_response(len as i64)
+			},
+			None => members_response(members, inputs.with_scores, connection.protocol()),
+		}
+	};
+	respond(connection, response).await;
+	Ok(())
+}
+
+/// An input of an operation. The members of plain sets have a score of 1, missing keys are empty.
+enum Input<'a> {
+	Set(&'a Set),
+	SortedSet(&'a SortedSet),
+	Empty,
+}
+
+impl Input<'_> {
+	fn len(&self) -> usize {
+		match self {
+			Input::Set(set) => set.len(),
+			Input::SortedSet(zset) => zset.len(),
+			Input::Empty => 0,
+		}
+	}
+
+	fn score(&self, member: &[u8]) -> Option<f64> {
+		match self {
+			Input::Set(set) => set.contains(member).then_some(1.0),
+			Input::SortedSet(zset) => zset.score(member),
+			Input::Empty => None,
+		}
+	}
+
+	fn iter(&self) -> Box<dyn Iterator<Item = (Bytes, f64)> + '_> {
+		match self {
+			Input::Set(set) => Box::new(set.iter().map(|member| (member, 1.0))),
+			Input::SortedSet(zset) => zset.iter(),
+			Input::Empty => Box::new(std::iter::empty()),
+		}
+	}
+}
+
+/// The members of the result of `operation` on `inputs` and their scores, ordered by score.
+/// Stops after `limit` members, before ordering them.
+pub fn zset_operation(store: &mut Store, inputs: &ZsetOperationInputs, operation: ZsetOperation, limit: usize) -> Result<Vec<(Bytes, f64)>, CommandError> {
+	for key in &inputs.keys {
+		if let Some(value) = store.get(key) {
+			if !matches!(value, Value::Set(_) | Value::SortedSet(_)) {
+				return Err(CommandError::WrongType);
+			}
+		}
+	}
+	let sets: Vec<Input> = inputs.keys.iter().map(|key| match store.peek(key) {
+		Some(Value::Set(set)) => Input::Set(set),
+		Some(Value::SortedSet(zset)) => Input::SortedSet(zset),
+		_ => Input::Empty,
+	}).collect();
+	let weighted = |score: f64, index: usize| zero_if_nan(score * inputs.weights[index]);
+	let mut members: Vec<(Bytes, f64)> = match operation {
+		ZsetOperation::Union => {
+			let mut scores: Dict<Bytes, f64> = Dict::new();
+			for (index, set) in sets.iter().enumerate() {
+				for (member, score) in set.iter() {
+					let score = weighted(score, index);
+					match scores.get_mut(&member) {
+						Some(current) => *current = inputs.aggregate.apply(*current, score),
+						None => {
+							scores.insert(member, score);
+						},
+					}
+				}
+			}
+			scores.iter().take(limit).map(|(member, score)| (member.clone(), *score)).collect()
+		},
+		ZsetOperation::Intersection => {
+			// Walk the smallest input and look its members up in the others.
+			let smallest = (0..sets.len()).min_by_key(|&index| sets[index].len()).unwrap_or(0);
+			sets[smallest].iter().filter_map(|(member, _)| {
+				let mut scores = sets.iter().enumerate().map(|(index, set)| Some(weighted(set.score(&member)?, index)));
+				let first = scores.next()??;
+				let score = scores.try_fold(first, |score, other| Some(inputs.aggregate.apply(score, other?)))?;
+				Some((member, score))
+			}).take(limit).collect()
+		},
+		ZsetOperation::Difference => {
+			sets[0].iter().filter(|(member, _)| !sets[1..].iter().any(|set| set.score(member).is_some())).take(limit).collect()
+		},
+	};
+	members.sort_by(|(member, score), (other_member, other_score)| compare(*score, member, *other_score, other_member));
+	Ok(members)
+}
@@ -0,0 +1,223 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use thiserror::Error;
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpStream};
+
+use crate::{resp::{inline::deserialize_inline, multibulk::{deserialize_multibulk, MultibulkScanner}, RespObject, RespParseError, RespProtocol, RespValues}, INPUT_BUFFER_SIZE};
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// How much of the input sent while a client is blocked is buffered, see `Connection::closed`.
+const MAX_PENDING_INPUT: usize = 1024 * 1024;
+
+/// Parses one value from the start of the buffer, see `RespObject::deserialize`.
+type Deserializer = fn(&[u8]) -> Result<(usize, RespValues), RespParseError>;
+
+/// Takes the next complete value out of the buffer of a connection, `Ok(None)` if there is none yet.
+type Parser = fn(&mut Connection) -> Result<Option<RespValues>, RespParseError>;
+
+#[derive(Error, Debug)]
+pub enum ConnectionError {
+	#[error("{0}")]
+	Io(#[from] std::io::Error),
+	#[error("{0}")]
+	Protocol(#[from] RespParseError),
+	#[error("connection closed in the middle of a request")]
+	ResetByPeer,
+}
+
+/// Wraps a `TcpStream` and frames the incoming bytes into `RespValues`.
+/// Bytes are accumulated until a value is complete, so requests may be split across
+/// several reads and several requests may arrive in a single read.
+/// Also holds the state a client negotiated for this connection.
+pub struct Connection {
+	stream: TcpStream,
+	buffer: BytesMut,
+	request_scanner: MultibulkScanner,
+	id: u64,
+	protocol: RespProtocol,
+	name: Option<Bytes>,
+	db: usize,
+	replica_listening_port: Option<u16>,
+}
+
+impl Connection {
+	pub fn new(stream: TcpStream) -> Connection {
+		Connection {
+			stream,
+			buffer: BytesMut::with_capacity(INPUT_BUFFER_SIZE),
+			request_scanner: MultibulkScanner::default(),
+			id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+			protocol: RespProtocol::default(),
+			name: None,
+			db: 0,
+			replica_listening_port: None,
+		}
+	}
+
+	pub fn id(&self) -> u64 {
+		self.id
+	}
+
+	pub fn protocol(&self) -> RespProtocol {
+		self.protocol
+	}
+
+	pub fn set_protocol(&mut self, protocol: RespProtocol) {
+		self.protocol = protocol;
+	}
+
+	pub fn set_name(&mut self, name: Option<Bytes>) {
+		self.name = name;
+	}
+
+	/// Index of the database the client selected.
+	pub fn db(&self) -> usize {
+		self.db
+	}
+
+	pub fn select_db(&mut self, db: usize) {
+		self.db = db;
+	}
+
+	/// The port a replica announced with `REPLCONF listening-port` during its handshake.
+	pub fn replica_listening_port(&self) -> Option<u16> {
+		self.replica_listening_port
+	}
+
+	pub fn set_replica_listening_port(&mut self, port: Option<u16>) {
+		self.replica_listening_port = port;
+	}
+
+	/// Sends `value` encoded with the protocol negotiated for this connection.
+	pub async fn write_frame(&mut self, value: &RespValues) -> std::io::Result<()> {
+		self.stream.write_all(&value.to_bytes(self.protocol)).await
+	}
+
+	/// Returns the next complete value sent by the peer.
+	/// Values that are already buffered are returned without touching the stream.
+	/// Returns `Ok(None)` once the peer closed the connection between two values.
+	pub async fn read_frame(&mut self) -> Result<Option<RespValues>, ConnectionError> {
+		self.read_with(|connection| connection.parse_frame(RespValues::deserialize)).await
+	}
+
+	/// Like `read_frame`, but for requests sent by a client.
+	/// Clients send requests as arrays of bulk strings, anything else is treated as an inline request.
+	/// Empty inline requests are skipped.
+	pub async fn read_request(&mut self) -> Result<Option<RespValues>, ConnectionError> {
+		loop {
+			let request = self.read_with(Connection::parse_request).await?;
+			match request {
+				Some(RespValues::Array(a)) if a.len() == 0 => continue,
+				request => return Ok(request),
+			}
+		}
+	}
+
+	async fn read_with(&mut self, parse: Parser) -> Result<Option<RespValues>, ConnectionError> {
+		loop {
+			if let Some(frame) = parse(self)? {
+				return Ok(Some(frame));
+			}
+
+			self.buffer.reserve(INPUT_BUFFER_SIZE);
+			if self.stream.read_buf(&mut self.buffer).await? == 0 {
+				if self.buffer.is_empty() {
+					return Ok(None);
+				}
+				return Err(ConnectionError::ResetByPeer);
+			}
+		}
+	}
+
+	/// Completes once the peer closed the connection, or it failed.
+	/// Data arriving in the meantime is buffered for the following requests, up to `MAX_PENDING_INPUT` bytes.
+	/// Once the buffer is full reading stops, so the peer is held back by flow control, and this never completes.
+	pub async fn closed(&mut self) {
+		while self.buffer.len() < MAX_PENDING_INPUT {
+			self.buffer.reserve(INPUT_BUFFER_SIZE);
+			let remaining = MAX_PENDING_INPUT - self.buffer.len();
+			let mut buffer = (&mut self.buffer).limit(remaining);
+			if !matches!(self.stream.read_buf(&mut buffer).await, Ok(n) if n > 0) {
+				return;
+			}
+		}
+		std::future::pending().await
+	}
+
+	fn parse_request(&mut self) -> Result<Option<RespValues>, RespParseError> {
+		if self.buffer.first() != Some(&b'*') {
+			return self.parse_frame(deserialize_inline);
+		}
+		// Parse the request only once it is complete. Large requests arrive in many reads,
+		// and parsing them from the start after every read would take quadratic time.
+		match self.request_scanner.scan(&self.buffer) {
+			Err(RespParseError::Incomplete) => Ok(None),
+			Err(e) => Err(e),
+			Ok(_) => self.parse_frame(deserialize_multibulk),
+		}
+	}
+
+	fn parse_frame(&mut self, deserialize: Deserializer) -> Result<Option<RespValues>, RespParseError> {
+		if self.buffer.is_empty() {
+			return Ok(None);
+		}
+		match deserialize(&self.buffer) {
+			Ok((consumed, frame)) => {
+				self.buffer.advance(consumed);
+				Ok(Some(frame))
+			},
+			Err(RespParseError::Incomplete) => Ok(None),
+			Err(e) => Err(e),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::time::Duration;
+
+	use tokio::time::timeout;
+
+	use crate::test_util::connected;
+
+	use super::*;
+
+	#[tokio::test]
+	async fn reads_requests_split_across_reads() {
+		let (mut connection, mut peer) = connected().await;
+		let request = b"*2\r\n$4\r\nECHO\r\n$5\r\nhello\r\nPING\r\n";
+		let writer = tokio::spawn(async move {
+			for chunk in request.chunks(3) {
+				peer.write_all(chunk).await.unwrap();
+				tokio::time::sleep(Duration::from_millis(1)).await;
+			}
+			peer
+		});
+		let echo = RespValues::deserialize(b"*2\r\n$4\r\nECHO\r\n$5\r\nhello\r\n").unwrap().1;
+		let ping = RespValues::deserialize(b"*1\r\n$4\r\nPING\r\n").unwrap().1;
+		assert_eq!(connection.read_request().await.unwrap(), Some(echo));
+		assert_eq!(connection.read_request().await.unwrap(), Some(ping));
+		drop(writer.await.unwrap());
+		assert_eq!(connection.read_request().await.unwrap(), None);
+	}
+
+	#[tokio::test]
+	async fn closed_keeps_the_data_sent_before_closing() {
+		let (mut connection, mut peer) = connected().await;
+		peer.write_all(b"PING\r\n").await.unwrap();
+		drop(peer);
+		connection.closed().await;
+		assert_eq!(&connection.buffer[..], b"PING\r\n");
+	}
+
+	#[tokio::test]
+	async fn closed_stops_reading_once_the_buffer_is_full() {
+		let (mut connection, mut peer) = connected().await;
+		let writer = tokio::spawn(async move { peer.write_all(&vec![b'x'; 2 * MAX_PENDING_INPUT]).await });
+		assert!(timeout(Duration::from_millis(500), connection.closed()).await.is_err());
+		assert_eq!(connection.buffer.len(), MAX_PENDING_INPUT);
+		writer.abort();
+	}
+}
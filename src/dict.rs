@@ -0,0 +1,438 @@
+use std::{borrow::Borrow, collections::hash_map::RandomState, hash::{BuildHasher, Hash}, time::{Duration, Instant}};
+
+use rand::Rng;
+
+/// Size of the table of a dict that got its first entry.
+const INITIAL_SIZE: usize = 4;
+/// A dict shrinks once less than this percentage of its buckets are in use.
+const MIN_FILL_PERCENT: usize = 10;
+/// A rehash step gives up after visiting this many empty buckets per bucket it was asked to move.
+const REHASH_EMPTY_VISITS: usize = 10;
+
+#[derive(Clone)]
+struct Entry<K, V> {
+	hash: u64,
+	key: K,
+	value: V,
+}
+
+#[derive(Clone)]
+struct Table<K, V> {
+	buckets: Vec<Vec<Entry<K, V>>>,
+	used: usize,
+}
+
+impl<K, V> Table<K, V> {
+	fn with_size(size: usize) -> Table<K, V> {
+		Table {
+			buckets: (0..size).map(|_| Vec::new()).collect(),
+			used: 0,
+		}
+	}
+
+	fn size(&self) -> usize {
+		self.buckets.len()
+	}
+
+	fn mask(&self) -> u64 {
+		(self.buckets.len() as u64).wrapping_sub(1)
+	}
+
+	fn bucket(&self, hash: u64) -> usize {
+		(hash & self.mask()) as usize
+	}
+}
+
+/// A hash table like the dict of Redis.
+/// Tables have a power of two size and grow or shrink by incrementally moving the buckets
+/// to a second table, a few with every modification, so no single operation has to rehash everything.
+/// This layout is what lets `scan` guarantee that entries present during a whole iteration are returned.
+#[derive(Clone)]
+pub struct Dict<K, V> {
+	tables: [Table<K, V>; 2],
+	/// Index of the next bucket of the first table to move to the second table while rehashing.
+	rehash_index: Option<usize>,
+	hasher: RandomState,
+}
+
+impl<K, V> Default for Dict<K, V> {
+	fn default() -> Self {
+		Dict {
+			tables: [Table::with_size(0), Table::with_size(0)],
+			rehash_index: None,
+			hasher: RandomState::new(),
+		}
+	}
+}
+
+impl<K: Hash + Eq, V> Dict<K, V> {
+	pub fn new() -> Dict<K, V> {
+		Dict::default()
+	}
+
+	pub fn len(&self) -> usize {
+		self.tables[0].used + self.tables[1].used
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn is_rehashing(&self) -> bool {
+		self.rehash_index.is_some()
+	}
+
+	fn hash<Q: Hash + ?Sized>(&self, key: &Q) -> u64 {
+		self.hasher.hash_one(key)
+	}
+
+	/// Returns the table, bucket and position in the bucket of `key`.
+	fn find<Q>(&self, key: &Q) -> Option<(usize, usize, usize)>
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		if self.is_empty() {
+			return None;
+		}
+		let hash = self.hash(key);
+		let tables = if self.is_rehashing() { 2 } else { 1 };
+		(0..tables).find_map(|t| {
+			let table = &self.tables[t];
+			if table.size() == 0 {
+				return None;
+			}
+			let bucket = table.bucket(hash);
+			table.buckets[bucket].iter()
+				.position(|e| e.hash == hash && e.key.borrow() == key)
+				.map(|position| (t, bucket, position))
+		})
+	}
+
+	pub fn get<Q>(&self, key: &Q) -> Option<&V>
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		self.get_key_value(key).map(|(_, v)| v)
+	}
+
+	pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		let (t, bucket, position) = self.find(key)?;
+		let entry = &self.tables[t].buckets[bucket][position];
+		Some((&entry.key, &entry.value))
+	}
+
+	pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		let (t, bucket, position) = self.find(key)?;
+		Some(&mut self.tables[t].buckets[bucket][position].value)
+	}
+
+	pub fn contains_key<Q>(&self, key: &Q) -> bool
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		self.find(key).is_some()
+	}
+
+	/// Returns the previous value of `key`.
+	pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+		self.rehash_step();
+		if let Some((t, bucket, position)) = self.find(&key) {
+			return Some(std::mem::replace(&mut self.tables[t].buckets[bucket][position].value, value));
+		}
+		self.expand_if_needed();
+		// New entries go to the new table while rehashing, so the old one only gets emptier.
+		let table = &mut self.tables[if self.is_rehashing() { 1 } else { 0 }];
+		let hash = self.hasher.hash_one(&key);
+		let bucket = table.bucket(hash);
+		table.buckets[bucket].push(Entry { hash, key, value });
+		table.used += 1;
+		None
+	}
+
+	pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		self.remove_entry(key).map(|(_, v)| v)
+	}
+
+	pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+	where
+		K: Borrow<Q>,
+		Q: Hash + Eq + ?Sized,
+	{
+		self.rehash_step();
+		let (t, bucket, position) = self.find(key)?;
+		let table = &mut self.tables[t];
+		let entry = table.buckets[bucket].swap_remove(position);
+		table.used -= 1;
+		self.shrink_if_needed();
+		Some((entry.key, entry.value))
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+		self.tables.iter()
+			.flat_map(|t| t.buckets.iter())
+			.flatten()
+			.map(|e| (&e.key, &e.value))
+	}
+
+	/// Picks a random entry. Picks a random non-empty bucket first,
+	/// so entries in crowded buckets are somewhat less likely to be picked.
+	pub fn random_entry(&self) -> Option<(&K, &V)> {
+		if self.is_empty() {
+			return None;
+		}
+		let mut rng = rand::thread_rng();
+		let bucket = loop {
+			let bucket = match self.rehash_index {
+				// Buckets of the first table below the rehash index are empty.
+				Some(rehash_index) => {
+					let (first, second) = (self.tables[0].size(), self.tables[1].size());
+					let index = rng.gen_range(rehash_index..first + second);
+					match index.checked_sub(first) {
+						Some(index) => &self.tables[1].buckets[index],
+						None => &self.tables[0].buckets[index],
+					}
+				},
+				None => &self.tables[0].buckets[rng.gen_range(0..self.tables[0].size())],
+			};
+			if !bucket.is_empty() {
+				break bucket;
+			}
+		};
+		let entry = &bucket[rng.gen_range(0..bucket.len())];
+		Some((&entry.key, &entry.value))
+	}
+
+	/// Calls `f` with the entries of the buckets at `cursor` and returns the cursor to continue with, 0 once done.
+	/// Start with 0. Every entry present from the start to the end of the iteration is visited at least once,
+	/// even if the dict is resized in between, but entries may be visited multiple times.
+	///
+	/// The cursor is incremented from its most significant bit downwards (within the mask of the table), so
+	/// when the table grows, the buckets already visited map to buckets of the larger table that were
+	/// visited as well, and when it shrinks, to buckets that contain at most some entries visited twice.
+	pub fn scan(&self, cursor: u64, mut f: impl FnMut(&K, &V)) -> u64 {
+		if self.is_empty() {
+			return 0;
+		}
+		let mut visit = |bucket: &Vec<Entry<K, V>>| bucket.iter().for_each(|e| f(&e.key, &e.value));
+		let mut cursor = cursor;
+		if !self.is_rehashing() {
+			let table = &self.tables[0];
+			visit(&table.buckets[(cursor & table.mask()) as usize]);
+			cursor = next_cursor(cursor, table.mask());
+		} else {
+			let (small, large) = match self.tables[0].size() <= self.tables[1].size() {
+				true => (&self.tables[0], &self.tables[1]),
+				false => (&self.tables[1], &self.tables[0]),
+			};
+			visit(&small.buckets[(cursor & small.mask()) as usize]);
+			// Visit all buckets of the larger table that the bucket of the smaller table expands to.
+			loop {
+				visit(&large.buckets[(cursor & large.mask()) as usize]);
+				cursor = next_cursor(cursor, large.mask());
+				if cursor & (small.mask() ^ large.mask()) == 0 {
+					break;
+				}
+			}
+		}
+		cursor
+	}
+
+	/// Moves buckets to the new table for about `duration`. Returns whether there is still work left.
+	pub fn rehash_for(&mut self, duration: Duration) -> bool {
+		let start = Instant::now();
+		while self.rehash(100) {
+			if start.elapsed() > duration {
+				return true;
+			}
+		}
+		false
+	}
+
+	fn rehash_step(&mut self) {
+		self.rehash(1);
+	}
+
+	/// Moves `buckets` non-empty buckets to the new table. Returns whether there is still work left.
+	fn rehash(&mut self, buckets: usize) -> bool {
+		let Some(mut index) = self.rehash_index else {
+			return false;
+		};
+		let mut empty_visits = buckets * REHASH_EMPTY_VISITS;
+		for _ in 0..buckets {
+			if self.tables[0].used == 0 {
+				break;
+			}
+			while self.tables[0].buckets[index].is_empty() {
+				index += 1;
+				empty_visits -= 1;
+				if empty_visits == 0 {
+					self.rehash_index = Some(index);
+					return true;
+				}
+			}
+			let entries = std::mem::take(&mut self.tables[0].buckets[index]);
+			self.tables[0].used -= entries.len();
+			let [_, new] = &mut self.tables;
+			new.used += entries.len();
+			for entry in entries {
+				let bucket = new.bucket(entry.hash);
+				new.buckets[bucket].push(entry);
+			}
+			index += 1;
+		}
+		if self.tables[0].used == 0 {
+			self.tables.swap(0, 1);
+			self.tables[1] = Table::with_size(0);
+			self.rehash_index = None;
+			// Entries removed while rehashing may have left the new table too sparse already.
+			self.shrink_if_needed();
+			return self.is_rehashing();
+		}
+		self.rehash_index = Some(index);
+		true
+	}
+
+	fn expand_if_needed(&mut self) {
+		if self.is_rehashing() {
+			return;
+		}
+		if self.tables[0].size() == 0 {
+			self.tables[0] = Table::with_size(INITIAL_SIZE);
+		} else if self.tables[0].used >= self.tables[0].size() {
+			self.resize(self.tables[0].used + 1);
+		}
+	}
+
+	fn shrink_if_needed(&mut self) {
+		let table = &self.tables[0];
+		if !self.is_rehashing() && table.size() > INITIAL_SIZE && table.used * 100 / table.size() < MIN_FILL_PERCENT {
+			self.resize(table.used);
+		}
+	}
+
+	/// Starts rehashing to a table with room for at least `entries` entries.
+	fn resize(&mut self, entries: usize) {
+		let size = entries.max(INITIAL_SIZE).next_power_of_two();
+		if size == self.tables[0].size() {
+			return;
+		}
+		self.tables[1] = Table::with_size(size);
+		self.rehash_index = Some(0);
+	}
+}
+
+/// Increments the bits of `cursor` covered by `mask` in reverse order.
+fn next_cursor(cursor: u64, mask: u64) -> u64 {
+	// Setting the bits outside the mask makes the increment carry over them.
+	let cursor = (cursor | !mask).reverse_bits();
+	cursor.wrapping_add(1).reverse_bits()
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+
+	use super::*;
+
+	/// Scans `dict` to the end, calling `between` after every step. Returns the visited keys and whether
+	/// the dict was rehashing during any step.
+	fn scan_all(dict: &mut Dict<u32, ()>, mut between: impl FnMut(&mut Dict<u32, ()>, usize)) -> (HashSet<u32>, bool) {
+		let mut visited = HashSet::new();
+		let mut rehashed = false;
+		let mut cursor = 0;
+		let mut step = 0;
+		loop {
+			rehashed |= dict.is_rehashing();
+			cursor = dict.scan(cursor, |key, _| {
+				visited.insert(*key);
+			});
+			if cursor == 0 {
+				return (visited, rehashed);
+			}
+			between(dict, step);
+			step += 1;
+		}
+	}
+
+	fn dict_of(keys: impl Iterator<Item = u32>) -> Dict<u32, ()> {
+		let mut dict = Dict::new();
+		for key in keys {
+			dict.insert(key, ());
+		}
+		dict
+	}
+
+	#[test]
+	fn inserts_gets_and_removes() {
+		let mut dict = dict_of(0..1000);
+		assert_eq!(dict.len(), 1000);
+		assert!((0..1000).all(|key| dict.contains_key(&key)));
+		assert_eq!(dict.insert(5, ()), Some(()));
+		assert_eq!(dict.remove(&5), Some(()));
+		assert_eq!(dict.remove(&5), None);
+		assert_eq!(dict.len(), 999);
+	}
+
+	#[test]
+	fn scan_visits_every_entry() {
+		let mut dict = dict_of(0..1000);
+		dict.rehash_for(Duration::from_secs(1));
+		let (visited, _) = scan_all(&mut dict, |_, _| ());
+		assert_eq!(visited, (0..1000).collect());
+	}
+
+	#[test]
+	fn scan_visits_every_entry_while_growing() {
+		let mut dict = dict_of(0..100);
+		// Growing on every step would keep the scan from ever catching up with the table.
+		let (visited, rehashed) = scan_all(&mut dict, |dict, step| {
+			if step < 20 {
+				for key in 0..20 {
+					dict.insert(1000 + step as u32 * 20 + key, ());
+				}
+			}
+		});
+		assert!(rehashed);
+		assert!((0..100).all(|key| visited.contains(&key)));
+	}
+
+	#[test]
+	fn scan_visits_every_entry_while_shrinking() {
+		let mut dict = dict_of(0..5000);
+		dict.rehash_for(Duration::from_secs(1));
+		let (visited, rehashed) = scan_all(&mut dict, |dict, step| {
+			for key in 0..200 {
+				dict.remove(&(100 + step as u32 * 200 + key));
+			}
+		});
+		assert!(rehashed);
+		assert!((0..100).all(|key| visited.contains(&key)));
+	}
+
+	#[test]
+	fn scan_visits_every_entry_of_a_dict_that_is_rehashing_at_the_start() {
+		let mut dict = Dict::new();
+		let mut key = 0;
+		while !dict.is_rehashing() || dict.len() < 500 {
+			dict.insert(key, ());
+			key += 1;
+		}
+		let (visited, _) = scan_all(&mut dict, |_, _| ());
+		assert_eq!(visited, (0..key).collect());
+	}
+}
@@ -0,0 +1,174 @@
+/// Matches `string` against a Redis style glob `pattern`.
+/// Supports `*`, `?`, character classes like `[a-z]` or `[^x]` and `\` to escape the next character.
+pub fn glob_match(pattern: &[u8], string: &[u8], nocase: bool) -> bool {
+	let mut skip_longer = false;
+	glob_match_impl(pattern, string, nocase, &mut skip_longer, 0)
+}
+
+/// Patterns with many stars would otherwise recurse deep enough to overflow the stack.
+const MAX_NESTING: usize = 1000;
+
+fn glob_match_impl(pattern: &[u8], string: &[u8], nocase: bool, skip_longer: &mut bool, nesting: usize) -> bool {
+	if nesting > MAX_NESTING {
+		return false;
+	}
+	let eq = |a: u8, b: u8| if nocase { a.eq_ignore_ascii_case(&b) } else { a == b };
+
+	let mut p = 0;
+	let mut s = 0;
+	if string.is_empty() {
+		while p < pattern.len() && pattern[p] == b'*' {
+			p += 1;
+		}
+	}
+	while p < pattern.len() && s < string.len() {
+		match pattern[p] {
+			b'*' => {
+				while p + 1 < pattern.len() && pattern[p + 1] == b'*' {
+					p += 1;
+				}
+				if p + 1 == pattern.len() {
+					return true;
+				}
+				while s < string.len() {
+					if glob_match_impl(&pattern[p + 1..], &string[s..], nocase, skip_longer, nesting + 1) {
+						return true;
+					}
+					// A failed match of the rest of the pattern can not succeed on a shorter suffix either.
+					if *skip_longer {
+						return false;
+					}
+					s += 1;
+				}
+				*skip_longer = true;
+				return false;
+			},
+			b'?' => s += 1,
+			b'[' => {
+				p += 1;
+				let negate = pattern.get(p) == Some(&b'^');
+				if negate {
+					p += 1;
+				}
+				let c = string[s];
+				let mut matched = false;
+				loop {
+					match pattern.get(p) {
+						// An unterminated class ends at the end of the pattern.
+						None => {
+							p -= 1;
+							break;
+						},
+						Some(b']') => break,
+						Some(b'\\') if p + 1 < pattern.len() => {
+							p += 1;
+							matched |= eq(pattern[p], c);
+						},
+						Some(&start) if p + 2 < pattern.len() && pattern[p + 1] == b'-' => {
+							let mut start = start;
+							let mut end = pattern[p + 2];
+							let mut c = c;
+							if start > end {
+								std::mem::swap(&mut start, &mut end);
+							}
+							if nocase {
+								start = start.to_ascii_lowercase();
+								end = end.to_ascii_lowercase();
+								c = c.to_ascii_lowercase();
+							}
+							p += 2;
+							matched |= (start..=end).contains(&c);
+						},
+						Some(&x) => matched |= eq(x, c),
+					}
+					p += 1;
+				}
+				if matched == negate {
+					return false;
+				}
+				s += 1;
+			},
+			b'\\' if p + 1 < pattern.len() => {
+				p += 1;
+				if !eq(pattern[p], string[s]) {
+					return false;
+				}
+				s += 1;
+			},
+			x => {
+				if !eq(x, string[s]) {
+					return false;
+				}
+				s += 1;
+			},
+		}
+		p += 1;
+		if s == string.len() {
+			while p < pattern.len() && pattern[p] == b'*' {
+				p += 1;
+			}
+			break;
+		}
+	}
+	p == pattern.len() && s == string.len()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn matches(pattern: &str, string: &str) -> bool {
+		glob_match(pattern.as_bytes(), string.as_bytes(), false)
+	}
+
+	#[test]
+	fn matches_wildcards() {
+		assert!(matches("*", ""));
+		assert!(matches("h*o", "hello"));
+		assert!(matches("h?llo", "hallo"));
+		assert!(!matches("h?llo", "hllo"));
+		assert!(matches("*a*b*", "xaxxbx"));
+		assert!(!matches("*a*b", "xbxa"));
+	}
+
+	#[test]
+	fn matches_classes_and_ranges() {
+		assert!(matches("h[ae]llo", "hello"));
+		assert!(!matches("h[ae]llo", "hillo"));
+		assert!(matches("key[0-9]", "key5"));
+		assert!(!matches("key[0-9]", "keyx"));
+		// Reversed ranges are accepted as well.
+		assert!(matches("key[9-0]", "key5"));
+		assert!(matches("h[^e]llo", "hallo"));
+		assert!(!matches("h[^e]llo", "hello"));
+		assert!(!matches("h[^a-z]llo", "hello"));
+		assert!(matches("h[^a-z]llo", "h1llo"));
+	}
+
+	#[test]
+	fn matches_escaped_characters() {
+		assert!(matches(r"a\*", "a*"));
+		assert!(!matches(r"a\*", "ab"));
+		assert!(matches(r"\?", "?"));
+		assert!(matches(r"[\]]", "]"));
+		assert!(matches(r"[\-a]", "-"));
+	}
+
+	#[test]
+	fn unterminated_classes_end_at_the_end_of_the_pattern() {
+		assert!(matches("a[bc", "ab"));
+		assert!(!matches("a[bc", "ad"));
+	}
+
+	#[test]
+	fn matches_ignoring_case() {
+		assert!(glob_match(b"H[A-Z]llo", b"hello", true));
+		assert!(!glob_match(b"H[A-Z]llo", b"hello", false));
+	}
+
+	#[test]
+	fn gives_up_on_deeply_nested_stars() {
+		let pattern = "a*".repeat(5000) + "b";
+		assert!(!matches(&pattern, &"a".repeat(5000)));
+	}
+}
@@ -1,21 +1,29 @@
 
 
-use std::error::Error;
+use std::{error::Error, sync::Arc};
 
-use resp::{RespObject, RespValues};
-use tokio::{io::AsyncReadExt, net::{TcpListener, TcpStream}};
+use tokio::net::TcpListener;
 use clap::Parser;
 
-use crate::{commands::{Command, Commands}, replication::initialize_replication};
+use crate::{replication::initialize_replication, server::{Server, ServerConfig, DEFAULT_PORT}, types::EncodingLimits};
 
+pub(crate) mod blocking;
 pub(crate) mod commands;
+pub(crate) mod connection;
 pub(crate) mod resp;
 pub(crate) mod store;
 pub(crate) mod replication;
+pub(crate) mod server;
 pub(crate) mod util;
+pub(crate) mod glob;
+pub(crate) mod dict;
+pub(crate) mod types;
+#[cfg(test)]
+pub(crate) mod test_util;
 
 pub const INPUT_BUFFER_SIZE: usize = 2048;
-pub static mut LISTENING_PORT: u16 = 6379;
+/// Redis version reported to clients, e.g. in the reply to `HELLO`.
+pub const REDIS_VERSION: &str = "7.4.0";
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -24,50 +32,58 @@ pub struct Args {
     port: Option<u16>,
 
     #[arg(long = "replicaof", value_delimiter = ' ', num_args = 2)]
-    replica_of: Option<Vec<String>>
+    replica_of: Option<Vec<String>>,
+
+    /// Hashes with more fields are converted from a listpack to a hash table.
+    #[arg(long = "hash-max-listpack-entries")]
+    hash_max_listpack_entries: Option<usize>,
+
+    /// Hashes with longer fields or values are converted from a listpack to a hash table.
+    #[arg(long = "hash-max-listpack-value")]
+    hash_max_listpack_value: Option<usize>,
 }
 
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
+    let config = server_config(args);
 
-    let listener = unsafe {
-        LISTENING_PORT = args.port.unwrap_or(LISTENING_PORT);
-        println!("Listening on port: {}", LISTENING_PORT);
-        TcpListener::bind(format!("127.0.0.1:{}", LISTENING_PORT)).await?
-    };
+    println!("Listening on port: {}", config.port);
+    let listener = TcpListener::bind(format!("127.0.0.1:{}", config.port)).await?;
+    let server = Arc::new(Server::new(config));
 
-    server_initialization(args).await;
+    server_initialization(&server).await;
+
+    server.serve(listener).await?;
+    Ok(())
+}
 
-    loop {
-        let (stream, _) = listener.accept().await?;
-        tokio::spawn(async move {
-            handle_connection(stream).await;
-        });
+fn server_config(args: Args) -> ServerConfig {
+    let replica_of = args.replica_of.map(|v| {
+        assert!(v.len() == 2);
+        let master_port = match v[1].parse::<u16>() {
+            Ok(v) => v,
+            Err(e) => panic!("--replicaof: Could not parse master port ('{}'), got: {}", v[1], e),
+        };
+        (v[0].clone(), master_port)
+    });
+    let defaults = EncodingLimits::default();
+    let encoding_limits = EncodingLimits {
+        hash_max_listpack_entries: args.hash_max_listpack_entries.unwrap_or(defaults.hash_max_listpack_entries),
+        hash_max_listpack_value: args.hash_max_listpack_value.unwrap_or(defaults.hash_max_listpack_value),
+        ..defaults
+    };
+    ServerConfig {
+        port: args.port.unwrap_or(DEFAULT_PORT),
+        replica_of,
+        encoding_limits,
+        ..ServerConfig::default()
     }
 }
 
-async fn server_initialization(args: Args) {
+async fn server_initialization(server: &Server) {
     println!("Initializing server.");
 
-    initialize_replication(args).await;
-}
-
-async fn handle_connection(mut stream: TcpStream) {
-    let mut input_buffer = [0; INPUT_BUFFER_SIZE];
-    loop {
-        match stream.read(&mut input_buffer).await {
-            Ok(n) => {
-                if n == 0 {
-                    return;
-                }
-                let raw_request_data = std::str::from_utf8(&input_buffer[0..n]).expect("RESP should always contain valid ASCII");
-                let (consumed, request_data) = RespValues::deserialize(raw_request_data);
-                assert_eq!(n, consumed);
-                Commands::invoke(&mut stream, request_data).await;
-            },
-            Err(e) => eprintln!("Terminating connection. Error when reading into input buffer: {e}"),
-        }
-    }
+    initialize_replication(server).await;
 }
@@ -1,7 +1,6 @@
-use core::panic;
 use std::fmt::Display;
 use tokio::net::TcpStream;
-use crate::{resp::{array::RespArray, bulk_string::RespBulkString, RespValues}, util::{await_response, generate_master_replid, ok_response, ping_response, request}, Args, LISTENING_PORT};
+use crate::{connection::Connection, resp::{array::RespArray, bulk_string::RespBulkString, RespValues}, server::Server, util::{await_response, generate_master_replid, ok_response, ping_response, request}};
 
 #[derive(PartialEq, Clone)]
 pub enum ReplicationRole {
@@ -29,13 +28,46 @@ pub struct ReplicationInfo {
 	pub repl_backlog_histlen: usize,
 	pub master_host: String,
 	pub master_port: u16,
+	pub slaves: Vec<ReplicationSlave>,
+}
+
+impl ReplicationInfo {
+	/// `replica_of` is the host and port of the master if the server is a replica.
+	pub fn new(replica_of: Option<(String, u16)>) -> ReplicationInfo {
+		let (role, master_host, master_port) = match replica_of {
+			Some((master_host, master_port)) => (ReplicationRole::Slave, master_host, master_port),
+			None => (ReplicationRole::Master, String::from(""), 0),
+		};
+		ReplicationInfo {
+			role,
+			master_replid: generate_master_replid(),
+			master_repl_offset: 0,
+			second_repl_offset: 0,
+			repl_backlog_active: 0,
+			repl_backlog_size: 0,
+			repl_backlog_first_byte_offset: 0,
+			repl_backlog_histlen: 0,
+			master_host,
+			master_port,
+			slaves: Vec::new(),
+		}
+	}
+
+	pub fn add_slave(&mut self, slave: ReplicationSlave) {
+		assert!(self.role == ReplicationRole::Master);
+		self.slaves.push(slave);
+
+		println!("Added replication slave! Now got {} slaves", self.slaves.len());
+		println!("Current slaves are:");
+		self.slaves.iter().for_each(|s| println!("{s}"));
+	}
 }
 
 impl Display for ReplicationInfo {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		writeln!(f, "# Replication")?;
 		writeln!(f, "role:{}", self.role)?;
-		writeln!(f, "connected_slaves:{}", unsafe { REPLICATION_CONFIGURATION.slaves.len() })?;
+		writeln!(f, "connected_slaves:{}", self.slaves.len())?;
 		writeln!(f, "master_replid:{}", self.master_replid)?;
 		writeln!(f, "master_repl_offset:{}", self.master_repl_offset)?;
 		writeln!(f, "second_repl_offset:{}", self.second_repl_offset)?;
@@ -51,16 +83,6 @@ impl Display for ReplicationInfo {
 	}
 }
 
-pub static mut REPLICATION_STATE: Option<ReplicationInfo> = None;
-pub fn replication_state() -> &'static ReplicationInfo {
-	unsafe {
-		match &REPLICATION_STATE {
-			Some(v) => v,
-			None => panic!("Tried accessing REPLICATION_STATE before server was initialized!"),
-		}
-	}
-}
-
 #[derive(Clone)]
 pub struct ReplicationSlave {
 	pub port: u16,
@@ -74,66 +96,17 @@ impl Display for ReplicationSlave {
 	}
 }
 
-struct ReplicationConfiguration {
-	pub slaves: Vec<ReplicationSlave>,
-}
-
-static mut REPLICATION_CONFIGURATION: ReplicationConfiguration = ReplicationConfiguration {
-	slaves: Vec::new(),
-};
-pub fn add_replication_slave(slave: ReplicationSlave) {
-	unsafe {
-		assert!(matches!(REPLICATION_STATE, Some(ReplicationInfo { role: ReplicationRole::Master, ..})));
-		REPLICATION_CONFIGURATION.slaves.push(slave);
-
-		println!("Added replication slave! Now got {} slaves", REPLICATION_CONFIGURATION.slaves.len());
-		println!("Current slaves are:");
-		REPLICATION_CONFIGURATION.slaves.iter().for_each(|s| println!("{s}"));
-	}
-}
-
-pub async fn initialize_replication(args: Args) {
+pub async fn initialize_replication(server: &Server) {
 	println!("Initializing replication.");
 
-	let (role, master_host, master_port) = match args.replica_of {
-		Some(v) => {
-			assert!(v.len() == 2);
-			let master_host = v[0].clone();
-			let master_port = match v[1].parse::<u16>() {
-				Ok(v) => v,
-				Err(e) => panic!("--replicaof: Could not parse master port ('{}'), got: {}", v[1], e),
-			};
-			(ReplicationRole::Slave, master_host, master_port)
-		},
-		None => (ReplicationRole::Master, String::from(""), 0)
-	};
-
-	unsafe {
-		REPLICATION_STATE = Some(ReplicationInfo {
-			role: role.clone(),
-			master_replid: generate_master_replid(),
-			master_repl_offset: 0,
-			second_repl_offset: 0,
-			repl_backlog_active: 0,
-			repl_backlog_size: 0,
-			repl_backlog_first_byte_offset: 0,
-			repl_backlog_histlen: 0,
-			master_host: master_host.clone(),
-			master_port,
-		});
+	if let Some((master_host, master_port)) = &server.config().replica_of {
+		execute_replication_handshake(server, master_host, master_port).await;
 	}
-
-	match role {
-		ReplicationRole::Slave => {
-			execute_replication_handshake(&master_host, &master_port).await;
-		},
-		ReplicationRole::Master => ()
-	};
 }
 
-async fn execute_replication_handshake(master_host: &str, master_port: &u16) {
-	let mut stream = match TcpStream::connect(format!("{}:{}", master_host, master_port)).await {
-		Ok(s) => s,
+async fn execute_replication_handshake(server: &Server, master_host: &str, master_port: &u16) {
+	let mut connection = match TcpStream::connect(format!("{}:{}", master_host, master_port)).await {
+		Ok(s) => Connection::new(s),
 		Err(e) => panic!("Could not connect to replication master at '{}:{}', got: {}", master_host, master_port, e),
 	};
 
@@ -141,8 +114,8 @@ async fn execute_replication_handshake(master_host: &str, master_port: &u16) {
 		RespValues::BulkString(RespBulkString::from_raw(String::from("ping").into_bytes())),
 	]));
 
-	request(&mut stream, ping_request).await;
-	let response = await_response(&mut stream).await;
+	request(&mut connection, ping_request).await;
+	let response = await_response(&mut connection).await;
 	match response {
 		Some(r) => {
 			if r != ping_response() {
@@ -155,10 +128,10 @@ async fn execute_replication_handshake(master_host: &str, master_port: &u16) {
 	let request_data = RespValues::Array(RespArray::from_raw(vec![
 		RespValues::BulkString(RespBulkString::from_raw(String::from("REPLCONF").into_bytes())),
 		RespValues::BulkString(RespBulkString::from_raw(String::from("listening-port").into_bytes())),
-		RespValues::BulkString(RespBulkString::from_raw(unsafe { LISTENING_PORT.to_string() }.into_bytes())),
+		RespValues::BulkString(RespBulkString::from_raw(server.config().port.to_string().into_bytes())),
 	]));
-	request(&mut stream, request_data).await;
-	let response = await_response(&mut stream).await;
+	request(&mut connection, request_data).await;
+	let response = await_response(&mut connection).await;
 	match response {
 		Some(r) => {
 			if r != ok_response() {
@@ -173,8 +146,8 @@ async fn execute_replication_handshake(master_host: &str, master_port: &u16) {
 		RespValues::BulkString(RespBulkString::from_raw(String::from("capa").into_bytes())),
 		RespValues::BulkString(RespBulkString::from_raw(String::from("psync2").into_bytes())),
 	]));
-	request(&mut stream, request_data).await;
-	let response = await_response(&mut stream).await;
+	request(&mut connection, request_data).await;
+	let response = await_response(&mut connection).await;
 	match response {
 		Some(r) => {
 			if r != ok_response() {
@@ -189,8 +162,8 @@ async fn execute_replication_handshake(master_host: &str, master_port: &u16) {
 		RespValues::BulkString(RespBulkString::from_raw(String::from("?").into_bytes())),
 		RespValues::BulkString(RespBulkString::from_raw(String::from("-1").into_bytes())),
 	]));
-	request(&mut stream, request_data).await;
-	let response = await_response(&mut stream).await;
+	request(&mut connection, request_data).await;
+	let response = await_response(&mut connection).await;
 	match response {
 		Some(r) => {
 			match r {
@@ -205,14 +178,9 @@ async fn execute_replication_handshake(master_host: &str, master_port: &u16) {
 								Ok(v) => v,
 								Err(e) => panic!("Replication handshake: Can not parse master_reploffset from FULLRESYNC response to PSYNC, got: '{}'", e),
 							};
-							unsafe {
-								if let Some(v) = &mut REPLICATION_STATE {
-									v.master_replid = master_replid;
-									v.master_repl_offset = master_reploffset;
-								} else {
-									panic!("Finished replication handshake but REPLICATION_STATE has not been initialized!");
-								}
-							}
+							let mut replication = server.replication_mut();
+							replication.master_replid = master_replid;
+							replication.master_repl_offset = master_reploffset;
 						},
 						v => panic!("Replication handshake: Received {v} as a reply to PSYNC from master. Do not know how to handle this yet!"),
 					}
@@ -1,52 +1,303 @@
 use std::fmt::Display;
 
-use self::{array::RespArray, bulk_string::RespBulkString, null::RespNull, simple_string::RespSimpleString};
+use bytes::{BufMut, Bytes, BytesMut};
+use thiserror::Error;
+
+use self::{array::RespArray, attribute::RespAttribute, big_number::RespBigNumber, boolean::RespBoolean, bulk_error::RespBulkError, bulk_string::RespBulkString, double::RespDouble, integer::RespInteger, map::RespMap, null::RespNull, push::RespPush, set::RespSet, simple_error::RespSimpleError, simple_string::RespSimpleString, verbatim_string::RespVerbatimString};
 
 pub(crate) mod array;
 pub(crate) mod bulk_string;
 pub(crate) mod simple_string;
+pub(crate) mod simple_error;
 pub(crate) mod null;
+pub(crate) mod integer;
+pub(crate) mod double;
+pub(crate) mod boolean;
+pub(crate) mod big_number;
+pub(crate) mod bulk_error;
+pub(crate) mod verbatim_string;
+pub(crate) mod map;
+pub(crate) mod set;
+pub(crate) mod attribute;
+pub(crate) mod push;
+pub(crate) mod inline;
+pub(crate) mod multibulk;
 
 pub(crate) const RESP_TERMINATOR: &str = "\r\n";
 
+/// Maximum number of bytes a single bulk string may announce (512MB, same as Redis' proto-max-bulk-len).
+pub(crate) const MAX_BULK_LENGTH: i64 = 512 * 1024 * 1024;
+/// Maximum number of elements a single aggregate may announce.
+pub(crate) const MAX_MULTIBULK_LENGTH: i64 = 1024 * 1024 * 1024;
+/// Maximum length of the length header of an aggregate or bulk value. Longer headers are not waited for.
+const MAX_LENGTH_HEADER: usize = 64 * 1024;
+
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum RespValues {
 	Array(RespArray),
 	BulkString(RespBulkString),
 	SimpleString(RespSimpleString),
+	Error(RespSimpleError),
 	Null(RespNull),
+	Integer(RespInteger),
+	Double(RespDouble),
+	Boolean(RespBoolean),
+	BigNumber(RespBigNumber),
+	BulkError(RespBulkError),
+	VerbatimString(RespVerbatimString),
+	Map(RespMap),
+	Set(RespSet),
+	Attribute(RespAttribute),
+	Push(RespPush),
+}
+
+/// The protocol version a connection negotiated with `HELLO`.
+/// RESP3 only types are downgraded to their closest RESP2 counterpart when serializing for RESP2.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RespProtocol {
+	#[default]
+	Resp2,
+	Resp3,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RespParseError {
+	/// The buffer ends before the value is complete. More data has to be read before trying again.
+	#[error("incomplete RESP value")]
+	Incomplete,
+	#[error("{0}")]
+	Invalid(String),
 }
 
 pub trait RespObject {
-	fn serialize(&self) -> String;
-	fn deserialize(data: &str) -> (usize, RespValues);
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol);
+	/// Parses a single value from the start of `data`.
+	/// Returns the number of consumed bytes together with the value or `RespParseError::Incomplete` if `data`
+	/// does not yet contain the whole value.
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError>;
+}
+
+impl RespValues {
+	pub fn to_bytes(&self, protocol: RespProtocol) -> Bytes {
+		let mut buf = BytesMut::new();
+		self.serialize(&mut buf, protocol);
+		buf.freeze()
+	}
 }
 
 impl Display for RespValues {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		write!(f, "{}", self.serialize())
+		write!(f, "{}", String::from_utf8_lossy(&self.to_bytes(RespProtocol::Resp3)))
 	}
 }
 
 impl RespObject for RespValues {
-	fn serialize(&self) -> String {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
 		match self {
-			RespValues::Array(v) => v.serialize(),
-			RespValues::BulkString(v) => v.serialize(),
-			RespValues::SimpleString(v) => v.serialize(),
-			RespValues::Null(v) => v.serialize(),
+			RespValues::Array(v) => v.serialize(buf, protocol),
+			RespValues::BulkString(v) => v.serialize(buf, protocol),
+			RespValues::SimpleString(v) => v.serialize(buf, protocol),
+			RespValues::Error(v) => v.serialize(buf, protocol),
+			RespValues::Null(v) => v.serialize(buf, protocol),
+			RespValues::Integer(v) => v.serialize(buf, protocol),
+			RespValues::Double(v) => v.serialize(buf, protocol),
+			RespValues::Boolean(v) => v.serialize(buf, protocol),
+			RespValues::BigNumber(v) => v.serialize(buf, protocol),
+			RespValues::BulkError(v) => v.serialize(buf, protocol),
+			RespValues::VerbatimString(v) => v.serialize(buf, protocol),
+			RespValues::Map(v) => v.serialize(buf, protocol),
+			RespValues::Set(v) => v.serialize(buf, protocol),
+			RespValues::Attribute(v) => v.serialize(buf, protocol),
+			RespValues::Push(v) => v.serialize(buf, protocol),
 		}
 	}
 
-	fn deserialize(data: &str) -> (usize, RespValues) {
-		assert!(!data.is_empty());
-		match data.chars().next() {
-			Some('*') => RespArray::deserialize(data),
-			Some('$') => RespBulkString::deserialize(data),
-			Some('+') => RespSimpleString::deserialize(data),
-			Some('_') => RespNull::deserialize(data),
-			c => panic!("Unknown data type {:?} in '{data}'", c),
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		match data.first() {
+			Some(b'*') => RespArray::deserialize(data),
+			Some(b'$') => RespBulkString::deserialize(data),
+			Some(b'+') => RespSimpleString::deserialize(data),
+			Some(b'-') => RespSimpleError::deserialize(data),
+			Some(b'_') => RespNull::deserialize(data),
+			Some(b':') => RespInteger::deserialize(data),
+			Some(b',') => RespDouble::deserialize(data),
+			Some(b'#') => RespBoolean::deserialize(data),
+			Some(b'(') => RespBigNumber::deserialize(data),
+			Some(b'!') => RespBulkError::deserialize(data),
+			Some(b'=') => RespVerbatimString::deserialize(data),
+			Some(b'%') => RespMap::deserialize(data),
+			Some(b'~') => RespSet::deserialize(data),
+			Some(b'|') => RespAttribute::deserialize(data),
+			Some(b'>') => RespPush::deserialize(data),
+			Some(c) => Err(RespParseError::Invalid(format!("unknown data type '{}'", c.escape_ascii()))),
+			None => Err(RespParseError::Incomplete),
 		}
 	}
 }
 
+/// Writes `prefix`, `value` and a terminator, e.g. the length header of an aggregate or bulk value.
+pub(crate) fn write_line<B: BufMut>(buf: &mut B, prefix: u8, value: impl Display) {
+	buf.put_u8(prefix);
+	buf.put_slice(value.to_string().as_bytes());
+	buf.put_slice(RESP_TERMINATOR.as_bytes());
+}
+
+/// Reads the line starting at `start` up to the next terminator.
+/// Returns the line without its terminator and the offset of the first byte after the terminator.
+pub(crate) fn read_line(data: &[u8], start: usize) -> Result<(&[u8], usize), RespParseError> {
+	match data[start..].windows(2).position(|w| w == RESP_TERMINATOR.as_bytes()) {
+		Some(i) => Ok((&data[start..start + i], start + i + 2)),
+		None => Err(RespParseError::Incomplete),
+	}
+}
+
+/// Reads the length header of an aggregate or bulk value, i.e. everything between the type byte and the first terminator.
+/// Returns the announced length and the offset of the first byte after the header.
+pub(crate) fn read_length(data: &[u8], kind: &str) -> Result<(i64, usize), RespParseError> {
+	let (line, offset) = match read_line(data, 1) {
+		Err(RespParseError::Incomplete) if data.len() > MAX_LENGTH_HEADER => {
+			return Err(RespParseError::Invalid(format!("too big {kind} count string")));
+		},
+		result => result?,
+	};
+	match std::str::from_utf8(line).ok().and_then(|l| l.parse::<i64>().ok()) {
+		Some(l) => Ok((l, offset)),
+		None => Err(RespParseError::Invalid(format!("invalid {kind} length"))),
+	}
+}
+
+/// Reads the length header of an aggregate, which has to announce a non-negative number of elements.
+pub(crate) fn read_aggregate_length(data: &[u8]) -> Result<(usize, usize), RespParseError> {
+	let (length, offset) = read_length(data, "multibulk")?;
+	if !(0..=MAX_MULTIBULK_LENGTH).contains(&length) {
+		return Err(RespParseError::Invalid(String::from("invalid multibulk length")));
+	}
+	Ok((length as usize, offset))
+}
+
+/// Reads `count` consecutive values starting at `offset`.
+/// Returns the values and the offset of the first byte after the last value.
+pub(crate) fn read_elements(data: &[u8], mut offset: usize, count: usize) -> Result<(Vec<RespValues>, usize), RespParseError> {
+	let mut values = Vec::new();
+	for _ in 0..count {
+		let (consumed, value) = RespValues::deserialize(&data[offset..])?;
+		values.push(value);
+		offset += consumed;
+	}
+	Ok((values, offset))
+}
+
+/// Reads `count` consecutive key value pairs starting at `offset`, the elements of maps and attributes.
+/// Returns the pairs and the offset of the first byte after the last value.
+pub(crate) fn read_pairs(data: &[u8], offset: usize, count: usize) -> Result<(Vec<(RespValues, RespValues)>, usize), RespParseError> {
+	let (elements, offset) = read_elements(data, offset, count * 2)?;
+	let mut elements = elements.into_iter();
+	let mut pairs = Vec::with_capacity(count);
+	while let (Some(k), Some(v)) = (elements.next(), elements.next()) {
+		pairs.push((k, v));
+	}
+	Ok((pairs, offset))
+}
+
+/// Reads a length prefixed, binary safe payload followed by a terminator.
+/// Returns the payload and the offset of the first byte after the terminator.
+pub(crate) fn read_blob<'a>(data: &'a [u8], kind: &str) -> Result<(&'a [u8], usize), RespParseError> {
+	let (length, offset) = read_length(data, kind)?;
+	if !(0..=MAX_BULK_LENGTH).contains(&length) {
+		return Err(RespParseError::Invalid(format!("invalid {kind} length")));
+	}
+	let length = length as usize;
+	if data.len() < offset + length + 2 {
+		return Err(RespParseError::Incomplete);
+	}
+	if &data[offset + length..offset + length + 2] != RESP_TERMINATOR.as_bytes() {
+		return Err(RespParseError::Invalid(format!("{kind} is not terminated by CRLF")));
+	}
+	Ok((&data[offset..offset + length], offset + length + 2))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Parses `data` as a single value and checks that serializing it gives `data` back.
+	fn round_trip(data: &[u8]) {
+		let (consumed, value) = RespValues::deserialize(data).unwrap();
+		assert_eq!(consumed, data.len());
+		assert_eq!(value.to_bytes(RespProtocol::Resp3).as_ref(), data);
+	}
+
+	#[test]
+	fn scalars_round_trip() {
+		round_trip(b":-42\r\n");
+		round_trip(b",1.5\r\n");
+		round_trip(b",inf\r\n");
+		round_trip(b",1e300\r\n");
+		round_trip(b"#t\r\n");
+		round_trip(b"#f\r\n");
+		round_trip(b"(3492890328409238509324850943850943825024385\r\n");
+		round_trip(b"_\r\n");
+	}
+
+	#[test]
+	fn blobs_round_trip() {
+		round_trip(b"!21\r\nSYNTAX invalid syntax\r\n");
+		round_trip(b"=15\r\ntxt:Some string\r\n");
+		round_trip(b"$5\r\na\r\nb\0\r\n");
+	}
+
+	#[test]
+	fn aggregates_round_trip() {
+		round_trip(b"%2\r\n+first\r\n:1\r\n$6\r\nsecond\r\n#f\r\n");
+		round_trip(b"~3\r\n:1\r\n,2.5\r\n+three\r\n");
+		round_trip(b"|1\r\n+key-popularity\r\n%1\r\n$1\r\na\r\n,0.1923\r\n");
+		round_trip(b">3\r\n+message\r\n+channel\r\n$5\r\nhello\r\n");
+		round_trip(b"*2\r\n%0\r\n~1\r\n*1\r\n#t\r\n");
+	}
+
+	#[test]
+	fn constructed_values_round_trip() {
+		let values = [
+			RespValues::Integer(RespInteger::from_raw(i64::MIN)),
+			RespValues::Double(RespDouble::from_raw(-0.1)),
+			RespValues::BigNumber(RespBigNumber::from_str("-18446744073709551616").unwrap()),
+			RespValues::VerbatimString(RespVerbatimString::from_raw(*b"mkd", "# Title")),
+			RespValues::Map(RespMap::from_raw(vec![
+				(RespValues::BulkString(RespBulkString::from_raw("k")), RespValues::Set(RespSet::from_raw(Vec::new()))),
+			])),
+		];
+		for value in values {
+			let data = value.to_bytes(RespProtocol::Resp3);
+			assert_eq!(RespValues::deserialize(&data).unwrap(), (data.len(), value));
+		}
+	}
+
+	#[test]
+	fn resp3_types_are_downgraded_for_resp2() {
+		let resp2 = |data: &[u8]| RespValues::deserialize(data).unwrap().1.to_bytes(RespProtocol::Resp2);
+		assert_eq!(resp2(b"#t\r\n").as_ref(), b":1\r\n");
+		assert_eq!(resp2(b",1.5\r\n").as_ref(), b"$3\r\n1.5\r\n");
+		assert_eq!(resp2(b"=15\r\ntxt:Some string\r\n").as_ref(), b"$11\r\nSome string\r\n");
+		assert_eq!(resp2(b"%1\r\n+a\r\n:1\r\n").as_ref(), b"*2\r\n+a\r\n:1\r\n");
+		assert_eq!(resp2(b"~1\r\n+a\r\n").as_ref(), b"*1\r\n+a\r\n");
+		assert_eq!(resp2(b">1\r\n+a\r\n").as_ref(), b"*1\r\n+a\r\n");
+		assert_eq!(resp2(b"|1\r\n+a\r\n:1\r\n").as_ref(), b"");
+		assert_eq!(resp2(b"!3\r\nERR\r\n").as_ref(), b"-ERR\r\n");
+	}
+
+	#[test]
+	fn partial_values_are_incomplete() {
+		let data = b"%2\r\n+first\r\n:1\r\n$6\r\nsecond\r\n#f\r\n";
+		for end in 1..data.len() {
+			assert_eq!(RespValues::deserialize(&data[..end]), Err(RespParseError::Incomplete));
+		}
+	}
+
+	#[test]
+	fn rejects_malformed_values() {
+		assert!(matches!(RespValues::deserialize(b"#x\r\n"), Err(RespParseError::Invalid(_))));
+		assert!(matches!(RespValues::deserialize(b",abc\r\n"), Err(RespParseError::Invalid(_))));
+		assert!(matches!(RespValues::deserialize(b"=3\r\ntxt\r\n"), Err(RespParseError::Invalid(_))));
+		assert!(matches!(RespValues::deserialize(b"%-1\r\n"), Err(RespParseError::Invalid(_))));
+	}
+}
@@ -1,6 +1,8 @@
-use crate::resp::RESP_TERMINATOR;
+use bytes::BufMut;
 
-use super::{RespObject, RespValues};
+use crate::resp::{read_aggregate_length, read_elements, read_length, write_line, RespParseError, RespProtocol};
+
+use super::{null::RespNull, RespObject, RespValues};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RespArray {
@@ -14,16 +16,8 @@ impl RespArray {
 		}
 	}
 
-	pub fn inner(&self) -> &Vec<RespValues> {
-		&self.values
-	}
-
-	pub fn get(&self, index: usize) -> Option<&RespValues> {
-		self.values.get(index)
-	}
-
-	pub fn set(&mut self, index: usize, element: RespValues) {
-		self.values[index] = element;
+	pub fn into_inner(self) -> Vec<RespValues> {
+		self.values
 	}
 
 	pub fn len(&self) -> usize {
@@ -32,43 +26,23 @@ impl RespArray {
 }
 
 impl RespObject for RespArray {
-	fn serialize(&self) -> String {
-		let serialized_values: Vec<String> = self.values.iter().map(|v| { v.serialize() }).collect();
-		let serialized_values = serialized_values.join("");
-		format!("*{}{}{}", self.values.len(), RESP_TERMINATOR, serialized_values)
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		write_line(buf, b'*', self.values.len());
+		self.values.iter().for_each(|v| v.serialize(buf, protocol));
 	}
 
-	fn deserialize(data: &str) -> (usize, RespValues) {
-		assert!(!data.is_empty());
-		assert_eq!(data.chars().next(), Some('*'));
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'*'));
 
-		let mut length: usize = 1;
-		let mut offset: usize = 1;
-		for i in 1..data.len() - 2 {
-			if data.chars().nth(i) == Some('\r') && data.chars().nth(i + 1) == Some('\n') {
-				offset = i + 2;
-				length = match data[1..length].parse::<usize>() {
-					Ok(l) => l,
-					Err(e) => panic!("Error reading length from RespArray ('{}'): {}", data, e)
-				};
-				break;
-			}
-			match data.chars().nth(i) {
-				Some(_) => {
-					length += 1
-				},
-				None => panic!("Misformed RespArray: '{}'", data),
-			}
-		}
-		let mut values = Vec::new();
-		for _ in 0..length {
-			let (consumed, value) = RespValues::deserialize(&data[offset..]);
-			values.push(value);
-			offset += consumed;
+		// RESP2 encodes a null array as an array with a length of -1.
+		if let (-1, offset) = read_length(data, "multibulk")? {
+			return Ok((offset, RespValues::Null(RespNull { array: true })));
 		}
-		
-		(offset, RespValues::Array(RespArray {
+		let (length, offset) = read_aggregate_length(data)?;
+		let (values, offset) = read_elements(data, offset, length)?;
+
+		Ok((offset, RespValues::Array(RespArray {
 			values
-		}))
+		})))
 	}
-}
\ No newline at end of file
+}
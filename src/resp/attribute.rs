@@ -0,0 +1,35 @@
+use bytes::BufMut;
+
+use crate::resp::{read_aggregate_length, read_pairs, write_line, RespParseError, RespProtocol};
+
+use super::{RespObject, RespValues};
+
+/// Auxiliary key value pairs describing the reply that follows them.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespAttribute {
+	values: Vec<(RespValues, RespValues)>
+}
+
+impl RespObject for RespAttribute {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		// Attributes are purely informational and can not be represented in RESP2, so they are left out.
+		if protocol == RespProtocol::Resp2 {
+			return;
+		}
+		write_line(buf, b'|', self.values.len());
+		self.values.iter().for_each(|(k, v)| {
+			k.serialize(buf, protocol);
+			v.serialize(buf, protocol);
+		});
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'|'));
+
+		let (length, offset) = read_aggregate_length(data)?;
+		let (values, offset) = read_pairs(data, offset, length)?;
+		Ok((offset, RespValues::Attribute(RespAttribute {
+			values
+		})))
+	}
+}
@@ -0,0 +1,43 @@
+use bytes::BufMut;
+
+use crate::resp::{read_line, write_line, RespParseError, RespProtocol};
+
+use super::{bulk_string::RespBulkString, RespObject, RespValues};
+
+/// Integers outside of the signed 64 bit range, kept as their decimal representation.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespBigNumber {
+	value: String
+}
+
+impl RespBigNumber {
+	/// Returns `None` if `value` is not an optionally signed sequence of digits.
+	pub fn from_str(value: &str) -> Option<RespBigNumber> {
+		let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+		if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+			return None;
+		}
+		Some(RespBigNumber {
+			value: value.to_string()
+		})
+	}
+}
+
+impl RespObject for RespBigNumber {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		match protocol {
+			RespProtocol::Resp2 => RespBulkString::from_raw(self.value.clone()).serialize(buf, protocol),
+			RespProtocol::Resp3 => write_line(buf, b'(', &self.value),
+		}
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'('));
+
+		let (line, offset) = read_line(data, 1)?;
+		match std::str::from_utf8(line).ok().and_then(RespBigNumber::from_str) {
+			Some(v) => Ok((offset, RespValues::BigNumber(v))),
+			None => Err(RespParseError::Invalid(format!("invalid big number '{}'", line.escape_ascii()))),
+		}
+	}
+}
@@ -0,0 +1,30 @@
+use bytes::BufMut;
+
+use crate::resp::{read_line, write_line, RespParseError, RespProtocol};
+
+use super::{RespObject, RespValues};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespBoolean {
+	value: bool
+}
+
+impl RespObject for RespBoolean {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		match protocol {
+			RespProtocol::Resp2 => write_line(buf, b':', if self.value { 1 } else { 0 }),
+			RespProtocol::Resp3 => write_line(buf, b'#', if self.value { 't' } else { 'f' }),
+		}
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'#'));
+
+		let (line, offset) = read_line(data, 1)?;
+		match line {
+			b"t" => Ok((offset, RespValues::Boolean(RespBoolean { value: true }))),
+			b"f" => Ok((offset, RespValues::Boolean(RespBoolean { value: false }))),
+			_ => Err(RespParseError::Invalid(format!("invalid boolean '{}'", line.escape_ascii()))),
+		}
+	}
+}
@@ -0,0 +1,33 @@
+use bytes::{BufMut, Bytes};
+
+use crate::resp::{read_blob, write_line, RespParseError, RespProtocol, RESP_TERMINATOR};
+
+use super::{simple_error::RespSimpleError, RespObject, RespValues};
+
+/// Binary safe counterpart of `RespSimpleError`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespBulkError {
+	values: Bytes,
+}
+
+impl RespObject for RespBulkError {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		match protocol {
+			RespProtocol::Resp2 => RespSimpleError::from_str(&String::from_utf8_lossy(&self.values)).serialize(buf, protocol),
+			RespProtocol::Resp3 => {
+				write_line(buf, b'!', self.values.len());
+				buf.put_slice(&self.values);
+				buf.put_slice(RESP_TERMINATOR.as_bytes());
+			},
+		}
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'!'));
+
+		let (payload, offset) = read_blob(data, "bulk error")?;
+		Ok((offset, RespValues::BulkError(RespBulkError {
+			values: Bytes::copy_from_slice(payload),
+		})))
+	}
+}
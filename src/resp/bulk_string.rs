@@ -1,33 +1,23 @@
-use crate::resp::RESP_TERMINATOR;
+use bytes::{BufMut, Bytes};
 
-use super::{RespObject, RespValues};
+use crate::resp::{read_blob, read_length, write_line, RespParseError, RespProtocol, RESP_TERMINATOR};
+
+use super::{null::RespNull, RespObject, RespValues};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct RespBulkString {
-	values: Vec<u8>,
+	values: Bytes,
 }
 
 impl RespBulkString {
-	pub fn from_raw(values: Vec<u8>) -> RespBulkString {
+	pub fn from_raw(values: impl Into<Bytes>) -> RespBulkString {
 		RespBulkString {
-			values,
+			values: values.into(),
 		}
 	}
 
-	pub fn inner(&self) -> &Vec<u8> {
-		&self.values
-	}
-
-	pub fn as_str(&self) -> &str {
-		std::str::from_utf8(&self.values).expect("RESP should always contain valid ASCII")
-	}
-
-	pub fn get(&self, index: usize) -> Option<&u8> {
-		self.values.get(index)
-	}
-
-	pub fn set(&mut self, index: usize, element: u8) {
-		self.values[index] = element;
+	pub fn into_inner(self) -> Bytes {
+		self.values
 	}
 
 	pub fn len(&self) -> usize {
@@ -36,45 +26,23 @@ impl RespBulkString {
 }
 
 impl RespObject for RespBulkString {
-	fn serialize(&self) -> String {
-		format!("${}{}{}{}", self.len(), RESP_TERMINATOR, self.as_str(), RESP_TERMINATOR)
+	fn serialize<B: BufMut>(&self, buf: &mut B, _protocol: RespProtocol) {
+		write_line(buf, b'$', self.len());
+		buf.put_slice(&self.values);
+		buf.put_slice(RESP_TERMINATOR.as_bytes());
 	}
 
-	fn deserialize(data: &str) -> (usize, RespValues) {
-		assert!(!data.is_empty());
-		assert_eq!(data.chars().next(), Some('$'));
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'$'));
 
-		let mut length: usize = 1;
-		let mut offset: usize = 1;
-		for i in 1..data.len() - 2 {
-			if data.chars().nth(i) == Some('\r') && data.chars().nth(i + 1) == Some('\n') {
-				offset = i + 2;
-				length = match data[1..length].parse::<usize>() {
-					Ok(l) => l,
-					Err(e) => panic!("Error reading length from RespBulkString ('{}'): {}", data, e)
-				};
-				break;
-			}
-			match data.chars().nth(i) {
-				Some(_) => {
-					length += 1
-				},
-				None => panic!("Misformed RespBulkString: '{}'", data),
-			}
+		// RESP2 encodes a null bulk string as a bulk string with a length of -1.
+		if let (-1, offset) = read_length(data, "bulk")? {
+			return Ok((offset, RespValues::Null(RespNull { array: false })));
 		}
+		let (values, offset) = read_blob(data, "bulk")?;
 
-		let mut values: Vec<u8> = Vec::new();
-		for i in 0..length {
-			match data.as_bytes().get(offset + i).copied() {
-				Some(c) => values.push(c),
-				None => panic!("Misformed RespSimpleString: '{}'", data),
-			}
-		}
-		assert_eq!(data.chars().nth(offset + length), Some('\r'));
-		assert_eq!(data.chars().nth(offset + length + 1), Some('\n'));
-		
-		(offset + length + 2, RespValues::BulkString(RespBulkString {
-			values,
-		}))
+		Ok((offset, RespValues::BulkString(RespBulkString {
+			values: Bytes::copy_from_slice(values),
+		})))
 	}
-}
\ No newline at end of file
+}
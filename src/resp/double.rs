@@ -0,0 +1,74 @@
+use std::cmp::Ordering;
+
+use bytes::BufMut;
+
+use crate::resp::{read_line, write_line, RespParseError, RespProtocol};
+
+use super::{bulk_string::RespBulkString, RespObject, RespValues};
+
+/// Doubles are compared by their bit pattern so `RespValues` can stay `Eq` and `Ord`, even with NaN.
+#[derive(Clone, Debug)]
+pub struct RespDouble {
+	value: f64
+}
+
+impl RespDouble {
+	pub fn from_raw(value: f64) -> RespDouble {
+		RespDouble {
+			value
+		}
+	}
+}
+
+impl PartialEq for RespDouble {
+	fn eq(&self, other: &Self) -> bool {
+		self.value.total_cmp(&other.value) == Ordering::Equal
+	}
+}
+
+impl Eq for RespDouble {}
+
+impl PartialOrd for RespDouble {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for RespDouble {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.value.total_cmp(&other.value)
+	}
+}
+
+/// Formats `value` with the shortest representation that parses back to the same double,
+/// switching to exponent notation for very large or very small magnitudes.
+pub fn format_double(value: f64) -> String {
+	if value.is_nan() {
+		String::from("nan")
+	} else if value.is_infinite() {
+		String::from(if value > 0.0 { "inf" } else { "-inf" })
+	} else if value == 0.0 || (1e-5..1e17).contains(&value.abs()) {
+		format!("{}", value)
+	} else {
+		format!("{:e}", value)
+	}
+}
+
+impl RespObject for RespDouble {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		match protocol {
+			RespProtocol::Resp2 => RespBulkString::from_raw(format_double(self.value)).serialize(buf, protocol),
+			RespProtocol::Resp3 => write_line(buf, b',', format_double(self.value)),
+		}
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b','));
+
+		let (line, offset) = read_line(data, 1)?;
+		match std::str::from_utf8(line).ok().and_then(|l| l.parse::<f64>().ok()) {
+			Some(value) => Ok((offset, RespValues::Double(RespDouble { value }))),
+			None => Err(RespParseError::Invalid(format!("invalid double '{}'", line.escape_ascii()))),
+		}
+	}
+}
@@ -0,0 +1,183 @@
+use bytes::Bytes;
+
+use crate::resp::{RespParseError, RespValues};
+
+use super::{array::RespArray, bulk_string::RespBulkString};
+
+/// Maximum length of an inline request line, larger lines are most likely not meant for us.
+const MAX_INLINE_LENGTH: usize = 64 * 1024;
+
+/// Parses an inline request, i.e. a single line of space separated arguments like `SET key "some value"`,
+/// as typed into telnet or netcat. The line may end in `\n` or `\r\n`.
+/// Returns the consumed bytes and the arguments as an array of bulk strings, the same shape as a regular request.
+pub fn deserialize_inline(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+	let end = match data.iter().position(|c| *c == b'\n') {
+		Some(end) => end,
+		None if data.len() > MAX_INLINE_LENGTH => return Err(RespParseError::Invalid(String::from("too big inline request"))),
+		None => return Err(RespParseError::Incomplete),
+	};
+	let line = data[..end].strip_suffix(b"\r").unwrap_or(&data[..end]);
+
+	let args = split_arguments(line)?.into_iter()
+		.map(|a| RespValues::BulkString(RespBulkString::from_raw(a)))
+		.collect();
+	Ok((end + 1, RespValues::Array(RespArray::from_raw(args))))
+}
+
+/// Splits `line` into arguments the same way `redis-cli` and the Redis inline protocol do.
+/// Arguments are separated by whitespace and may be quoted. Double quoted arguments support the escapes
+/// `\n`, `\r`, `\t`, `\b`, `\a` and `\xHH`, single quoted arguments only support `\'`.
+fn split_arguments(line: &[u8]) -> Result<Vec<Bytes>, RespParseError> {
+	let unbalanced = || RespParseError::Invalid(String::from("unbalanced quotes in request"));
+	let mut args = Vec::new();
+	let mut i = 0;
+
+	loop {
+		while i < line.len() && line[i].is_ascii_whitespace() {
+			i += 1;
+		}
+		if i == line.len() {
+			return Ok(args);
+		}
+
+		let mut arg = Vec::new();
+		let mut in_double_quotes = false;
+		let mut in_single_quotes = false;
+		loop {
+			let c = line.get(i).copied();
+			if in_double_quotes {
+				match c {
+					None => return Err(unbalanced()),
+					Some(b'\\') if line.get(i + 1) == Some(&b'x') && hex_byte(line.get(i + 2..i + 4)).is_some() => {
+						arg.push(hex_byte(line.get(i + 2..i + 4)).unwrap());
+						i += 3;
+					},
+					Some(b'\\') if i + 1 < line.len() => {
+						i += 1;
+						arg.push(match line[i] {
+							b'n' => b'\n',
+							b'r' => b'\r',
+							b't' => b'\t',
+							b'b' => 0x08,
+							b'a' => 0x07,
+							c => c,
+						});
+					},
+					Some(b'"') => {
+						// The closing quote has to be followed by a space or the end of the line.
+						if line.get(i + 1).is_some_and(|c| !c.is_ascii_whitespace()) {
+							return Err(unbalanced());
+						}
+						in_double_quotes = false;
+					},
+					Some(c) => arg.push(c),
+				}
+			} else if in_single_quotes {
+				match c {
+					None => return Err(unbalanced()),
+					Some(b'\\') if line.get(i + 1) == Some(&b'\'') => {
+						arg.push(b'\'');
+						i += 1;
+					},
+					Some(b'\'') => {
+						if line.get(i + 1).is_some_and(|c| !c.is_ascii_whitespace()) {
+							return Err(unbalanced());
+						}
+						in_single_quotes = false;
+					},
+					Some(c) => arg.push(c),
+				}
+			} else {
+				match c {
+					None => break,
+					Some(c) if c.is_ascii_whitespace() => break,
+					Some(b'"') => in_double_quotes = true,
+					Some(b'\'') => in_single_quotes = true,
+					Some(c) => arg.push(c),
+				}
+			}
+			i += 1;
+		}
+		args.push(Bytes::from(arg));
+	}
+}
+
+fn hex_byte(digits: Option<&[u8]>) -> Option<u8> {
+	let digits = std::str::from_utf8(digits?).ok()?;
+	if !digits.bytes().all(|c| c.is_ascii_hexdigit()) {
+		return None;
+	}
+	u8::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::test_util::bytes;
+
+	use super::*;
+
+	fn split(line: &str) -> Result<Vec<Bytes>, RespParseError> {
+		split_arguments(line.as_bytes())
+	}
+
+	#[test]
+	fn parses_lines_into_requests() {
+		let (consumed, request) = deserialize_inline(b"SET key value\r\nPING\r\n").unwrap();
+		assert_eq!(consumed, 15);
+		let expected = RespArray::from_raw(vec![
+			RespValues::BulkString(RespBulkString::from_raw("SET")),
+			RespValues::BulkString(RespBulkString::from_raw("key")),
+			RespValues::BulkString(RespBulkString::from_raw("value")),
+		]);
+		assert_eq!(request, RespValues::Array(expected));
+		assert_eq!(deserialize_inline(b"PING\n").unwrap().0, 5);
+	}
+
+	#[test]
+	fn empty_lines_are_empty_requests() {
+		assert_eq!(deserialize_inline(b"\r\n").unwrap(), (2, RespValues::Array(RespArray::from_raw(Vec::new()))));
+		assert_eq!(deserialize_inline(b" \t \n").unwrap(), (4, RespValues::Array(RespArray::from_raw(Vec::new()))));
+	}
+
+	#[test]
+	fn partial_lines_are_incomplete() {
+		assert_eq!(deserialize_inline(b"PING"), Err(RespParseError::Incomplete));
+		assert_eq!(deserialize_inline(b"SET key \"value\r"), Err(RespParseError::Incomplete));
+		let line = vec![b'a'; MAX_INLINE_LENGTH + 1];
+		assert!(matches!(deserialize_inline(&line), Err(RespParseError::Invalid(_))));
+	}
+
+	#[test]
+	fn splits_on_whitespace() {
+		assert_eq!(split("  SET\tkey   value  "), Ok(bytes(&["SET", "key", "value"])));
+	}
+
+	#[test]
+	fn parses_quoted_arguments() {
+		assert_eq!(split(r#"SET "some value" 'other value'"#), Ok(bytes(&["SET", "some value", "other value"])));
+		assert_eq!(split(r#""" ''"#), Ok(bytes(&["", ""])));
+		// Quotes may start in the middle of an argument.
+		assert_eq!(split(r#"a"b c" d"#), Ok(bytes(&["ab c", "d"])));
+	}
+
+	#[test]
+	fn parses_escapes_in_double_quotes() {
+		assert_eq!(split(r#""a\nb\r\t\b\a\"\\""#), Ok(bytes(&["a\nb\r\t\x08\x07\"\\"])));
+		assert_eq!(split(r#""\x41\xff\x00""#), Ok(vec![Bytes::from_static(b"A\xff\x00")]));
+		// Invalid hex escapes are taken literally, without the backslash.
+		assert_eq!(split(r#""\xZZ" "\x4""#), Ok(bytes(&["xZZ", "x4"])));
+	}
+
+	#[test]
+	fn single_quotes_only_escape_single_quotes() {
+		assert_eq!(split(r"'it\'s \n'"), Ok(bytes(&["it's \\n"])));
+	}
+
+	#[test]
+	fn rejects_unbalanced_quotes() {
+		for line in [r#""open"#, "'open", r#""closed"text"#, "'closed'text", r#""escaped\""#, r#""trailing\"#, r#""\x"#] {
+			assert!(matches!(split(line), Err(RespParseError::Invalid(_))), "{line}");
+		}
+		assert!(matches!(deserialize_inline(b"GET \"key\r\n"), Err(RespParseError::Invalid(_))));
+	}
+}
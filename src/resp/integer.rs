@@ -0,0 +1,34 @@
+use bytes::BufMut;
+
+use crate::resp::{read_line, write_line, RespParseError, RespProtocol};
+
+use super::{RespObject, RespValues};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespInteger {
+	value: i64
+}
+
+impl RespInteger {
+	pub fn from_raw(value: i64) -> RespInteger {
+		RespInteger {
+			value
+		}
+	}
+}
+
+impl RespObject for RespInteger {
+	fn serialize<B: BufMut>(&self, buf: &mut B, _protocol: RespProtocol) {
+		write_line(buf, b':', self.value);
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b':'));
+
+		let (line, offset) = read_line(data, 1)?;
+		match std::str::from_utf8(line).ok().and_then(|l| l.parse::<i64>().ok()) {
+			Some(value) => Ok((offset, RespValues::Integer(RespInteger { value }))),
+			None => Err(RespParseError::Invalid(format!("invalid integer '{}'", line.escape_ascii()))),
+		}
+	}
+}
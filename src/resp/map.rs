@@ -0,0 +1,43 @@
+use bytes::BufMut;
+
+use crate::resp::{read_aggregate_length, read_pairs, write_line, RespParseError, RespProtocol};
+
+use super::{RespObject, RespValues};
+
+/// Key value pairs in the order they were inserted.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespMap {
+	values: Vec<(RespValues, RespValues)>
+}
+
+impl RespMap {
+	pub fn from_raw(values: Vec<(RespValues, RespValues)>) -> RespMap {
+		RespMap {
+			values
+		}
+	}
+}
+
+impl RespObject for RespMap {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		// RESP2 has no maps, so they are flattened into an array of alternating keys and values.
+		match protocol {
+			RespProtocol::Resp2 => write_line(buf, b'*', self.values.len() * 2),
+			RespProtocol::Resp3 => write_line(buf, b'%', self.values.len()),
+		}
+		self.values.iter().for_each(|(k, v)| {
+			k.serialize(buf, protocol);
+			v.serialize(buf, protocol);
+		});
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'%'));
+
+		let (length, offset) = read_aggregate_length(data)?;
+		let (values, offset) = read_pairs(data, offset, length)?;
+		Ok((offset, RespValues::Map(RespMap {
+			values
+		})))
+	}
+}
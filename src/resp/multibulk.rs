@@ -0,0 +1,155 @@
+use bytes::Bytes;
+
+use crate::resp::{read_blob, read_length, RespParseError, RespValues, MAX_MULTIBULK_LENGTH};
+
+use super::{array::RespArray, bulk_string::RespBulkString};
+
+/// Parses a request sent as an array of bulk strings, the only aggregate clients send requests as.
+/// Unlike `RespValues::deserialize`, nested values are rejected instead of parsed recursively,
+/// so a client can't exhaust the stack with deeply nested arrays.
+/// Like Redis, an array announcing no elements is an empty request.
+pub fn deserialize_multibulk(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+	assert_eq!(data.first(), Some(&b'*'));
+
+	let (length, mut offset) = read_length(data, "multibulk")?;
+	if length > MAX_MULTIBULK_LENGTH {
+		return Err(RespParseError::Invalid(String::from("invalid multibulk length")));
+	}
+	let mut args = Vec::new();
+	for _ in 0..length.max(0) {
+		match data.get(offset) {
+			Some(b'$') => (),
+			Some(c) => return Err(RespParseError::Invalid(format!("expected '$', got '{}'", c.escape_ascii()))),
+			None => return Err(RespParseError::Incomplete),
+		}
+		let (arg, consumed) = read_blob(&data[offset..], "bulk")?;
+		args.push(RespValues::BulkString(RespBulkString::from_raw(Bytes::copy_from_slice(arg))));
+		offset += consumed;
+	}
+	Ok((offset, RespValues::Array(RespArray::from_raw(args))))
+}
+
+/// Finds the end of a request sent as an array of bulk strings without parsing it, see `deserialize_multibulk`.
+/// Keeps its position between calls, so a request that arrives in many reads is scanned only once
+/// instead of being parsed again from the start after every read.
+#[derive(Default)]
+pub struct MultibulkScanner {
+	/// The offset of the next element, 0 until the header was read.
+	offset: usize,
+	/// The number of elements after `offset`.
+	remaining: usize,
+}
+
+impl MultibulkScanner {
+	/// Returns the length of the request at the start of `data` once it is complete.
+	/// `data` has to start with the bytes passed to the previous calls, until the request is complete or an error is returned.
+	/// Then the scanner starts over with the next request.
+	pub fn scan(&mut self, data: &[u8]) -> Result<usize, RespParseError> {
+		let result = self.advance(data);
+		if !matches!(result, Err(RespParseError::Incomplete)) {
+			*self = MultibulkScanner::default();
+		}
+		result
+	}
+
+	fn advance(&mut self, data: &[u8]) -> Result<usize, RespParseError> {
+		if self.offset == 0 {
+			let (length, offset) = read_length(data, "multibulk")?;
+			if length > MAX_MULTIBULK_LENGTH {
+				return Err(RespParseError::Invalid(String::from("invalid multibulk length")));
+			}
+			self.offset = offset;
+			self.remaining = length.max(0) as usize;
+		}
+		while self.remaining > 0 {
+			match data.get(self.offset) {
+				Some(b'$') => (),
+				Some(c) => return Err(RespParseError::Invalid(format!("expected '$', got '{}'", c.escape_ascii()))),
+				None => return Err(RespParseError::Incomplete),
+			}
+			let (_, consumed) = read_blob(&data[self.offset..], "bulk")?;
+			self.offset += consumed;
+			self.remaining -= 1;
+		}
+		Ok(self.offset)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_arrays_of_bulk_strings() {
+		let (consumed, request) = deserialize_multibulk(b"*2\r\n$4\r\nECHO\r\n$2\r\nhi\r\n*1").unwrap();
+		assert_eq!(consumed, 22);
+		let expected = RespArray::from_raw(vec![
+			RespValues::BulkString(RespBulkString::from_raw("ECHO")),
+			RespValues::BulkString(RespBulkString::from_raw("hi")),
+		]);
+		assert_eq!(request, RespValues::Array(expected));
+	}
+
+	#[test]
+	fn empty_arrays_are_empty_requests() {
+		assert_eq!(deserialize_multibulk(b"*0\r\n").unwrap(), (4, RespValues::Array(RespArray::from_raw(Vec::new()))));
+		assert_eq!(deserialize_multibulk(b"*-1\r\n").unwrap(), (5, RespValues::Array(RespArray::from_raw(Vec::new()))));
+	}
+
+	#[test]
+	fn partial_requests_are_incomplete() {
+		let request = b"*2\r\n$4\r\nECHO\r\n$2\r\nhi\r\n";
+		for end in 1..request.len() {
+			assert_eq!(deserialize_multibulk(&request[..end]), Err(RespParseError::Incomplete));
+		}
+	}
+
+	#[test]
+	fn rejects_other_element_types() {
+		assert!(matches!(deserialize_multibulk(b"*1\r\n:1\r\n"), Err(RespParseError::Invalid(_))));
+		assert!(matches!(deserialize_multibulk(b"*1\r\n$-1\r\n"), Err(RespParseError::Invalid(_))));
+	}
+
+	#[test]
+	fn rejects_nested_arrays_without_recursing() {
+		let request = b"*1\r\n".repeat(200_000);
+		assert!(matches!(deserialize_multibulk(&request), Err(RespParseError::Invalid(_))));
+	}
+
+	#[test]
+	fn rejects_overlong_headers() {
+		let mut request = b"*1".to_vec();
+		request.extend(vec![b'1'; 64 * 1024]);
+		assert!(matches!(deserialize_multibulk(&request), Err(RespParseError::Invalid(_))));
+		let mut request = b"*1\r\n$1".to_vec();
+		request.extend(vec![b'1'; 64 * 1024]);
+		assert!(matches!(deserialize_multibulk(&request), Err(RespParseError::Invalid(_))));
+	}
+
+	#[test]
+	fn scanner_finds_the_end_of_requests_read_in_pieces() {
+		let request = b"*3\r\n$3\r\nSET\r\n$3\r\nkey\r\n$5\r\nvalue\r\n*1\r\n";
+		let length = request.len() - 4;
+		let mut scanner = MultibulkScanner::default();
+		for end in 1..length {
+			assert_eq!(scanner.scan(&request[..end]), Err(RespParseError::Incomplete));
+		}
+		// Elements that were complete are not scanned again.
+		assert_eq!((scanner.offset, scanner.remaining), (22, 1));
+		assert_eq!(scanner.scan(request), Ok(length));
+		assert_eq!(deserialize_multibulk(request).unwrap().0, length);
+		// The scanner starts over with the next request.
+		assert_eq!(scanner.scan(b"*0\r\n"), Ok(4));
+		assert_eq!(scanner.scan(b"*-1\r\n"), Ok(5));
+	}
+
+	#[test]
+	fn scanner_rejects_what_the_parser_rejects() {
+		let mut scanner = MultibulkScanner::default();
+		for request in [b"*1\r\n:1\r\n".as_slice(), b"*1\r\n$-1\r\n", b"*1\r\n*1\r\n", b"*x\r\n", b"*1\r\n$1\r\nab\r\n"] {
+			assert!(matches!(scanner.scan(request), Err(RespParseError::Invalid(_))));
+			assert!(matches!(deserialize_multibulk(request), Err(RespParseError::Invalid(_))));
+		}
+		assert_eq!(scanner.scan(b"*1\r\n$1\r\na\r\n"), Ok(11));
+	}
+}
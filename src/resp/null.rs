@@ -1,26 +1,34 @@
-use crate::resp::RESP_TERMINATOR;
+use bytes::BufMut;
+
+use crate::resp::{read_line, RespParseError, RespProtocol, RESP_TERMINATOR};
 
 use super::{RespObject, RespValues};
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct RespNull {}
+pub struct RespNull {
+	/// RESP2 has null bulk strings and null arrays, replies that are otherwise arrays use the latter.
+	pub array: bool,
+}
 
 impl RespObject for RespNull {
-	fn serialize(&self) -> String {
-		// NOTE: CodeCrafters tests with RESP2 which uses null bulk string replys for gets that do not return a value.
-		// The null bulk string reply has already been extracted into its own value,
-		// because representing a null bulk string reply as its own value is easier to implement and is the case
-		// for future versions of RESP.
-		// TODO: Maybe switch to RESP3 in the future which uses the following special null value: _\r\n
-		format!("$-1{}", RESP_TERMINATOR)
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		// NOTE: RESP2 has no dedicated null value and uses null bulk string or null array replies instead.
+		// Commands always reply with this value, so the encoding only depends on the negotiated protocol.
+		match protocol {
+			RespProtocol::Resp2 if self.array => buf.put_slice(b"*-1"),
+			RespProtocol::Resp2 => buf.put_slice(b"$-1"),
+			RespProtocol::Resp3 => buf.put_u8(b'_'),
+		}
+		buf.put_slice(RESP_TERMINATOR.as_bytes());
 	}
 
-	fn deserialize(data: &str) -> (usize, RespValues) {
-		assert!(!data.is_empty());
-		assert_eq!(data.chars().next(), Some('_'));
-		assert_eq!(data.chars().nth(1), Some('\r'));
-		assert_eq!(data.chars().nth(1), Some('\n'));
-		
-		(3, RespValues::Null(RespNull {}))
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'_'));
+
+		let (line, offset) = read_line(data, 1)?;
+		if !line.is_empty() {
+			return Err(RespParseError::Invalid(format!("unexpected content in null value: '{}'", line.escape_ascii())));
+		}
+		Ok((offset, RespValues::Null(RespNull { array: false })))
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,31 @@
+use bytes::BufMut;
+
+use crate::resp::{read_aggregate_length, read_elements, write_line, RespParseError, RespProtocol};
+
+use super::{RespObject, RespValues};
+
+/// Out of band data the server sends without a matching request, e.g. pub/sub messages.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespPush {
+	values: Vec<RespValues>
+}
+
+impl RespObject for RespPush {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		match protocol {
+			RespProtocol::Resp2 => write_line(buf, b'*', self.values.len()),
+			RespProtocol::Resp3 => write_line(buf, b'>', self.values.len()),
+		}
+		self.values.iter().for_each(|v| v.serialize(buf, protocol));
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'>'));
+
+		let (length, offset) = read_aggregate_length(data)?;
+		let (values, offset) = read_elements(data, offset, length)?;
+		Ok((offset, RespValues::Push(RespPush {
+			values
+		})))
+	}
+}
@@ -0,0 +1,39 @@
+use bytes::BufMut;
+
+use crate::resp::{read_aggregate_length, read_elements, write_line, RespParseError, RespProtocol};
+
+use super::{RespObject, RespValues};
+
+/// An unordered collection of distinct values.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespSet {
+	values: Vec<RespValues>
+}
+
+impl RespSet {
+	pub fn from_raw(values: Vec<RespValues>) -> RespSet {
+		RespSet {
+			values
+		}
+	}
+}
+
+impl RespObject for RespSet {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		match protocol {
+			RespProtocol::Resp2 => write_line(buf, b'*', self.values.len()),
+			RespProtocol::Resp3 => write_line(buf, b'~', self.values.len()),
+		}
+		self.values.iter().for_each(|v| v.serialize(buf, protocol));
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'~'));
+
+		let (length, offset) = read_aggregate_length(data)?;
+		let (values, offset) = read_elements(data, offset, length)?;
+		Ok((offset, RespValues::Set(RespSet {
+			values
+		})))
+	}
+}
@@ -0,0 +1,33 @@
+use bytes::BufMut;
+
+use crate::resp::{read_line, write_line, RespParseError, RespProtocol};
+
+use super::{RespObject, RespValues};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespSimpleError {
+	value: String
+}
+
+impl RespSimpleError {
+	/// `value` starts with the error code, e.g. `ERR syntax error` or `WRONGTYPE ...`.
+	pub fn from_str(value: &str) -> RespSimpleError {
+		RespSimpleError {
+			// Errors are sent as a single line, so terminators in the message would corrupt the reply.
+			value: value.replace(['\r', '\n'], " ")
+		}
+	}
+}
+
+impl RespObject for RespSimpleError {
+	fn serialize<B: BufMut>(&self, buf: &mut B, _protocol: RespProtocol) {
+		write_line(buf, b'-', &self.value);
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'-'));
+
+		let (value, offset) = read_line(data, 1)?;
+		Ok((offset, RespValues::Error(RespSimpleError { value: String::from_utf8_lossy(value).into_owned() })))
+	}
+}
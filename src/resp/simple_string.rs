@@ -1,4 +1,6 @@
-use crate::resp::RESP_TERMINATOR;
+use bytes::BufMut;
+
+use crate::resp::{read_line, write_line, RespParseError, RespProtocol};
 
 use super::{RespObject, RespValues};
 
@@ -17,31 +19,17 @@ impl RespSimpleString {
 	pub fn inner(&self) -> &str {
 		&self.value
 	}
-
-	pub fn len(&self) -> usize {
-		self.value.len()
-	}
 }
 
 impl RespObject for RespSimpleString {
-	fn serialize(&self) -> String {
-		format!("+{}{}", self.value, RESP_TERMINATOR)
+	fn serialize<B: BufMut>(&self, buf: &mut B, _protocol: RespProtocol) {
+		write_line(buf, b'+', &self.value);
 	}
 
-	fn deserialize(data: &str) -> (usize, RespValues) {
-		assert!(!data.is_empty());
-		assert_eq!(data.chars().next(), Some('+'));
-
-		let mut value = Vec::new();
-		for i in 1..data.len() - 1 {
-			if data.chars().nth(i) == Some('\r') && data.chars().nth(i + 1) == Some('\n') {
-				return (i + 1, RespValues::SimpleString(RespSimpleString { value: String::from_iter(value) }))
-			}
-			match data.chars().nth(i) {
-				Some(c) => value.push(c),
-				None => panic!("Misformed RespSimpleString: '{}'", data),
-			}
-		}
-		panic!("Unterminated RespSimpleString: '{}'", data);
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'+'));
+
+		let (value, offset) = read_line(data, 1)?;
+		Ok((offset, RespValues::SimpleString(RespSimpleString { value: String::from_utf8_lossy(value).into_owned() })))
 	}
-}
\ No newline at end of file
+}
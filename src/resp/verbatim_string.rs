@@ -0,0 +1,50 @@
+use bytes::{BufMut, Bytes};
+
+use crate::resp::{read_blob, write_line, RespParseError, RespProtocol, RESP_TERMINATOR};
+
+use super::{bulk_string::RespBulkString, RespObject, RespValues};
+
+/// A bulk string with a three character format hint, e.g. `txt` or `mkd`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RespVerbatimString {
+	format: [u8; 3],
+	values: Bytes,
+}
+
+impl RespVerbatimString {
+	pub fn from_raw(format: [u8; 3], values: impl Into<Bytes>) -> RespVerbatimString {
+		RespVerbatimString {
+			format,
+			values: values.into(),
+		}
+	}
+}
+
+impl RespObject for RespVerbatimString {
+	fn serialize<B: BufMut>(&self, buf: &mut B, protocol: RespProtocol) {
+		match protocol {
+			RespProtocol::Resp2 => RespBulkString::from_raw(self.values.clone()).serialize(buf, protocol),
+			RespProtocol::Resp3 => {
+				write_line(buf, b'=', self.values.len() + 4);
+				buf.put_slice(&self.format);
+				buf.put_u8(b':');
+				buf.put_slice(&self.values);
+				buf.put_slice(RESP_TERMINATOR.as_bytes());
+			},
+		}
+	}
+
+	fn deserialize(data: &[u8]) -> Result<(usize, RespValues), RespParseError> {
+		assert_eq!(data.first(), Some(&b'='));
+
+		let (payload, offset) = read_blob(data, "verbatim string")?;
+		if payload.len() < 4 || payload[3] != b':' {
+			return Err(RespParseError::Invalid(String::from("verbatim string is missing its format")));
+		}
+		let format = [payload[0], payload[1], payload[2]];
+		Ok((offset, RespValues::VerbatimString(RespVerbatimString {
+			format,
+			values: Bytes::copy_from_slice(&payload[4..]),
+		})))
+	}
+}
@@ -0,0 +1,192 @@
+use std::{ops::{Deref, DerefMut}, sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak}, time::{Duration, Instant}};
+
+use tokio::{net::{TcpListener, TcpStream}, time::{interval, MissedTickBehavior}};
+
+use crate::{blocking::serve_blocked_clients, commands::{CommandError, Commands}, connection::{Connection, ConnectionError}, replication::ReplicationInfo, store::{ExpireStats, Store}, types::EncodingLimits, util::error_reply};
+
+pub const DEFAULT_PORT: u16 = 6379;
+pub const DEFAULT_DATABASES: usize = 16;
+/// How often per second background tasks like the active expire cycle run.
+const SERVER_HZ: u64 = 10;
+/// Percentage of the time between two runs the active expire cycle may use.
+const ACTIVE_EXPIRE_CYCLE_TIME_PERC: u64 = 25;
+/// Time spent resizing each database per run of the background tasks.
+const ACTIVE_REHASH_DURATION: Duration = Duration::from_millis(1);
+
+/// Settings the server has been started with.
+pub struct ServerConfig {
+	pub port: u16,
+	/// Number of databases clients can `SELECT`.
+	pub databases: usize,
+	/// Host and port of the master, if the server runs as a replica.
+	pub replica_of: Option<(String, u16)>,
+	pub encoding_limits: EncodingLimits,
+}
+
+impl Default for ServerConfig {
+	fn default() -> Self {
+		ServerConfig {
+			port: DEFAULT_PORT,
+			databases: DEFAULT_DATABASES,
+			replica_of: None,
+			encoding_limits: EncodingLimits::default(),
+		}
+	}
+}
+
+/// A locked database. Clients blocked on keys that became ready while it was locked are served before the lock is released,
+/// so they get the elements before any other client can take them, like Redis serves them right after each command.
+pub struct StoreGuard<'a>(MutexGuard<'a, Store>);
+
+impl Deref for StoreGuard<'_> {
+	type Target = Store;
+
+	fn deref(&self) -> &Store {
+		&self.0
+	}
+}
+
+impl DerefMut for StoreGuard<'_> {
+	fn deref_mut(&mut self) -> &mut Store {
+		&mut self.0
+	}
+}
+
+impl Drop for StoreGuard<'_> {
+	fn drop(&mut self) {
+		serve_blocked_clients(&mut self.0);
+	}
+}
+
+/// State shared by all connections of a server instance.
+/// Each part sits behind its own lock, so clients may be served concurrently from any thread.
+/// Guards must be dropped before the next `.await`, which the compiler enforces for spawned tasks.
+pub struct Server {
+	config: ServerConfig,
+	databases: Vec<Mutex<Store>>,
+	replication: RwLock<ReplicationInfo>,
+	/// Counters of the active expire cycle. Expired keys are counted by each database.
+	expire_stats: Mutex<ExpireStats>,
+}
+
+impl Server {
+	pub fn new(config: ServerConfig) -> Server {
+		let replication = ReplicationInfo::new(config.replica_of.clone());
+		Server {
+			databases: (0..config.databases).map(|_| Mutex::new(Store::init())).collect(),
+			config,
+			replication: RwLock::new(replication),
+			expire_stats: Mutex::new(ExpireStats::default()),
+		}
+	}
+
+	pub fn config(&self) -> &ServerConfig {
+		&self.config
+	}
+
+	/// The database with the index `db`, which has to be lower than the configured number of databases.
+	/// Lock at most one database at a time to avoid deadlocks.
+	pub fn store(&self, db: usize) -> StoreGuard<'_> {
+		// A command panicking while holding a lock does not leave the state half updated
+		// in a way later commands could not deal with, so poisoned locks are recovered.
+		StoreGuard(self.databases[db].lock().unwrap_or_else(PoisonError::into_inner))
+	}
+
+	/// Checks a database index sent by a client.
+	pub fn db_index(&self, index: i64) -> Option<usize> {
+		usize::try_from(index).ok().filter(|&db| db < self.databases.len())
+	}
+
+	pub fn expire_stats(&self) -> ExpireStats {
+		let mut stats = self.expire_stats.lock().unwrap_or_else(PoisonError::into_inner).clone();
+		stats.expired_keys = (0..self.databases.len()).map(|db| self.store(db).expired_keys()).sum();
+		stats.expired_subkeys = (0..self.databases.len()).map(|db| self.store(db).expired_fields()).sum();
+		stats
+	}
+
+	pub fn replication(&self) -> RwLockReadGuard<'_, ReplicationInfo> {
+		self.replication.read().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	pub fn replication_mut(&self) -> RwLockWriteGuard<'_, ReplicationInfo> {
+		self.replication.write().unwrap_or_else(PoisonError::into_inner)
+	}
+
+	/// Accepts clients on `listener` and serves each of them on its own task.
+	/// Also starts the background tasks of the server.
+	pub async fn serve(self: Arc<Self>, listener: TcpListener) -> std::io::Result<()> {
+		tokio::spawn(Server::cron(Arc::downgrade(&self)));
+		loop {
+			let (stream, _) = listener.accept().await?;
+			let server = Arc::clone(&self);
+			tokio::spawn(async move {
+				server.handle_connection(stream).await;
+			});
+		}
+	}
+
+	/// Runs the periodic background tasks until the server is dropped.
+	async fn cron(server: Weak<Server>) {
+		let period = Duration::from_millis(1000 / SERVER_HZ);
+		let expire_time_limit = period * ACTIVE_EXPIRE_CYCLE_TIME_PERC as u32 / 100;
+		let mut ticks = interval(period);
+		ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+		loop {
+			ticks.tick().await;
+			let Some(server) = server.upgrade() else {
+				return;
+			};
+			server.active_expire_cycle(expire_time_limit);
+			// Keep resizing the keyspaces even if no keys are modified.
+			for db in 0..server.databases.len() {
+				server.store(db).rehash_for(ACTIVE_REHASH_DURATION);
+			}
+		}
+	}
+
+	/// Runs the active expire cycle on every database, sharing `time_limit` between them.
+	fn active_expire_cycle(&self, time_limit: Duration) {
+		let deadline = Instant::now() + time_limit;
+		let mut sampled = 0;
+		let mut expired = 0;
+		let mut timed_out = false;
+		for db in 0..self.databases.len() {
+			let cycle = self.store(db).active_expire_cycle(deadline);
+			sampled += cycle.sampled;
+			expired += cycle.expired;
+			if cycle.timed_out {
+				timed_out = true;
+				break;
+			}
+		}
+
+		let mut stats = self.expire_stats.lock().unwrap_or_else(PoisonError::into_inner);
+		if timed_out {
+			stats.expired_time_cap_reached_count += 1;
+		}
+		let current_perc = match sampled {
+			0 => 0.0,
+			_ => expired as f64 / sampled as f64,
+		};
+		stats.expired_stale_perc = current_perc * 0.05 + stats.expired_stale_perc * 0.95;
+	}
+
+	async fn handle_connection(&self, stream: TcpStream) {
+		let mut connection = Connection::new(stream);
+		loop {
+			match connection.read_request().await {
+				Ok(Some(request_data)) => Commands::dispatch(self, &mut connection, request_data).await,
+				Ok(None) => return,
+				Err(ConnectionError::Protocol(e)) => {
+					eprintln!("Terminating connection. Received invalid request: {e}");
+					error_reply(&mut connection, CommandError::Protocol(e.to_string())).await;
+					return;
+				},
+				Err(e) => {
+					eprintln!("Terminating connection. Error when reading request: {e}");
+					return;
+				},
+			}
+		}
+	}
+}
@@ -1,67 +1,534 @@
-use std::{collections::HashMap, time::{Duration, Instant}};
+use std::{collections::{BTreeSet, HashMap}, fmt::Display, time::{Duration, Instant}};
 
-struct StoreValue {
-	value: String,
-	expiry_time: Option<u64>,
-	created_at: Instant,
+use bytes::Bytes;
+
+use crate::{blocking::BlockedClients, commands::CommandError, dict::Dict, types::{string::RedisString, Value}, util::unix_time_ms};
+
+/// Number of keys with an expiry checked per round of the active expire cycle.
+const ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP: usize = 20;
+/// The active expire cycle keeps going while more than this percentage of the checked keys was expired.
+const ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE: usize = 10;
+/// Values that take more work than this to free are freed in the background by `UNLINK`.
+pub const LAZYFREE_THRESHOLD: usize = 64;
+
+#[derive(Clone)]
+pub struct StoreValue {
+	value: Value,
+	/// Unix timestamp in milliseconds.
+	expires_at: Option<i64>,
 }
 
 impl StoreValue {
-	pub fn new(value: String, expiry_time: Option<u64>) -> StoreValue {
+	pub fn new(value: Value, expires_at: Option<i64>) -> StoreValue {
 		StoreValue {
 			value,
-			expiry_time,
-			created_at: Instant::now(),
+			expires_at,
+		}
+	}
+
+	pub fn is_expired(&self) -> bool {
+		self.is_expired_at(unix_time_ms())
+	}
+
+	pub fn is_expired_at(&self, now: i64) -> bool {
+		self.expires_at.is_some_and(|expires_at| now > expires_at)
+	}
+
+	pub fn value(&self) -> &Value {
+		&self.value
+	}
+}
+
+/// The keys that have an expiry, in no particular order.
+/// Kept in a vector so the active expire cycle can walk them incrementally with a cursor.
+#[derive(Default)]
+struct VolatileKeys {
+	keys: Vec<Bytes>,
+	positions: HashMap<Bytes, usize>,
+}
+
+impl VolatileKeys {
+	fn len(&self) -> usize {
+		self.keys.len()
+	}
+
+	fn is_empty(&self) -> bool {
+		self.keys.is_empty()
+	}
+
+	fn get(&self, index: usize) -> &Bytes {
+		&self.keys[index]
+	}
+
+	fn insert(&mut self, key: Bytes) {
+		if !self.positions.contains_key(&key) {
+			self.positions.insert(key.clone(), self.keys.len());
+			self.keys.push(key);
 		}
 	}
 
-	pub fn value(&self) -> Option<&String> {
-		if let Some(expiry_time) = self.expiry_time {
-			if (Instant::now() - self.created_at) > Duration::from_millis(expiry_time) {
-				return None
+	/// Removes `key` by moving the last key into its position.
+	fn remove(&mut self, key: &[u8]) {
+		if let Some(index) = self.positions.remove(key) {
+			self.keys.swap_remove(index);
+			if let Some(moved) = self.keys.get(index) {
+				self.positions.insert(moved.clone(), index);
 			}
 		}
-		Some(&self.value)
 	}
 }
 
-pub struct Store {
-	data: HashMap<String, StoreValue>
+/// What a call of `Store::active_expire_cycle` did.
+pub struct ExpireCycle {
+	pub sampled: usize,
+	pub expired: usize,
+	pub timed_out: bool,
 }
 
-static mut GLOBAL_STORE: Option<Store> = None;
+/// Counters about expired keys, reported in the stats section of `INFO`.
+#[derive(Clone, Default)]
+pub struct ExpireStats {
+	pub expired_keys: u64,
+	/// Hash fields deleted because they expired.
+	pub expired_subkeys: u64,
+	/// Running estimate of the fraction of keys with an expiry that are already expired.
+	pub expired_stale_perc: f64,
+	pub expired_time_cap_reached_count: u64,
+}
 
-pub fn global_store() -> &'static mut Store {
-	unsafe {
-		if GLOBAL_STORE.is_none() {
-			GLOBAL_STORE = Some(Store::init())
-		}
-		GLOBAL_STORE.as_mut().unwrap()
+impl Display for ExpireStats {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		writeln!(f, "# Stats")?;
+		writeln!(f, "expired_keys:{}", self.expired_keys)?;
+		writeln!(f, "expired_subkeys:{}", self.expired_subkeys)?;
+		writeln!(f, "expired_stale_perc:{:.2}", self.expired_stale_perc * 100.0)?;
+		writeln!(f, "expired_time_cap_reached_count:{}", self.expired_time_cap_reached_count)?;
+		Ok(())
 	}
 }
 
+/// A database of the keyspace.
+/// Expired keys are deleted when they are accessed and by `active_expire_cycle`,
+/// which has to be called periodically so keys that are never accessed again are reclaimed as well.
+pub struct Store {
+	data: Dict<Bytes, StoreValue>,
+	volatile: VolatileKeys,
+	expire_cursor: usize,
+	expired_keys: u64,
+	expired_fields: u64,
+	/// Hashes with field expiries, by the time their next field expires, so the active expire cycle finds them.
+	/// Entries may be outdated, they are checked against the hash when they are due.
+	hash_field_expiries: BTreeSet<(i64, Bytes)>,
+	blocked: BlockedClients,
+}
+
 impl Store {
 	pub fn init() -> Store {
 		Store {
-			data: HashMap::new()
+			data: Dict::new(),
+			volatile: VolatileKeys::default(),
+			expire_cursor: 0,
+			expired_keys: 0,
+			expired_fields: 0,
+			hash_field_expiries: BTreeSet::new(),
+			blocked: BlockedClients::default(),
 		}
 	}
 
-	pub fn get(&self, key: String) -> Option<&String> {
-		match self.data.get(&key) {
-			Some(v) => v.value(),
-			None => None,
+	/// Number of keys deleted because they expired.
+	pub fn expired_keys(&self) -> u64 {
+		self.expired_keys
+	}
+
+	/// Number of hash fields deleted because they expired.
+	pub fn expired_fields(&self) -> u64 {
+		self.expired_fields
+	}
+
+	/// Registers that a field of the hash at `key` expires at `expires_at`, so the active expire cycle reclaims it.
+	pub fn track_field_expiry(&mut self, key: &[u8], expires_at: i64) {
+		self.hash_field_expiries.insert((expires_at, Bytes::copy_from_slice(key)));
+	}
+
+	/// The clients blocked on keys of this database.
+	pub fn blocked_clients(&mut self) -> &mut BlockedClients {
+		&mut self.blocked
+	}
+
+	pub fn entry(&mut self, key: &[u8]) -> Option<&StoreValue> {
+		self.expire_if_needed(key);
+		self.data.get(key)
+	}
+
+	pub fn get(&mut self, key: &[u8]) -> Option<&Value> {
+		self.expire_if_needed(key);
+		self.data.get(key).map(|v| &v.value)
+	}
+
+	/// Like `get`, but does not delete the key if it expired, so several values can be borrowed at once.
+	/// Expired keys are still reported as missing.
+	pub fn peek(&self, key: &[u8]) -> Option<&Value> {
+		self.data.get(key).filter(|v| !v.is_expired()).map(|v| &v.value)
+	}
+
+	pub fn get_mut(&mut self, key: &[u8]) -> Option<&mut Value> {
+		self.expire_if_needed(key);
+		self.data.get_mut(key).map(|v| &mut v.value)
+	}
+
+	/// The value of `key`, which is created without an expiry by `f` if it does not exist.
+	pub fn get_or_insert_with(&mut self, key: &[u8], f: impl FnOnce() -> Value) -> &mut Value {
+		self.expire_if_needed(key);
+		if !self.data.contains_key(key) {
+			self.data.insert(Bytes::copy_from_slice(key), StoreValue::new(f(), None));
+			self.blocked.signal_key_as_ready(key);
+		}
+		&mut self.data.get_mut(key).unwrap().value
+	}
+
+	/// Deletes `key` if its value is a container that became empty. Returns whether it was deleted.
+	pub fn delete_if_empty(&mut self, key: &[u8]) -> bool {
+		if self.data.get(key).is_some_and(|v| v.value.is_empty()) {
+			return self.delete(key);
 		}
+		false
+	}
+
+	/// The value of `key` if it is a string, `WRONGTYPE` if it is of another type.
+	pub fn get_string(&mut self, key: &[u8]) -> Result<Option<Bytes>, CommandError> {
+		match self.get(key) {
+			Some(value) => Ok(Some(value.as_string()?.to_bytes())),
+			None => Ok(None),
+		}
+	}
+
+	/// Returns the Unix timestamp in milliseconds the key expires at, or `None` if the key does not exist or has no expiry.
+	pub fn expires_at(&mut self, key: &[u8]) -> Option<i64> {
+		self.expire_if_needed(key);
+		self.data.get(key).and_then(|v| v.expires_at)
+	}
+
+	/// Sets `key` to a string. Returns the previous value.
+	pub fn set(&mut self, key: Bytes, value: Bytes, expires_at: Option<i64>) -> Option<Value> {
+		self.insert(key, StoreValue::new(Value::String(RedisString::new(value)), expires_at)).map(|v| v.value)
+	}
+
+	/// Replaces the value of `key` but keeps its expiry. Creates the key without an expiry if it does not exist.
+	pub fn update(&mut self, key: &[u8], value: Value) {
+		self.expire_if_needed(key);
+		match self.data.get_mut(key) {
+			Some(current) => current.value = value,
+			None => {
+				self.data.insert(Bytes::copy_from_slice(key), StoreValue::new(value, None));
+				self.blocked.signal_key_as_ready(key);
+			},
+		}
+	}
+
+	/// Replaces the value and the expiry of `key`. Returns the previous value.
+	pub fn insert(&mut self, key: Bytes, value: StoreValue) -> Option<StoreValue> {
+		self.expire_if_needed(&key);
+		match value.expires_at {
+			Some(_) => self.volatile.insert(key.clone()),
+			None => self.volatile.remove(&key),
+		}
+		self.blocked.signal_key_as_ready(&key);
+		if let Value::Hash(hash) = &value.value {
+			if let Some(expires_at) = hash.next_field_expiry() {
+				self.hash_field_expiries.insert((expires_at, key.clone()));
+			}
+		}
+		self.data.insert(key, value)
+	}
+
+	pub fn has(&mut self, key: &[u8]) -> bool {
+		self.expire_if_needed(key);
+		self.data.contains_key(key)
 	}
 
-	pub fn set(&mut self, key: String, value: String, expiry_time: Option<u64>) -> Option<String> {
-		match self.data.insert(key, StoreValue::new(value, expiry_time)) {
-			Some(v) => v.value().cloned(),
-			None => None,
+	/// Sets the Unix timestamp in milliseconds the key expires at, `None` removes the expiry.
+	/// Returns `false` if the key does not exist.
+	pub fn set_expires_at(&mut self, key: &[u8], expires_at: Option<i64>) -> bool {
+		self.expire_if_needed(key);
+		let Some(value) = self.data.get_mut(key) else {
+			return false;
+		};
+		value.expires_at = expires_at;
+		match expires_at {
+			Some(_) => self.volatile.insert(Bytes::copy_from_slice(key)),
+			None => self.volatile.remove(key),
 		}
+		true
 	}
 
-	pub fn has(&self, key: String) -> bool {
-		self.data.contains_key(&key)
+	/// Returns `false` if the key did not exist.
+	pub fn delete(&mut self, key: &[u8]) -> bool {
+		self.remove(key).is_some()
 	}
-}
\ No newline at end of file
+
+	pub fn remove(&mut self, key: &[u8]) -> Option<StoreValue> {
+		self.expire_if_needed(key);
+		self.volatile.remove(key);
+		self.data.remove(key)
+	}
+
+	/// Moves the value and the expiry of `from` to `to`, replacing the value of `to`.
+	/// Returns `false` if `from` does not exist.
+	pub fn rename(&mut self, from: &[u8], to: Bytes) -> bool {
+		match self.remove(from) {
+			Some(value) => {
+				self.insert(to, value);
+				true
+			},
+			None => false,
+		}
+	}
+
+	/// Calls `f` with the keys at `cursor`, see `Dict::scan`. Expired keys are skipped.
+	pub fn scan(&self, cursor: u64, mut f: impl FnMut(&Bytes, &StoreValue)) -> u64 {
+		let now = unix_time_ms();
+		self.data.scan(cursor, |key, value| {
+			if !value.is_expired_at(now) {
+				f(key, value);
+			}
+		})
+	}
+
+	/// All keys that are not expired.
+	pub fn keys(&self) -> impl Iterator<Item = &Bytes> {
+		let now = unix_time_ms();
+		self.data.iter().filter(move |(_, v)| !v.is_expired_at(now)).map(|(k, _)| k)
+	}
+
+	/// Continues resizing the keyspace for about `duration`, so it also progresses while no keys are modified.
+	pub fn rehash_for(&mut self, duration: Duration) {
+		self.data.rehash_for(duration);
+	}
+
+	pub fn random_key(&mut self) -> Option<Bytes> {
+		// If all keys have an expiry, all of them might be expired without having been deleted yet.
+		// Give up after some tries instead of deleting the whole database.
+		let mut tries = 0;
+		loop {
+			if self.data.is_empty() {
+				return None;
+			}
+			let (key, _) = self.data.random_entry()?;
+			let key = key.clone();
+			if self.volatile.len() == self.data.len() {
+				tries += 1;
+				if tries > 100 {
+					return Some(key);
+				}
+			}
+			if !self.expire_if_needed(&key) {
+				return Some(key);
+			}
+		}
+	}
+
+	/// Deletes `key` if it is expired, or the expired fields of a hash. Returns whether the key was deleted,
+	/// which is also the case if all fields of a hash expired.
+	fn expire_if_needed(&mut self, key: &[u8]) -> bool {
+		let now = unix_time_ms();
+		let Some(value) = self.data.get_mut(key) else {
+			return false;
+		};
+		if value.is_expired_at(now) {
+			self.data.remove(key);
+			self.volatile.remove(key);
+			self.expired_keys += 1;
+			return true;
+		}
+		let Value::Hash(hash) = &mut value.value else {
+			return false;
+		};
+		if hash.next_field_expiry().is_none_or(|expires_at| now <= expires_at) {
+			return false;
+		}
+		self.expired_fields += hash.expire_fields(now) as u64;
+		if hash.is_empty() {
+			self.data.remove(key);
+			self.volatile.remove(key);
+			return true;
+		}
+		false
+	}
+
+	/// Deletes the expired fields of hashes, in the order they expired, until `deadline` has passed.
+	/// Returns whether it ran out of time.
+	fn active_expire_hash_fields(&mut self, deadline: Instant) -> bool {
+		let now = unix_time_ms();
+		let mut iteration: u32 = 0;
+		while let Some((expires_at, key)) = self.hash_field_expiries.first().cloned() {
+			if expires_at >= now {
+				return false;
+			}
+			self.hash_field_expiries.pop_first();
+			self.expire_if_needed(&key);
+			// Check the hash again, the entry may have been outdated.
+			if let Some(Value::Hash(hash)) = self.data.get(&key).map(|v| &v.value) {
+				if let Some(expires_at) = hash.next_field_expiry() {
+					self.hash_field_expiries.insert((expires_at, key));
+				}
+			}
+			iteration += 1;
+			if iteration.is_multiple_of(16) && Instant::now() > deadline {
+				return true;
+			}
+		}
+		false
+	}
+
+	/// Deletes expired keys that have not been accessed since they expired.
+	/// Works like the adaptive algorithm of Redis: keys with an expiry are checked in rounds of
+	/// `ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP` and another round follows as long as too many of them were expired,
+	/// until `deadline` has passed. Consecutive calls continue where the previous call stopped.
+	pub fn active_expire_cycle(&mut self, deadline: Instant) -> ExpireCycle {
+		let mut timed_out = false;
+		let mut total_sampled = 0;
+		let mut total_expired = 0;
+		let mut iteration: u32 = 0;
+		loop {
+			let mut sampled = 0;
+			let mut expired = 0;
+			let to_sample = ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP.min(self.volatile.len());
+			while sampled < to_sample && !self.volatile.is_empty() {
+				if self.expire_cursor >= self.volatile.len() {
+					self.expire_cursor = 0;
+				}
+				let key = self.volatile.get(self.expire_cursor).clone();
+				sampled += 1;
+				// Deleting the key moves another one into the position at the cursor.
+				if self.expire_if_needed(&key) {
+					expired += 1;
+				} else {
+					self.expire_cursor += 1;
+				}
+			}
+			total_sampled += sampled;
+			total_expired += expired;
+
+			iteration += 1;
+			// Checking the time is not free, so only do it every now and then.
+			if iteration.is_multiple_of(16) && Instant::now() > deadline {
+				timed_out = true;
+				break;
+			}
+			if sampled == 0 || expired * 100 / sampled <= ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE {
+				break;
+			}
+		}
+
+		if !timed_out {
+			timed_out = self.active_expire_hash_fields(deadline);
+		}
+
+		ExpireCycle {
+			sampled: total_sampled,
+			expired: total_expired,
+			timed_out,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{types::{hash::Hash, EncodingLimits}, util::freeze_time_ms};
+
+	use super::*;
+
+	fn set(store: &mut Store, key: String, expires_at: Option<i64>) {
+		store.set(Bytes::from(key), Bytes::from_static(b"value"), expires_at);
+	}
+
+	fn expire_cycle(store: &mut Store) -> ExpireCycle {
+		store.active_expire_cycle(Instant::now() + Duration::from_secs(10))
+	}
+
+	#[test]
+	fn deletes_expired_keys_when_they_are_accessed() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		set(&mut store, String::from("volatile"), Some(1500));
+		set(&mut store, String::from("persistent"), None);
+		freeze_time_ms(Some(1500));
+		assert!(store.has(b"volatile"));
+		freeze_time_ms(Some(1501));
+		assert!(!store.has(b"volatile"));
+		assert!(store.get(b"volatile").is_none());
+		assert!(store.has(b"persistent"));
+		assert_eq!(store.expired_keys(), 1);
+	}
+
+	#[test]
+	fn active_expire_cycle_keeps_going_while_many_keys_are_expired() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		(0..100).for_each(|i| set(&mut store, format!("volatile:{i}"), Some(2000)));
+		(0..50).for_each(|i| set(&mut store, format!("persistent:{i}"), None));
+		freeze_time_ms(Some(3000));
+		let cycle = expire_cycle(&mut store);
+		assert_eq!((cycle.sampled, cycle.expired, cycle.timed_out), (100, 100, false));
+		assert_eq!(store.expired_keys(), 100);
+		assert_eq!(store.keys().count(), 50);
+	}
+
+	#[test]
+	fn active_expire_cycle_continues_where_it_stopped() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		(0..100).for_each(|i| set(&mut store, format!("key:{i}"), Some(if i == 50 { 2000 } else { 1_000_000 })));
+		freeze_time_ms(Some(3000));
+		// Few of the sampled keys are expired, so each call only checks one round of keys.
+		let expired: Vec<_> = (0..5).map(|_| expire_cycle(&mut store)).map(|cycle| (cycle.sampled, cycle.expired)).collect();
+		assert_eq!(expired, [(20, 0), (20, 0), (20, 1), (20, 0), (20, 0)]);
+		assert!(!store.data.contains_key(b"key:50".as_slice()));
+		assert_eq!(store.keys().count(), 99);
+	}
+
+	#[test]
+	fn active_expire_cycle_stops_at_the_deadline() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		(0..1000).for_each(|i| set(&mut store, format!("key:{i}"), Some(2000)));
+		freeze_time_ms(Some(3000));
+		let cycle = store.active_expire_cycle(Instant::now());
+		// The time is checked every 16 rounds.
+		assert_eq!((cycle.sampled, cycle.expired, cycle.timed_out), (16 * 20, 16 * 20, true));
+		// The remaining keys are expired, but not deleted yet.
+		assert_eq!(store.data.len(), 1000 - 16 * 20);
+	}
+
+	/// Creates a hash whose fields expire at the given times and tracks them like the commands do.
+	fn hash_with_expiries(store: &mut Store, key: &str, fields: &[(&str, i64)]) {
+		let hash = store.get_or_insert_with(key.as_bytes(), || Value::Hash(Hash::new())).as_hash_mut().unwrap();
+		for &(field, expires_at) in fields {
+			hash.insert(Bytes::copy_from_slice(field.as_bytes()), Bytes::from_static(b"value"), &EncodingLimits::default());
+			hash.set_field_expires_at(field.as_bytes(), Some(expires_at));
+		}
+		for &(_, expires_at) in fields {
+			store.track_field_expiry(key.as_bytes(), expires_at);
+		}
+	}
+
+	#[test]
+	fn active_expire_cycle_deletes_expired_hash_fields() {
+		let mut store = Store::init();
+		freeze_time_ms(Some(1000));
+		hash_with_expiries(&mut store, "expired", &[("a", 2000), ("b", 3000)]);
+		hash_with_expiries(&mut store, "partly", &[("a", 2000), ("b", 20_000)]);
+		hash_with_expiries(&mut store, "later", &[("a", 9000)]);
+		// An outdated entry, the field got a later expiry afterwards.
+		store.get_mut(b"later").unwrap().as_hash_mut().unwrap().set_field_expires_at(b"a", Some(10_000));
+		freeze_time_ms(Some(9500));
+		expire_cycle(&mut store);
+		assert!(!store.data.contains_key(b"expired".as_slice()));
+		assert_eq!(store.peek(b"partly").unwrap().as_hash().unwrap().len(), 1);
+		assert_eq!(store.peek(b"later").unwrap().as_hash().unwrap().len(), 1);
+		assert_eq!(store.expired_fields(), 3);
+		// Each remaining hash is tracked once, by the time its next field expires.
+		let tracked: Vec<_> = store.hash_field_expiries.iter().cloned().collect();
+		assert_eq!(tracked, [(10_000, Bytes::from_static(b"later")), (20_000, Bytes::from_static(b"partly"))]);
+	}
+}
@@ -0,0 +1,19 @@
+//! Fixtures shared by the tests of several modules.
+
+use bytes::Bytes;
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::connection::Connection;
+
+/// Copies `values` into `Bytes`, the way commands receive their arguments.
+pub fn bytes<T: AsRef<[u8]> + ?Sized>(values: &[&T]) -> Vec<Bytes> {
+	values.iter().map(|value| Bytes::copy_from_slice(value.as_ref())).collect()
+}
+
+/// A connection to a client over loopback, and the stream of that client.
+pub async fn connected() -> (Connection, TcpStream) {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+	let (stream, _) = listener.accept().await.unwrap();
+	(Connection::new(stream), client)
+}
@@ -0,0 +1,236 @@
+use crate::commands::CommandError;
+
+use self::{hash::Hash, list::List, set::Set, stream::Stream, string::RedisString, zset::SortedSet};
+
+pub(crate) mod hash;
+pub(crate) mod intset;
+pub(crate) mod list;
+pub(crate) mod listpack;
+pub(crate) mod set;
+pub(crate) mod skiplist;
+pub(crate) mod stream;
+pub(crate) mod string;
+pub(crate) mod zset;
+
+/// Sizes up to which values are kept in their compact encodings, named like the settings of Redis.
+#[derive(Clone)]
+pub struct EncodingLimits {
+	/// Positive values limit the entries of a list node, -1 to -5 its size to 4 to 64 kb.
+	pub list_max_listpack_size: i64,
+	pub hash_max_listpack_entries: usize,
+	pub hash_max_listpack_value: usize,
+	pub set_max_intset_entries: usize,
+	pub set_max_listpack_entries: usize,
+	pub set_max_listpack_value: usize,
+	pub zset_max_listpack_entries: usize,
+	pub zset_max_listpack_value: usize,
+}
+
+impl Default for EncodingLimits {
+	fn default() -> Self {
+		EncodingLimits {
+			list_max_listpack_size: -2,
+			hash_max_listpack_entries: 128,
+			hash_max_listpack_value: 64,
+			set_max_intset_entries: 512,
+			set_max_listpack_entries: 128,
+			set_max_listpack_value: 64,
+			zset_max_listpack_entries: 128,
+			zset_max_listpack_value: 64,
+		}
+	}
+}
+
+/// The value of a key, one of the data types of Redis.
+#[derive(Clone)]
+pub enum Value {
+	String(RedisString),
+	List(List),
+	Hash(Hash),
+	Set(Set),
+	SortedSet(SortedSet),
+	Stream(Stream),
+}
+
+impl Value {
+	/// Name of the type as reported by `TYPE`.
+	pub fn type_name(&self) -> &'static str {
+		match self {
+			Value::String(_) => "string",
+			Value::List(_) => "list",
+			Value::Hash(_) => "hash",
+			Value::Set(_) => "set",
+			Value::SortedSet(_) => "zset",
+			Value::Stream(_) => "stream",
+		}
+	}
+
+	/// Name of the internal representation as reported by `OBJECT ENCODING`.
+	pub fn encoding(&self) -> &'static str {
+		match self {
+			Value::String(string) => string.encoding(),
+			Value::List(list) => list.encoding(),
+			Value::Hash(hash) => hash.encoding(),
+			Value::Set(set) => set.encoding(),
+			Value::SortedSet(zset) => zset.encoding(),
+			Value::Stream(stream) => stream.encoding(),
+		}
+	}
+
+	/// Whether the value is a container without elements. Such keys are deleted, Redis has no empty containers.
+	/// Streams are the exception, they keep their last ID when they become empty.
+	pub fn is_empty(&self) -> bool {
+		match self {
+			Value::String(_) | Value::Stream(_) => false,
+			Value::List(list) => list.is_empty(),
+			Value::Hash(hash) => hash.is_empty(),
+			Value::Set(set) => set.is_empty(),
+			Value::SortedSet(zset) => zset.is_empty(),
+		}
+	}
+
+	/// Roughly the number of allocations freeing the value takes.
+	pub fn free_effort(&self) -> usize {
+		match self {
+			Value::String(_) => 1,
+			Value::List(list) => list.free_effort(),
+			Value::Hash(hash) => hash.free_effort(),
+			Value::Set(set) => set.free_effort(),
+			Value::SortedSet(zset) => zset.free_effort(),
+			Value::Stream(stream) => stream.free_effort(),
+		}
+	}
+
+	pub fn as_string(&self) -> Result<&RedisString, CommandError> {
+		match self {
+			Value::String(string) => Ok(string),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_list(&self) -> Result<&List, CommandError> {
+		match self {
+			Value::List(list) => Ok(list),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_list_mut(&mut self) -> Result<&mut List, CommandError> {
+		match self {
+			Value::List(list) => Ok(list),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_hash(&self) -> Result<&Hash, CommandError> {
+		match self {
+			Value::Hash(hash) => Ok(hash),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_hash_mut(&mut self) -> Result<&mut Hash, CommandError> {
+		match self {
+			Value::Hash(hash) => Ok(hash),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_set(&self) -> Result<&Set, CommandError> {
+		match self {
+			Value::Set(set) => Ok(set),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_set_mut(&mut self) -> Result<&mut Set, CommandError> {
+		match self {
+			Value::Set(set) => Ok(set),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_sorted_set(&self) -> Result<&SortedSet, CommandError> {
+		match self {
+			Value::SortedSet(zset) => Ok(zset),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_sorted_set_mut(&mut self) -> Result<&mut SortedSet, CommandError> {
+		match self {
+			Value::SortedSet(zset) => Ok(zset),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_stream(&self) -> Result<&Stream, CommandError> {
+		match self {
+			Value::Stream(stream) => Ok(stream),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+
+	pub fn as_stream_mut(&mut self) -> Result<&mut Stream, CommandError> {
+		match self {
+			Value::Stream(stream) => Ok(stream),
+			_ => Err(CommandError::WrongType),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use bytes::Bytes;
+
+	use super::*;
+
+	#[test]
+	fn strings_use_the_most_compact_encoding() {
+		assert_eq!(RedisString::new(Bytes::from("12345")).encoding(), "int");
+		assert_eq!(RedisString::new(Bytes::from("-9223372036854775808")).encoding(), "int");
+		assert_eq!(RedisString::new(Bytes::from("9223372036854775808")).encoding(), "embstr");
+		assert_eq!(RedisString::new(Bytes::from("007")).encoding(), "embstr");
+		assert_eq!(RedisString::new(Bytes::from("a".repeat(44))).encoding(), "embstr");
+		assert_eq!(RedisString::new(Bytes::from("a".repeat(45))).encoding(), "raw");
+		assert_eq!(RedisString::plain(Bytes::from("3")).encoding(), "embstr");
+		assert_eq!(RedisString::new(Bytes::from("-12")).to_bytes(), Bytes::from("-12"));
+	}
+
+	#[test]
+	fn values_report_their_type_and_encoding() {
+		let limits = EncodingLimits::default();
+		let values = [
+			(Value::String(RedisString::new(Bytes::from("1"))), "string", "int"),
+			(Value::List(List::new()), "list", "listpack"),
+			(Value::Hash(Hash::new()), "hash", "listpack"),
+			(Value::Set(Set::from_members(&[Bytes::from("1")], &limits)), "set", "intset"),
+			(Value::Set(Set::from_members(&[Bytes::from("a")], &limits)), "set", "listpack"),
+			(Value::SortedSet(SortedSet::from_entries(&[(Bytes::from("a"), 1.0)], &limits)), "zset", "listpack"),
+			(Value::Stream(Stream::new()), "stream", "stream"),
+		];
+		for (value, type_name, encoding) in values {
+			assert_eq!(value.type_name(), type_name);
+			assert_eq!(value.encoding(), encoding);
+		}
+	}
+
+	#[test]
+	fn accessors_of_other_types_are_wrongtype() {
+		let mut value = Value::List(List::new());
+		assert!(value.as_list().is_ok());
+		assert!(matches!(value.as_string(), Err(CommandError::WrongType)));
+		assert!(matches!(value.as_hash_mut(), Err(CommandError::WrongType)));
+		assert!(matches!(value.as_set(), Err(CommandError::WrongType)));
+		assert!(matches!(value.as_sorted_set_mut(), Err(CommandError::WrongType)));
+		assert!(matches!(value.as_stream(), Err(CommandError::WrongType)));
+	}
+
+	#[test]
+	fn empty_containers_are_empty() {
+		assert!(Value::List(List::new()).is_empty());
+		assert!(Value::Hash(Hash::new()).is_empty());
+		assert!(!Value::String(RedisString::new(Bytes::new())).is_empty());
+		assert!(!Value::Stream(Stream::new()).is_empty());
+	}
+}
@@ -0,0 +1,228 @@
+use std::collections::{BTreeSet, HashMap};
+
+use bytes::Bytes;
+use rand::Rng;
+
+use crate::dict::Dict;
+
+use super::{listpack::Listpack, EncodingLimits};
+
+/// A hash. Small hashes are a listpack of alternating fields and values,
+/// they are converted to a hash table once they have too many or too large entries.
+/// Fields may have their own expiry, which the keyspace enforces through `expire_fields`.
+#[derive(Clone)]
+pub struct Hash {
+	entries: Entries,
+	expiries: FieldExpiries,
+}
+
+#[derive(Clone)]
+enum Entries {
+	Listpack(Listpack),
+	Hashtable(Dict<Bytes, Bytes>),
+}
+
+/// The Unix timestamps in milliseconds the fields with a TTL expire at, also ordered by time to find the next one quickly.
+#[derive(Clone, Default)]
+struct FieldExpiries {
+	by_field: HashMap<Bytes, i64>,
+	by_time: BTreeSet<(i64, Bytes)>,
+}
+
+impl FieldExpiries {
+	fn get(&self, field: &[u8]) -> Option<i64> {
+		self.by_field.get(field).copied()
+	}
+
+	fn set(&mut self, field: &[u8], expires_at: Option<i64>) {
+		if let Some(previous) = self.by_field.remove(field) {
+			self.by_time.remove(&(previous, Bytes::copy_from_slice(field)));
+		}
+		if let Some(expires_at) = expires_at {
+			let field = Bytes::copy_from_slice(field);
+			self.by_field.insert(field.clone(), expires_at);
+			self.by_time.insert((expires_at, field));
+		}
+	}
+}
+
+impl Hash {
+	pub fn new() -> Hash {
+		Hash {
+			entries: Entries::Listpack(Listpack::new()),
+			expiries: FieldExpiries::default(),
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		match &self.entries {
+			Entries::Listpack(listpack) => listpack.len() / 2,
+			Entries::Hashtable(dict) => dict.len(),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn encoding(&self) -> &'static str {
+		match &self.entries {
+			// Listpacks with field expiries are a separate encoding in Redis.
+			Entries::Listpack(_) if !self.expiries.by_field.is_empty() => "listpackex",
+			Entries::Listpack(_) => "listpack",
+			Entries::Hashtable(_) => "hashtable",
+		}
+	}
+
+	pub fn free_effort(&self) -> usize {
+		match &self.entries {
+			Entries::Listpack(_) => 1,
+			Entries::Hashtable(dict) => dict.len(),
+		}
+	}
+
+	pub fn get(&self, field: &[u8]) -> Option<Bytes> {
+		match &self.entries {
+			Entries::Listpack(listpack) => {
+				let offset = find_field(listpack, field)?;
+				Some(listpack.get(listpack.next(offset)?).to_bytes())
+			},
+			Entries::Hashtable(dict) => dict.get(field).cloned(),
+		}
+	}
+
+	pub fn contains(&self, field: &[u8]) -> bool {
+		match &self.entries {
+			Entries::Listpack(listpack) => find_field(listpack, field).is_some(),
+			Entries::Hashtable(dict) => dict.contains_key(field),
+		}
+	}
+
+	/// Sets `field` to `value` and removes its expiry. Returns whether the field is new.
+	pub fn insert(&mut self, field: Bytes, value: Bytes, limits: &EncodingLimits) -> bool {
+		self.expiries.set(&field, None);
+		self.set(field, value, limits)
+	}
+
+	/// Like `insert`, but keeps the expiry of the field.
+	pub fn update(&mut self, field: Bytes, value: Bytes, limits: &EncodingLimits) -> bool {
+		self.set(field, value, limits)
+	}
+
+	fn set(&mut self, field: Bytes, value: Bytes, limits: &EncodingLimits) -> bool {
+		if let Entries::Listpack(listpack) = &mut self.entries {
+			if field.len() > limits.hash_max_listpack_value || value.len() > limits.hash_max_listpack_value {
+				self.convert();
+			} else {
+				if let Some(offset) = find_field(listpack, &field) {
+					let offset = listpack.next(offset).unwrap();
+					listpack.replace(offset, &value);
+					return false;
+				}
+				listpack.push_back(&field);
+				listpack.push_back(&value);
+				if listpack.len() / 2 > limits.hash_max_listpack_entries {
+					self.convert();
+				}
+				return true;
+			}
+		}
+		let Entries::Hashtable(dict) = &mut self.entries else {
+			unreachable!();
+		};
+		dict.insert(field, value).is_none()
+	}
+
+	/// Returns whether the field existed.
+	pub fn remove(&mut self, field: &[u8]) -> bool {
+		self.expiries.set(field, None);
+		match &mut self.entries {
+			Entries::Listpack(listpack) => match find_field(listpack, field) {
+				Some(offset) => {
+					listpack.delete_range(offset, 2);
+					true
+				},
+				None => false,
+			},
+			Entries::Hashtable(dict) => dict.remove(field).is_some(),
+		}
+	}
+
+	/// A random field and its value.
+	pub fn random(&self) -> Option<(Bytes, Bytes)> {
+		match &self.entries {
+			Entries::Listpack(listpack) => {
+				let index = rand::thread_rng().gen_range(0..self.len().max(1));
+				let offset = listpack.seek(2 * index as i64)?;
+				Some((listpack.get(offset).to_bytes(), listpack.get(listpack.next(offset)?).to_bytes()))
+			},
+			Entries::Hashtable(dict) => dict.random_entry().map(|(field, value)| (field.clone(), value.clone())),
+		}
+	}
+
+	pub fn iter(&self) -> Box<dyn Iterator<Item = (Bytes, Bytes)> + '_> {
+		match &self.entries {
+			Entries::Listpack(listpack) => {
+				let mut entries = listpack.iter();
+				Box::new(std::iter::from_fn(move || Some((entries.next()?.to_bytes(), entries.next()?.to_bytes()))))
+			},
+			Entries::Hashtable(dict) => Box::new(dict.iter().map(|(field, value)| (field.clone(), value.clone()))),
+		}
+	}
+
+	/// Calls `f` with the fields and values at `cursor` and returns the cursor to continue with, see `Dict::scan`.
+	/// Listpacks are small, so all of their entries are visited at once and the returned cursor is 0.
+	pub fn scan(&self, cursor: u64, mut f: impl FnMut(Bytes, Bytes)) -> u64 {
+		match &self.entries {
+			Entries::Listpack(_) => {
+				self.iter().for_each(|(field, value)| f(field, value));
+				0
+			},
+			Entries::Hashtable(dict) => dict.scan(cursor, |field, value| f(field.clone(), value.clone())),
+		}
+	}
+
+	/// The Unix timestamp in milliseconds `field` expires at, if it has an expiry.
+	pub fn field_expires_at(&self, field: &[u8]) -> Option<i64> {
+		self.expiries.get(field)
+	}
+
+	/// Sets the expiry of `field`, `None` removes it. The field has to exist.
+	pub fn set_field_expires_at(&mut self, field: &[u8], expires_at: Option<i64>) {
+		self.expiries.set(field, expires_at);
+	}
+
+	/// The earliest expiry of a field.
+	pub fn next_field_expiry(&self) -> Option<i64> {
+		self.expiries.by_time.first().map(|(expires_at, _)| *expires_at)
+	}
+
+	/// Removes the fields that expired before `now`. Returns the number of removed fields.
+	pub fn expire_fields(&mut self, now: i64) -> usize {
+		let mut expired = 0;
+		while let Some((expires_at, field)) = self.expiries.by_time.first().cloned() {
+			if expires_at >= now {
+				break;
+			}
+			self.remove(&field);
+			expired += 1;
+		}
+		expired
+	}
+
+	/// Converts a listpack to a hash table.
+	fn convert(&mut self) {
+		if let Entries::Listpack(_) = &self.entries {
+			let mut dict = Dict::new();
+			for (field, value) in self.iter() {
+				dict.insert(field, value);
+			}
+			self.entries = Entries::Hashtable(dict);
+		}
+	}
+}
+
+/// Offset of `field` in a listpack of alternating fields and values.
+fn find_field(listpack: &Listpack, field: &[u8]) -> Option<usize> {
+	listpack.find(listpack.first()?, field, 1)
+}
@@ -0,0 +1,166 @@
+use rand::Rng;
+
+/// A sorted set of integers in a single allocation, like the intset of Redis.
+/// All elements are stored with the same width, the smallest of 16, 32 or 64 bits that fits every element,
+/// so adding a larger element upgrades the whole set.
+#[derive(Clone)]
+pub struct Intset {
+	data: Vec<u8>,
+	/// Bytes per element.
+	width: usize,
+}
+
+impl Default for Intset {
+	fn default() -> Self {
+		Intset {
+			data: Vec::new(),
+			width: 2,
+		}
+	}
+}
+
+impl Intset {
+	pub fn new() -> Intset {
+		Intset::default()
+	}
+
+	pub fn len(&self) -> usize {
+		self.data.len() / self.width
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+
+	pub fn get(&self, index: usize) -> i64 {
+		let bytes = &self.data[index * self.width..(index + 1) * self.width];
+		match self.width {
+			2 => i16::from_le_bytes(bytes.try_into().unwrap()) as i64,
+			4 => i32::from_le_bytes(bytes.try_into().unwrap()) as i64,
+			_ => i64::from_le_bytes(bytes.try_into().unwrap()),
+		}
+	}
+
+	pub fn contains(&self, value: i64) -> bool {
+		width_of(value) <= self.width && self.search(value).is_ok()
+	}
+
+	/// Returns whether `value` was added.
+	pub fn insert(&mut self, value: i64) -> bool {
+		if width_of(value) > self.width {
+			self.upgrade(width_of(value));
+		}
+		match self.search(value) {
+			Ok(_) => false,
+			Err(index) => {
+				let offset = index * self.width;
+				self.data.splice(offset..offset, encode(value, self.width));
+				true
+			},
+		}
+	}
+
+	/// Returns whether `value` was removed.
+	pub fn remove(&mut self, value: i64) -> bool {
+		if width_of(value) > self.width {
+			return false;
+		}
+		match self.search(value) {
+			Ok(index) => {
+				self.data.drain(index * self.width..(index + 1) * self.width);
+				true
+			},
+			Err(_) => false,
+		}
+	}
+
+	pub fn random(&self) -> Option<i64> {
+		(!self.is_empty()).then(|| self.get(rand::thread_rng().gen_range(0..self.len())))
+	}
+
+	pub fn iter(&self) -> impl DoubleEndedIterator<Item = i64> + '_ {
+		(0..self.len()).map(|i| self.get(i))
+	}
+
+	/// Binary search for `value`, like `slice::binary_search`.
+	fn search(&self, value: i64) -> Result<usize, usize> {
+		let (mut low, mut high) = (0, self.len());
+		while low < high {
+			let middle = (low + high) / 2;
+			match self.get(middle).cmp(&value) {
+				std::cmp::Ordering::Less => low = middle + 1,
+				std::cmp::Ordering::Greater => high = middle,
+				std::cmp::Ordering::Equal => return Ok(middle),
+			}
+		}
+		Err(low)
+	}
+
+	fn upgrade(&mut self, width: usize) {
+		let data = self.iter().flat_map(|v| encode(v, width)).collect();
+		self.data = data;
+		self.width = width;
+	}
+}
+
+fn width_of(value: i64) -> usize {
+	if i16::try_from(value).is_ok() {
+		2
+	} else if i32::try_from(value).is_ok() {
+		4
+	} else {
+		8
+	}
+}
+
+fn encode(value: i64, width: usize) -> Vec<u8> {
+	value.to_le_bytes()[..width].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn upgrades_the_width_for_larger_elements() {
+		let mut intset = Intset::new();
+		intset.insert(-5);
+		assert_eq!(intset.width, 2);
+		intset.insert(i16::MAX as i64 + 1);
+		assert_eq!(intset.width, 4);
+		intset.insert(i32::MIN as i64 - 1);
+		assert_eq!(intset.width, 8);
+		assert_eq!(intset.iter().collect::<Vec<_>>(), [i32::MIN as i64 - 1, -5, i16::MAX as i64 + 1]);
+		// Removing the large elements does not downgrade, like in Redis.
+		intset.remove(i32::MIN as i64 - 1);
+		assert_eq!(intset.width, 8);
+	}
+
+	#[test]
+	fn keeps_elements_sorted_and_unique() {
+		let mut intset = Intset::new();
+		for value in [5, -3, 100, 0, 5, i64::MAX, i64::MIN, -3] {
+			intset.insert(value);
+		}
+		assert_eq!(intset.iter().collect::<Vec<_>>(), [i64::MIN, -3, 0, 5, 100, i64::MAX]);
+		assert!(!intset.insert(100));
+		assert!(intset.contains(i64::MIN));
+		assert!(!intset.contains(6));
+	}
+
+	#[test]
+	fn removes_elements() {
+		let mut intset = Intset::new();
+		(0..10).for_each(|value| {
+			intset.insert(value);
+		});
+		assert!(intset.remove(0));
+		assert!(intset.remove(9));
+		assert!(intset.remove(4));
+		assert!(!intset.remove(4));
+		// Elements wider than the set can not be in it.
+		assert!(!intset.remove(1 << 40));
+		assert_eq!(intset.iter().collect::<Vec<_>>(), [1, 2, 3, 5, 6, 7, 8]);
+		assert_eq!(intset.len(), 7);
+	}
+}
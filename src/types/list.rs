@@ -0,0 +1,524 @@
+use std::collections::VecDeque;
+
+use bytes::Bytes;
+
+use super::listpack::{Listpack, ListpackEntry};
+
+/// Nodes with a non-positive fill factor are never larger than this, whatever their number of entries.
+const SIZE_SAFETY_LIMIT: usize = 8192;
+/// Byte limits for the fill factors -1 to -5.
+const OPTIMIZATION_LEVELS: [usize; 5] = [4096, 8192, 16384, 32768, 65536];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ListEnd {
+	Head,
+	Tail,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ListEncoding {
+	Listpack,
+	Quicklist,
+}
+
+/// A list like the quicklist of Redis: a deque of listpacks.
+/// Small lists consist of a single listpack and report the `listpack` encoding. Once that listpack outgrows
+/// `fill`, the list switches to the `quicklist` encoding, and back once it fits in half a node again.
+///
+/// `fill` is the `list-max-listpack-size` setting: positive values limit the number of entries of a node,
+/// the values -1 to -5 limit its size to 4, 8, 16, 32 or 64 kb.
+#[derive(Clone)]
+pub struct List {
+	nodes: VecDeque<Listpack>,
+	len: usize,
+	encoding: ListEncoding,
+}
+
+impl List {
+	pub fn new() -> List {
+		List {
+			nodes: VecDeque::from([Listpack::new()]),
+			len: 0,
+			encoding: ListEncoding::Listpack,
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	pub fn encoding(&self) -> &'static str {
+		match self.encoding {
+			ListEncoding::Listpack => "listpack",
+			ListEncoding::Quicklist => "quicklist",
+		}
+	}
+
+	/// Freeing the list frees each of its nodes.
+	pub fn free_effort(&self) -> usize {
+		self.nodes.len()
+	}
+
+	pub fn push(&mut self, element: &[u8], end: ListEnd, fill: i64) {
+		let index = match end {
+			ListEnd::Head => 0,
+			ListEnd::Tail => self.nodes.len() - 1,
+		};
+		let node = &self.nodes[index];
+		if self.encoding == ListEncoding::Quicklist && exceeds_limit(fill, node.bytes() + element.len() + 11, node.len() + 1) {
+			let mut node = Listpack::new();
+			push_node(&mut node, element, end);
+			match end {
+				ListEnd::Head => self.nodes.push_front(node),
+				ListEnd::Tail => self.nodes.push_back(node),
+			}
+		} else {
+			push_node(&mut self.nodes[index], element, end);
+		}
+		self.len += 1;
+		self.try_convert(fill);
+	}
+
+	pub fn pop(&mut self, end: ListEnd, fill: i64) -> Option<Bytes> {
+		let index = match end {
+			ListEnd::Head => 0,
+			ListEnd::Tail => self.nodes.len() - 1,
+		};
+		let node = &mut self.nodes[index];
+		let offset = match end {
+			ListEnd::Head => node.first()?,
+			ListEnd::Tail => node.last()?,
+		};
+		let element = node.get(offset).to_bytes();
+		node.delete(offset);
+		self.len -= 1;
+		self.remove_node_if_empty(index);
+		self.try_convert(fill);
+		Some(element)
+	}
+
+	/// The element at `index`, negative indexes count from the end.
+	pub fn get(&self, index: i64) -> Option<Bytes> {
+		let (node, offset) = self.locate(index)?;
+		Some(self.nodes[node].get(offset).to_bytes())
+	}
+
+	pub fn iter(&self) -> impl DoubleEndedIterator<Item = ListpackEntry<'_>> {
+		self.nodes.iter().flat_map(|node| node.iter())
+	}
+
+	/// `count` elements from the one at `start` on.
+	pub fn range(&self, start: usize, count: usize) -> Vec<Bytes> {
+		let mut elements = Vec::with_capacity(count);
+		let mut skip = start;
+		for node in &self.nodes {
+			// Skip whole nodes instead of walking their elements.
+			if skip >= node.len() {
+				skip -= node.len();
+				continue;
+			}
+			for entry in node.iter().skip(skip) {
+				if elements.len() == count {
+					return elements;
+				}
+				elements.push(entry.to_bytes());
+			}
+			skip = 0;
+		}
+		elements
+	}
+
+	/// Replaces the element at `index`, negative indexes count from the end. Returns `false` if the index is out of range.
+	pub fn set(&mut self, index: i64, element: &[u8], fill: i64) -> bool {
+		let Some((node, offset)) = self.locate(index) else {
+			return false;
+		};
+		self.nodes[node].replace(offset, element);
+		self.fit_node(node, fill);
+		true
+	}
+
+	/// Inserts `element` before or after the first element equal to `pivot`. Returns `false` if there is no such element.
+	pub fn insert(&mut self, pivot: &[u8], element: &[u8], after: bool, fill: i64) -> bool {
+		for i in 0..self.nodes.len() {
+			let node = &mut self.nodes[i];
+			let Some(offset) = node.first().and_then(|first| node.find(first, pivot, 0)) else {
+				continue;
+			};
+			let offset = if after { node.next(offset).unwrap_or(node.end()) } else { offset };
+			node.insert(offset, element);
+			self.len += 1;
+			self.fit_node(i, fill);
+			return true;
+		}
+		false
+	}
+
+	/// Removes up to `count` elements equal to `element`, starting from the head, or from the tail if `count` is negative.
+	/// A `count` of 0 removes all of them. Returns the number of removed elements.
+	pub fn remove(&mut self, element: &[u8], count: i64, fill: i64) -> usize {
+		let limit = if count == 0 { usize::MAX } else { count.unsigned_abs() as usize };
+		let mut removed = 0;
+		let mut i = if count >= 0 { 0 } else { self.nodes.len() };
+		while removed < limit {
+			if count >= 0 && i == self.nodes.len() || count < 0 && i == 0 {
+				break;
+			}
+			if count < 0 {
+				i -= 1;
+			}
+			let node = &mut self.nodes[i];
+			let mut offset = if count >= 0 { node.first() } else { node.last() };
+			while let Some(current) = offset {
+				if removed == limit {
+					break;
+				}
+				// Deleting an element moves the following ones, but not the preceding ones.
+				let previous = node.prev(current);
+				if node.get(current).matches(element) {
+					node.delete(current);
+					removed += 1;
+					offset = if count >= 0 { (current < node.end()).then_some(current) } else { previous };
+				} else {
+					offset = if count >= 0 { node.next(current) } else { previous };
+				}
+			}
+			let len = self.nodes.len();
+			self.remove_node_if_empty(i);
+			if count >= 0 && self.nodes.len() == len {
+				i += 1;
+			}
+		}
+		self.len -= removed;
+		self.try_convert(fill);
+		removed
+	}
+
+	/// Keeps only the `count` elements from the one at `start` on.
+	pub fn trim(&mut self, start: usize, count: usize, fill: i64) {
+		let end = start + count;
+		if end < self.len {
+			self.delete_range(end, self.len - end);
+		}
+		self.delete_range(0, start);
+		self.try_convert(fill);
+	}
+
+	/// Deletes `count` elements from the one at `start` on.
+	fn delete_range(&mut self, start: usize, count: usize) {
+		let mut skip = start;
+		let mut remaining = count;
+		let mut i = 0;
+		while i < self.nodes.len() && remaining > 0 {
+			let len = self.nodes[i].len();
+			if skip >= len {
+				skip -= len;
+				i += 1;
+				continue;
+			}
+			let deleted = remaining.min(len - skip);
+			let node = &mut self.nodes[i];
+			let offset = node.seek(skip as i64).unwrap();
+			node.delete_range(offset, deleted);
+			self.len -= deleted;
+			remaining -= deleted;
+			skip = 0;
+			let nodes = self.nodes.len();
+			self.remove_node_if_empty(i);
+			if self.nodes.len() == nodes {
+				i += 1;
+			}
+		}
+	}
+
+	/// Splits the node at `index` if it grew too large, and switches the encoding if needed.
+	fn fit_node(&mut self, index: usize, fill: i64) {
+		let node = &self.nodes[index];
+		if self.encoding == ListEncoding::Quicklist && exceeds_limit(fill, node.bytes(), node.len()) {
+			self.split_node(index, fill);
+		}
+		self.try_convert(fill);
+	}
+
+	/// The node and the offset in the node of the element at `index`, negative indexes count from the end.
+	fn locate(&self, index: i64) -> Option<(usize, usize)> {
+		let mut index = if index < 0 { self.len as i64 + index } else { index };
+		if index < 0 || index >= self.len as i64 {
+			return None;
+		}
+		for (i, node) in self.nodes.iter().enumerate() {
+			if index < node.len() as i64 {
+				return Some((i, node.seek(index)?));
+			}
+			index -= node.len() as i64;
+		}
+		None
+	}
+
+	fn remove_node_if_empty(&mut self, index: usize) {
+		// A list always keeps at least one node, empty lists are deleted from the keyspace anyway.
+		if self.nodes[index].is_empty() && self.nodes.len() > 1 {
+			self.nodes.remove(index);
+		}
+	}
+
+	/// Switches between the encodings if the list outgrew its single listpack, or fits in one again.
+	fn try_convert(&mut self, fill: i64) {
+		match self.encoding {
+			ListEncoding::Listpack => {
+				let node = &self.nodes[0];
+				if exceeds_limit(fill, node.bytes(), node.len()) {
+					self.encoding = ListEncoding::Quicklist;
+					self.split_node(0, fill);
+				}
+			},
+			ListEncoding::Quicklist => {
+				// Only convert back once the list is half the limit, so lists around the limit don't flip every time.
+				if self.nodes.len() == 1 {
+					let node = &self.nodes[0];
+					let (bytes, count) = limits(fill);
+					if node.bytes() <= bytes / 2 && node.len() <= count / 2 {
+						self.encoding = ListEncoding::Listpack;
+					}
+				}
+			},
+		}
+	}
+
+	/// Splits the node at `index` into nodes that do not exceed `fill`.
+	fn split_node(&mut self, index: usize, fill: i64) {
+		let mut node = self.nodes.remove(index).unwrap_or_default();
+		let mut split = Vec::new();
+		while exceeds_limit(fill, node.bytes(), node.len()) && node.len() > 1 {
+			// Find the longest prefix that fits.
+			let mut count = 0;
+			let mut offset = node.first();
+			let mut bytes = node.bytes() - node.end();
+			while let Some(current) = offset {
+				let next = node.next(current);
+				let size = next.unwrap_or(node.end()) - current;
+				if count > 0 && exceeds_limit(fill, bytes + size, count + 1) {
+					break;
+				}
+				bytes += size;
+				count += 1;
+				offset = next;
+			}
+			let Some(offset) = offset else {
+				break;
+			};
+			let rest = node.split_off(offset);
+			split.push(node);
+			node = rest;
+		}
+		split.push(node);
+		for (i, node) in split.into_iter().enumerate() {
+			self.nodes.insert(index + i, node);
+		}
+	}
+}
+
+fn push_node(node: &mut Listpack, element: &[u8], end: ListEnd) {
+	match end {
+		ListEnd::Head => node.push_front(element),
+		ListEnd::Tail => node.push_back(element),
+	}
+}
+
+/// The maximum size in bytes and number of entries of a node.
+fn limits(fill: i64) -> (usize, usize) {
+	if fill >= 0 {
+		(SIZE_SAFETY_LIMIT, fill.max(1) as usize)
+	} else {
+		let level = ((-fill - 1) as usize).min(OPTIMIZATION_LEVELS.len() - 1);
+		(OPTIMIZATION_LEVELS[level], usize::MAX)
+	}
+}
+
+fn exceeds_limit(fill: i64, bytes: usize, count: usize) -> bool {
+	let (max_bytes, max_count) = limits(fill);
+	bytes > max_bytes || count > max_count
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn list_of(elements: &[&str], fill: i64) -> List {
+		let mut list = List::new();
+		elements.iter().for_each(|element| list.push(element.as_bytes(), ListEnd::Tail, fill));
+		list
+	}
+
+	fn elements(list: &List) -> Vec<Bytes> {
+		list.range(0, list.len())
+	}
+
+	fn node_lengths(list: &List) -> Vec<usize> {
+		list.nodes.iter().map(|node| node.len()).collect()
+	}
+
+	/// Checks that the nodes agree with the length of the list and respect `fill`.
+	fn check(list: &List, fill: i64) {
+		assert_eq!(list.nodes.iter().map(|node| node.len()).sum::<usize>(), list.len());
+		assert_eq!(list.iter().count(), list.len());
+		if list.encoding == ListEncoding::Quicklist {
+			assert!(list.nodes.iter().all(|node| node.len() == 1 || !exceeds_limit(fill, node.bytes(), node.len())));
+			assert!(list.nodes.len() == 1 || list.nodes.iter().all(|node| !node.is_empty()));
+		}
+	}
+
+	#[test]
+	fn pushes_and_pops_at_both_ends_across_nodes() {
+		let mut list = List::new();
+		for i in 0..10 {
+			list.push(i.to_string().as_bytes(), ListEnd::Tail, 3);
+			list.push((-i - 1).to_string().as_bytes(), ListEnd::Head, 3);
+			check(&list, 3);
+		}
+		assert_eq!(list.encoding(), "quicklist");
+		assert!(list.nodes.len() >= 7);
+		assert_eq!(list.get(0), Some(Bytes::from("-10")));
+		assert_eq!(list.get(-1), Some(Bytes::from("9")));
+		for i in (0..10).rev() {
+			assert_eq!(list.pop(ListEnd::Tail, 3), Some(Bytes::from(i.to_string())));
+			assert_eq!(list.pop(ListEnd::Head, 3), Some(Bytes::from((-i - 1).to_string())));
+			check(&list, 3);
+		}
+		assert!(list.is_empty());
+		assert_eq!(list.pop(ListEnd::Head, 3), None);
+		assert_eq!(list.pop(ListEnd::Tail, 3), None);
+	}
+
+	#[test]
+	fn gets_and_sets_by_index_across_nodes() {
+		let mut list = list_of(&["a", "b", "c", "d", "e"], 2);
+		assert_eq!(node_lengths(&list), [2, 2, 1]);
+		assert_eq!(list.get(2), Some(Bytes::from("c")));
+		assert_eq!(list.get(-2), Some(Bytes::from("d")));
+		assert_eq!(list.get(5), None);
+		assert_eq!(list.get(-6), None);
+		assert!(list.set(3, b"x", 2));
+		assert!(!list.set(5, b"x", 2));
+		assert_eq!(elements(&list), ["a", "b", "c", "x", "e"]);
+		assert_eq!(list.range(1, 3), ["b", "c", "x"]);
+	}
+
+	#[test]
+	fn removes_from_the_head_with_a_positive_count() {
+		let mut list = list_of(&["x", "a", "x", "b", "x", "x", "c"], 2);
+		assert_eq!(list.remove(b"x", 3, 2), 3);
+		assert_eq!(elements(&list), ["a", "b", "x", "c"]);
+		check(&list, 2);
+	}
+
+	#[test]
+	fn removes_from_the_tail_with_a_negative_count() {
+		let mut list = list_of(&["x", "a", "x", "b", "x", "x", "c"], 2);
+		assert_eq!(list.remove(b"x", -3, 2), 3);
+		assert_eq!(elements(&list), ["x", "a", "b", "c"]);
+		check(&list, 2);
+		assert_eq!(list.remove(b"x", -5, 2), 1);
+		assert_eq!(elements(&list), ["a", "b", "c"]);
+		check(&list, 2);
+	}
+
+	#[test]
+	fn removes_all_matches_with_a_zero_count() {
+		let mut list = list_of(&["1", "x", "1", "1", "y", "1"], 2);
+		assert_eq!(list.remove(b"1", 0, 2), 4);
+		assert_eq!(elements(&list), ["x", "y"]);
+		check(&list, 2);
+		assert_eq!(list.remove(b"z", 0, 2), 0);
+		assert_eq!(list.remove(b"x", 0, 2) + list.remove(b"y", 0, 2), 2);
+		assert!(list.is_empty());
+		check(&list, 2);
+	}
+
+	#[test]
+	fn trims_across_nodes() {
+		let all: Vec<String> = (0..20).map(|i| i.to_string()).collect();
+		let all: Vec<&str> = all.iter().map(String::as_str).collect();
+		let mut list = list_of(&all, 3);
+		list.trim(4, 9, 3);
+		assert_eq!(elements(&list), all[4..13]);
+		check(&list, 3);
+		list.trim(0, 1, 3);
+		assert_eq!(elements(&list), ["4"]);
+		check(&list, 3);
+		list.trim(0, 0, 3);
+		assert!(list.is_empty());
+	}
+
+	#[test]
+	fn inserting_into_a_full_node_splits_it() {
+		let mut list = list_of(&["a", "b", "c", "d", "e", "f"], 3);
+		assert_eq!(node_lengths(&list), [3, 3]);
+		assert!(list.insert(b"b", b"x", true, 3));
+		assert_eq!(elements(&list), ["a", "b", "x", "c", "d", "e", "f"]);
+		assert_eq!(node_lengths(&list), [3, 1, 3]);
+		assert!(list.insert(b"a", b"y", false, 3));
+		assert_eq!(elements(&list), ["y", "a", "b", "x", "c", "d", "e", "f"]);
+		check(&list, 3);
+		assert!(!list.insert(b"missing", b"z", true, 3));
+		assert_eq!(list.len(), 8);
+	}
+
+	#[test]
+	fn converts_by_number_of_entries_for_a_positive_fill() {
+		let mut list = list_of(&["a", "b", "c", "d"], 4);
+		assert_eq!(list.encoding(), "listpack");
+		list.push(b"e", ListEnd::Tail, 4);
+		assert_eq!(list.encoding(), "quicklist");
+		check(&list, 4);
+		assert_eq!(node_lengths(&list), [4, 1]);
+		// Only converts back once the list is a single node with at most half the entries.
+		list.pop(ListEnd::Tail, 4);
+		assert_eq!(node_lengths(&list), [4]);
+		assert_eq!(list.encoding(), "quicklist");
+		list.pop(ListEnd::Head, 4);
+		assert_eq!(list.encoding(), "quicklist");
+		list.pop(ListEnd::Head, 4);
+		assert_eq!(list.encoding(), "listpack");
+		assert_eq!(elements(&list), ["c", "d"]);
+	}
+
+	#[test]
+	fn converts_by_size_for_a_negative_fill() {
+		let element = "x".repeat(1000);
+		let mut list = List::new();
+		for _ in 0..4 {
+			list.push(element.as_bytes(), ListEnd::Tail, -1);
+		}
+		assert_eq!(list.encoding(), "listpack");
+		list.push(element.as_bytes(), ListEnd::Tail, -1);
+		assert_eq!(list.encoding(), "quicklist");
+		check(&list, -1);
+		// With -2, 5 elements of 1 kb fit into a single 8 kb node again.
+		let mut list = list_of(&[element.as_str(); 8], -2);
+		assert_eq!(list.encoding(), "listpack");
+		list.push(element.as_bytes(), ListEnd::Head, -2);
+		assert_eq!(list.encoding(), "quicklist");
+		check(&list, -2);
+		while list.len() > 3 {
+			list.pop(ListEnd::Tail, -2);
+			check(&list, -2);
+		}
+		assert_eq!(list.encoding(), "listpack");
+	}
+
+	#[test]
+	fn oversized_elements_get_their_own_node() {
+		let large = "x".repeat(10_000);
+		let mut list = list_of(&["a", "b"], -1);
+		list.push(large.as_bytes(), ListEnd::Tail, -1);
+		assert_eq!(list.encoding(), "quicklist");
+		list.push(b"c", ListEnd::Tail, -1);
+		check(&list, -1);
+		assert_eq!(elements(&list), ["a", "b", large.as_str(), "c"]);
+	}
+}
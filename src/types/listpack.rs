@@ -0,0 +1,441 @@
+use bytes::Bytes;
+
+use crate::util::parse_integer;
+
+/// Size of the header (total bytes and number of elements) and the terminator of a Redis listpack.
+/// Only counted in `bytes` so sizes compare like they do in Redis.
+const LISTPACK_OVERHEAD: usize = 7;
+
+/// An element of a listpack. Elements that look like integers are stored as integers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListpackEntry<'a> {
+	Int(i64),
+	Str(&'a [u8]),
+}
+
+impl ListpackEntry<'_> {
+	pub fn to_bytes(self) -> Bytes {
+		match self {
+			ListpackEntry::Int(v) => Bytes::from(v.to_string()),
+			ListpackEntry::Str(s) => Bytes::copy_from_slice(s),
+		}
+	}
+
+	/// Compares with an element as given by a client.
+	pub fn matches(self, element: &[u8]) -> bool {
+		match self {
+			ListpackEntry::Int(v) => as_int(element) == Some(v),
+			ListpackEntry::Str(s) => s == element,
+		}
+	}
+}
+
+/// Elements that are the canonical representation of a 64 bit integer are stored as integers.
+fn as_int(element: &[u8]) -> Option<i64> {
+	if element.len() > 20 {
+		return None;
+	}
+	parse_integer(element).ok()
+}
+
+/// A list of strings and integers in a single allocation, with the entry format of the Redis listpack:
+/// each entry is an encoding byte, possibly followed by a length and the data, and ends with its own
+/// length in a variable number of bytes so the list can be walked in both directions.
+/// Entries are addressed by their byte offset.
+#[derive(Clone, Default)]
+pub struct Listpack {
+	data: Vec<u8>,
+	len: usize,
+}
+
+impl Listpack {
+	pub fn new() -> Listpack {
+		Listpack::default()
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	/// The number of bytes the listpack takes in Redis.
+	pub fn bytes(&self) -> usize {
+		self.data.len() + LISTPACK_OVERHEAD
+	}
+
+	/// Offset of the element at `index`, negative indexes count from the end.
+	pub fn seek(&self, index: i64) -> Option<usize> {
+		if index >= 0 {
+			let mut offset = self.first();
+			for _ in 0..index {
+				offset = self.next(offset?);
+			}
+			offset
+		} else {
+			let mut offset = self.last();
+			for _ in 0..(-index - 1) {
+				offset = self.prev(offset?);
+			}
+			offset
+		}
+	}
+
+	pub fn first(&self) -> Option<usize> {
+		(!self.is_empty()).then_some(0)
+	}
+
+	pub fn last(&self) -> Option<usize> {
+		self.prev(self.data.len())
+	}
+
+	/// Offset just past the last element, inserting there appends.
+	pub fn end(&self) -> usize {
+		self.data.len()
+	}
+
+	pub fn next(&self, offset: usize) -> Option<usize> {
+		let next = offset + self.entry_size(offset);
+		(next < self.data.len()).then_some(next)
+	}
+
+	pub fn prev(&self, offset: usize) -> Option<usize> {
+		if offset == 0 {
+			return None;
+		}
+		let (size, backlen_size) = decode_backlen(&self.data[..offset]);
+		Some(offset - size - backlen_size)
+	}
+
+	pub fn get(&self, offset: usize) -> ListpackEntry<'_> {
+		let data = &self.data[offset..];
+		let encoding = data[0];
+		match encoding {
+			0x00..=0x7f => ListpackEntry::Int(encoding as i64),
+			0x80..=0xbf => ListpackEntry::Str(&data[1..1 + (encoding & 0x3f) as usize]),
+			0xc0..=0xdf => {
+				let v = (((encoding & 0x1f) as i64) << 8) | data[1] as i64;
+				// 13 bit two's complement.
+				ListpackEntry::Int(if v >= 1 << 12 { v - (1 << 13) } else { v })
+			},
+			0xe0..=0xef => {
+				let len = (((encoding & 0x0f) as usize) << 8) | data[1] as usize;
+				ListpackEntry::Str(&data[2..2 + len])
+			},
+			0xf0 => {
+				let len = u32::from_le_bytes([data[1], data[2], data[3], data[4]]) as usize;
+				ListpackEntry::Str(&data[5..5 + len])
+			},
+			0xf1 => ListpackEntry::Int(i16::from_le_bytes([data[1], data[2]]) as i64),
+			0xf2 => ListpackEntry::Int((i32::from_le_bytes([0, data[1], data[2], data[3]]) >> 8) as i64),
+			0xf3 => ListpackEntry::Int(i32::from_le_bytes([data[1], data[2], data[3], data[4]]) as i64),
+			0xf4 => ListpackEntry::Int(i64::from_le_bytes(data[1..9].try_into().unwrap())),
+			_ => unreachable!("invalid listpack encoding {encoding:#x}"),
+		}
+	}
+
+	pub fn iter(&self) -> ListpackIter<'_> {
+		ListpackIter {
+			listpack: self,
+			front: 0,
+			back: self.data.len(),
+			remaining: self.len,
+		}
+	}
+
+	/// Inserts `element` before the element at `offset`, or at the end if `offset` is the size of the data.
+	pub fn insert(&mut self, offset: usize, element: &[u8]) {
+		let encoded = encode(element);
+		self.data.splice(offset..offset, encoded);
+		self.len += 1;
+	}
+
+	pub fn push_back(&mut self, element: &[u8]) {
+		self.insert(self.data.len(), element);
+	}
+
+	pub fn push_front(&mut self, element: &[u8]) {
+		self.insert(0, element);
+	}
+
+	/// Replaces the element at `offset`.
+	pub fn replace(&mut self, offset: usize, element: &[u8]) {
+		let size = self.entry_size(offset);
+		self.data.splice(offset..offset + size, encode(element));
+	}
+
+	/// Deletes the element at `offset`. The following element then starts at `offset`.
+	pub fn delete(&mut self, offset: usize) {
+		let size = self.entry_size(offset);
+		self.data.drain(offset..offset + size);
+		self.len -= 1;
+	}
+
+	/// Deletes `count` elements starting with the element at `offset`.
+	pub fn delete_range(&mut self, offset: usize, count: usize) {
+		let mut end = offset;
+		for _ in 0..count {
+			if end >= self.data.len() {
+				break;
+			}
+			end += self.entry_size(end);
+			self.len -= 1;
+		}
+		self.data.drain(offset..end);
+	}
+
+	/// Returns the offset of the first element at or after `offset` matching `element`,
+	/// checking only every `skip + 1`th element.
+	pub fn find(&self, offset: usize, element: &[u8], skip: usize) -> Option<usize> {
+		let mut offset = Some(offset);
+		while let Some(current) = offset {
+			if self.get(current).matches(element) {
+				return Some(current);
+			}
+			offset = self.next(current);
+			for _ in 0..skip {
+				offset = offset.and_then(|o| self.next(o));
+			}
+		}
+		None
+	}
+
+	/// Moves the elements from `offset` on into a new listpack.
+	pub fn split_off(&mut self, offset: usize) -> Listpack {
+		let data = self.data.split_off(offset);
+		let mut count = 0;
+		let mut position = 0;
+		while position < data.len() {
+			position += entry_size(&data[position..]);
+			count += 1;
+		}
+		self.len -= count;
+		Listpack {
+			data,
+			len: count,
+		}
+	}
+
+	/// Size of the entry at `offset`, including its encoding and length.
+	fn entry_size(&self, offset: usize) -> usize {
+		entry_size(&self.data[offset..])
+	}
+}
+
+/// Iterates over the elements of a listpack from either end.
+pub struct ListpackIter<'a> {
+	listpack: &'a Listpack,
+	front: usize,
+	back: usize,
+	remaining: usize,
+}
+
+impl<'a> Iterator for ListpackIter<'a> {
+	type Item = ListpackEntry<'a>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		let entry = self.listpack.get(self.front);
+		self.front += self.listpack.entry_size(self.front);
+		self.remaining -= 1;
+		Some(entry)
+	}
+
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		(self.remaining, Some(self.remaining))
+	}
+}
+
+impl DoubleEndedIterator for ListpackIter<'_> {
+	fn next_back(&mut self) -> Option<Self::Item> {
+		if self.remaining == 0 {
+			return None;
+		}
+		self.back = self.listpack.prev(self.back)?;
+		self.remaining -= 1;
+		Some(self.listpack.get(self.back))
+	}
+}
+
+impl ExactSizeIterator for ListpackIter<'_> {}
+
+/// Size of the entry at the start of `data`, including its encoding and length.
+fn entry_size(data: &[u8]) -> usize {
+	let encoding = data[0];
+	let size = match encoding {
+		0x00..=0x7f => 1,
+		0x80..=0xbf => 1 + (encoding & 0x3f) as usize,
+		0xc0..=0xdf => 2,
+		0xe0..=0xef => 2 + ((((encoding & 0x0f) as usize) << 8) | data[1] as usize),
+		0xf0 => 5 + u32::from_le_bytes([data[1], data[2], data[3], data[4]]) as usize,
+		0xf1 => 3,
+		0xf2 => 4,
+		0xf3 => 5,
+		0xf4 => 9,
+		_ => unreachable!("invalid listpack encoding {encoding:#x}"),
+	};
+	size + backlen_size(size)
+}
+
+fn encode(element: &[u8]) -> Vec<u8> {
+	let mut encoded = Vec::with_capacity(element.len() + 10);
+	match as_int(element) {
+		Some(v @ 0..=127) => encoded.push(v as u8),
+		Some(v @ -4096..=4095) => {
+			let v = (v as u16) & 0x1fff;
+			encoded.extend_from_slice(&[0xc0 | (v >> 8) as u8, v as u8]);
+		},
+		Some(v @ -32768..=32767) => {
+			encoded.push(0xf1);
+			encoded.extend_from_slice(&(v as i16).to_le_bytes());
+		},
+		Some(v @ -8388608..=8388607) => {
+			encoded.push(0xf2);
+			encoded.extend_from_slice(&(v as i32).to_le_bytes()[..3]);
+		},
+		Some(v @ -2147483648..=2147483647) => {
+			encoded.push(0xf3);
+			encoded.extend_from_slice(&(v as i32).to_le_bytes());
+		},
+		Some(v) => {
+			encoded.push(0xf4);
+			encoded.extend_from_slice(&v.to_le_bytes());
+		},
+		None if element.len() < 64 => {
+			encoded.push(0x80 | element.len() as u8);
+			encoded.extend_from_slice(element);
+		},
+		None if element.len() < 4096 => {
+			encoded.extend_from_slice(&[0xe0 | (element.len() >> 8) as u8, element.len() as u8]);
+			encoded.extend_from_slice(element);
+		},
+		None => {
+			encoded.push(0xf0);
+			encoded.extend_from_slice(&(element.len() as u32).to_le_bytes());
+			encoded.extend_from_slice(element);
+		},
+	}
+	encode_backlen(encoded.len(), &mut encoded);
+	encoded
+}
+
+fn backlen_size(size: usize) -> usize {
+	match size {
+		0..=127 => 1,
+		128..=16383 => 2,
+		16384..=2097151 => 3,
+		2097152..=268435455 => 4,
+		_ => 5,
+	}
+}
+
+/// Appends `size` 7 bits at a time, most significant bits first.
+/// All bytes but the first one have their high bit set, so the length can be read from the end.
+fn encode_backlen(size: usize, out: &mut Vec<u8>) {
+	let bytes = backlen_size(size);
+	for i in (0..bytes).rev() {
+		let bits = ((size >> (7 * i)) & 127) as u8;
+		out.push(if i == bytes - 1 { bits } else { bits | 128 });
+	}
+}
+
+/// Reads the length at the end of `data`. Returns the size of the entry without and the size of the length.
+fn decode_backlen(data: &[u8]) -> (usize, usize) {
+	let mut size = 0;
+	let mut shift = 0;
+	let mut position = data.len();
+	loop {
+		position -= 1;
+		let byte = data[position];
+		size |= ((byte & 127) as usize) << shift;
+		if byte & 128 == 0 {
+			break;
+		}
+		shift += 7;
+	}
+	(size, data.len() - position)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn listpack_of(elements: &[&str]) -> Listpack {
+		let mut listpack = Listpack::new();
+		elements.iter().for_each(|element| listpack.push_back(element.as_bytes()));
+		listpack
+	}
+
+	fn elements(listpack: &Listpack) -> Vec<Bytes> {
+		listpack.iter().map(|entry| entry.to_bytes()).collect()
+	}
+
+	#[test]
+	fn round_trips_every_encoding() {
+		let long = "x".repeat(5000);
+		let values = [
+			"0", "127", "128", "-1", "-4096", "4095", "-32768", "32767", "-8388608", "8388607",
+			"-2147483648", "2147483647", "9223372036854775807", "-9223372036854775808",
+			"", "a", &"y".repeat(63), &"z".repeat(64), &"w".repeat(4095), &long, "01", "1.5",
+		];
+		let listpack = listpack_of(&values);
+		assert_eq!(elements(&listpack), values);
+		let reversed: Vec<Bytes> = listpack.iter().rev().map(|entry| entry.to_bytes()).collect();
+		assert_eq!(reversed, values.iter().rev().copied().collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn stores_integers_as_integers() {
+		let listpack = listpack_of(&["12", "012", "-7"]);
+		assert_eq!(listpack.iter().collect::<Vec<_>>(), [ListpackEntry::Int(12), ListpackEntry::Str(b"012"), ListpackEntry::Int(-7)]);
+		assert!(listpack.get(0).matches(b"12"));
+		assert!(!listpack.get(0).matches(b"012"));
+	}
+
+	#[test]
+	fn seeks_from_both_ends() {
+		let listpack = listpack_of(&["a", "b", "c"]);
+		assert_eq!(listpack.get(listpack.seek(1).unwrap()).to_bytes(), "b");
+		assert_eq!(listpack.get(listpack.seek(-1).unwrap()).to_bytes(), "c");
+		assert_eq!(listpack.get(listpack.seek(-3).unwrap()).to_bytes(), "a");
+		assert_eq!(listpack.seek(3), None);
+		assert_eq!(listpack.seek(-4), None);
+		assert_eq!(Listpack::new().seek(0), None);
+	}
+
+	#[test]
+	fn inserts_replaces_and_deletes_in_the_middle() {
+		let mut listpack = listpack_of(&["a", "b", "c", "d"]);
+		let offset = listpack.seek(1).unwrap();
+		listpack.replace(offset, "x".repeat(200).as_bytes());
+		listpack.insert(listpack.seek(1).unwrap(), b"1000");
+		assert_eq!(elements(&listpack), ["a", "1000", &"x".repeat(200), "c", "d"]);
+		listpack.delete(listpack.seek(2).unwrap());
+		assert_eq!(elements(&listpack), ["a", "1000", "c", "d"]);
+		listpack.delete_range(listpack.seek(1).unwrap(), 10);
+		assert_eq!(elements(&listpack), ["a"]);
+		assert_eq!(listpack.len(), 1);
+	}
+
+	#[test]
+	fn finds_every_nth_element() {
+		let listpack = listpack_of(&["f1", "v1", "f2", "f1"]);
+		assert_eq!(listpack.find(0, b"f1", 1), Some(0));
+		// Values are skipped, so a value equal to the field is not found.
+		assert_eq!(listpack.find(0, b"v1", 1), None);
+		assert_eq!(listpack.find(listpack.seek(1).unwrap(), b"f1", 0), listpack.seek(3));
+	}
+
+	#[test]
+	fn splits_off_the_tail() {
+		let mut listpack = listpack_of(&["a", "2", "c", "4"]);
+		let tail = listpack.split_off(listpack.seek(2).unwrap());
+		assert_eq!(elements(&listpack), ["a", "2"]);
+		assert_eq!(elements(&tail), ["c", "4"]);
+		assert_eq!((listpack.len(), tail.len()), (2, 2));
+	}
+}
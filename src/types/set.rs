@@ -0,0 +1,250 @@
+use bytes::Bytes;
+use rand::Rng;
+
+use crate::{dict::Dict, util::parse_integer};
+
+use super::{intset::Intset, listpack::Listpack, EncodingLimits};
+
+/// A set. Sets of integers are an intset, other small sets a listpack,
+/// and both are converted to a hash table once they have too many or too large members.
+#[derive(Clone)]
+pub enum Set {
+	Intset(Intset),
+	Listpack(Listpack),
+	Hashtable(Dict<Bytes, ()>),
+}
+
+impl Set {
+	/// Creates a set with the encoding that fits `member` and about `size` members.
+	pub fn new(member: &[u8], size: usize, limits: &EncodingLimits) -> Set {
+		if as_int(member).is_some() && size <= limits.set_max_intset_entries {
+			Set::Intset(Intset::new())
+		} else if size <= limits.set_max_listpack_entries {
+			Set::Listpack(Listpack::new())
+		} else {
+			Set::Hashtable(Dict::new())
+		}
+	}
+
+	/// Creates a set of `members`, which may contain duplicates.
+	pub fn from_members(members: &[Bytes], limits: &EncodingLimits) -> Set {
+		let mut set = Set::new(members.first().map_or(&[][..], |member| member), members.len(), limits);
+		for member in members {
+			set.insert(member, limits);
+		}
+		set
+	}
+
+	pub fn len(&self) -> usize {
+		match self {
+			Set::Intset(intset) => intset.len(),
+			Set::Listpack(listpack) => listpack.len(),
+			Set::Hashtable(dict) => dict.len(),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	pub fn encoding(&self) -> &'static str {
+		match self {
+			Set::Intset(_) => "intset",
+			Set::Listpack(_) => "listpack",
+			Set::Hashtable(_) => "hashtable",
+		}
+	}
+
+	pub fn free_effort(&self) -> usize {
+		match self {
+			Set::Intset(_) | Set::Listpack(_) => 1,
+			Set::Hashtable(dict) => dict.len(),
+		}
+	}
+
+	pub fn contains(&self, member: &[u8]) -> bool {
+		match self {
+			Set::Intset(intset) => as_int(member).is_some_and(|v| intset.contains(v)),
+			Set::Listpack(listpack) => listpack.first().and_then(|first| listpack.find(first, member, 0)).is_some(),
+			Set::Hashtable(dict) => dict.contains_key(member),
+		}
+	}
+
+	/// Returns whether `member` was added.
+	pub fn insert(&mut self, member: &[u8], limits: &EncodingLimits) -> bool {
+		match self {
+			Set::Intset(intset) => match as_int(member) {
+				Some(v) => {
+					let added = intset.insert(v);
+					if intset.len() > limits.set_max_intset_entries {
+						self.convert(Set::Hashtable(Dict::new()));
+					}
+					added
+				},
+				None => {
+					// Only switch to a listpack if the integers fit in one as well.
+					let max_len = intset.iter().map(|v| v.to_string().len()).max().unwrap_or(0);
+					if intset.len() < limits.set_max_listpack_entries && member.len().max(max_len) <= limits.set_max_listpack_value {
+						self.convert(Set::Listpack(Listpack::new()));
+					} else {
+						self.convert(Set::Hashtable(Dict::new()));
+					}
+					self.insert(member, limits)
+				},
+			},
+			Set::Listpack(listpack) => {
+				if listpack.first().and_then(|first| listpack.find(first, member, 0)).is_some() {
+					return false;
+				}
+				if listpack.len() < limits.set_max_listpack_entries && member.len() <= limits.set_max_listpack_value {
+					listpack.push_back(member);
+					return true;
+				}
+				self.convert(Set::Hashtable(Dict::new()));
+				self.insert(member, limits)
+			},
+			Set::Hashtable(dict) => dict.insert(Bytes::copy_from_slice(member), ()).is_none(),
+		}
+	}
+
+	/// Returns whether `member` was removed.
+	pub fn remove(&mut self, member: &[u8]) -> bool {
+		match self {
+			Set::Intset(intset) => as_int(member).is_some_and(|v| intset.remove(v)),
+			Set::Listpack(listpack) => match listpack.first().and_then(|first| listpack.find(first, member, 0)) {
+				Some(offset) => {
+					listpack.delete(offset);
+					true
+				},
+				None => false,
+			},
+			Set::Hashtable(dict) => dict.remove(member).is_some(),
+		}
+	}
+
+	pub fn random(&self) -> Option<Bytes> {
+		match self {
+			Set::Intset(intset) => intset.random().map(|v| Bytes::from(v.to_string())),
+			Set::Listpack(listpack) => {
+				let index = rand::thread_rng().gen_range(0..listpack.len().max(1));
+				listpack.seek(index as i64).map(|offset| listpack.get(offset).to_bytes())
+			},
+			Set::Hashtable(dict) => dict.random_entry().map(|(member, _)| member.clone()),
+		}
+	}
+
+	pub fn iter(&self) -> Box<dyn Iterator<Item = Bytes> + '_> {
+		match self {
+			Set::Intset(intset) => Box::new(intset.iter().map(|v| Bytes::from(v.to_string()))),
+			Set::Listpack(listpack) => Box::new(listpack.iter().map(|entry| entry.to_bytes())),
+			Set::Hashtable(dict) => Box::new(dict.iter().map(|(member, _)| member.clone())),
+		}
+	}
+
+	/// Calls `f` with the members at `cursor` and returns the cursor to continue with, see `Dict::scan`.
+	/// Intsets and listpacks are small, so all of their members are visited at once and the returned cursor is 0.
+	pub fn scan(&self, cursor: u64, mut f: impl FnMut(Bytes)) -> u64 {
+		match self {
+			Set::Intset(_) | Set::Listpack(_) => {
+				self.iter().for_each(f);
+				0
+			},
+			Set::Hashtable(dict) => dict.scan(cursor, |member, _| f(member.clone())),
+		}
+	}
+
+	/// Moves all members into the empty set `target`.
+	fn convert(&mut self, mut target: Set) {
+		match &mut target {
+			Set::Intset(intset) => self.iter().for_each(|member| {
+				intset.insert(as_int(&member).unwrap());
+			}),
+			Set::Listpack(listpack) => self.iter().for_each(|member| listpack.push_back(&member)),
+			Set::Hashtable(dict) => self.iter().for_each(|member| {
+				dict.insert(member, ());
+			}),
+		}
+		*self = target;
+	}
+}
+
+/// Members that are the canonical representation of a 64 bit integer can be stored in an intset.
+fn as_int(member: &[u8]) -> Option<i64> {
+	if member.len() > 20 {
+		return None;
+	}
+	parse_integer(member).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn limits() -> EncodingLimits {
+		EncodingLimits {
+			set_max_intset_entries: 4,
+			set_max_listpack_entries: 3,
+			set_max_listpack_value: 8,
+			..EncodingLimits::default()
+		}
+	}
+
+	fn members(set: &Set) -> Vec<Bytes> {
+		let mut members: Vec<Bytes> = set.iter().collect();
+		members.sort();
+		members
+	}
+
+	#[test]
+	fn intsets_become_hashtables_beyond_the_entry_limit() {
+		let limits = limits();
+		let mut set = Set::from_members(&[Bytes::from("1")], &limits);
+		for member in ["3", "2", "4"] {
+			assert!(set.insert(member.as_bytes(), &limits));
+		}
+		assert_eq!(set.encoding(), "intset");
+		assert!(!set.insert(b"4", &limits));
+		assert!(set.insert(b"5", &limits));
+		assert_eq!(set.encoding(), "hashtable");
+		assert_eq!(members(&set), ["1", "2", "3", "4", "5"]);
+	}
+
+	#[test]
+	fn intsets_become_listpacks_for_small_strings() {
+		let limits = limits();
+		let mut set = Set::from_members(&[Bytes::from("1"), Bytes::from("2")], &limits);
+		assert!(set.insert(b"a", &limits));
+		assert_eq!(set.encoding(), "listpack");
+		assert!(set.contains(b"1") && set.contains(b"a"));
+		// Too many members for a listpack.
+		let mut set = Set::from_members(&[Bytes::from("1"), Bytes::from("2"), Bytes::from("3")], &limits);
+		set.insert(b"a", &limits);
+		assert_eq!(set.encoding(), "hashtable");
+		// Too long for a listpack.
+		let mut set = Set::from_members(&[Bytes::from("1")], &limits);
+		set.insert(b"abcdefghi", &limits);
+		assert_eq!(set.encoding(), "hashtable");
+	}
+
+	#[test]
+	fn non_canonical_integers_are_strings() {
+		let limits = limits();
+		let set = Set::from_members(&[Bytes::from("01")], &limits);
+		assert_eq!(set.encoding(), "listpack");
+		assert!(set.contains(b"01"));
+		assert!(!set.contains(b"1"));
+	}
+
+	#[test]
+	fn removes_members_of_every_encoding() {
+		let limits = limits();
+		for initial in [&["1", "2"][..], &["a", "b"], &["a", "b", "c", "d"]] {
+			let initial: Vec<Bytes> = initial.iter().map(|m| Bytes::copy_from_slice(m.as_bytes())).collect();
+			let mut set = Set::from_members(&initial, &limits);
+			assert!(set.remove(&initial[0]));
+			assert!(!set.remove(&initial[0]));
+			assert!(!set.remove(b"missing"));
+			assert_eq!(set.len(), initial.len() - 1);
+		}
+	}
+}
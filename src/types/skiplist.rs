@@ -0,0 +1,351 @@
+use std::cmp::Ordering;
+
+use bytes::Bytes;
+use rand::Rng;
+
+/// Maximum number of levels of a node, enough for 2^64 elements.
+const MAX_LEVEL: usize = 32;
+/// Probability of a node having another level.
+const LEVEL_PROBABILITY: f64 = 0.25;
+/// The header node is the first node of the arena and has all levels.
+const HEAD: usize = 0;
+
+#[derive(Clone)]
+struct Level {
+	forward: Option<usize>,
+	/// Number of elements between this node and `forward`, so ranks can be computed while walking the levels.
+	span: usize,
+}
+
+#[derive(Clone)]
+struct Node {
+	member: Bytes,
+	score: f64,
+	backward: Option<usize>,
+	levels: Vec<Level>,
+}
+
+impl Node {
+	/// Whether the node sorts before the element `score`, `member`.
+	fn before(&self, score: f64, member: &[u8]) -> bool {
+		compare(self.score, &self.member, score, member) == Ordering::Less
+	}
+}
+
+/// Orders elements by score, then by member.
+pub fn compare(score: f64, member: &[u8], other_score: f64, other_member: &[u8]) -> Ordering {
+	score.total_cmp(&other_score).then_with(|| member.cmp(other_member))
+}
+
+/// A skiplist of members ordered by score like the one of Redis, with the spans that make rank queries O(log n).
+/// Nodes live in an arena and refer to each other by index.
+#[derive(Clone)]
+pub struct Skiplist {
+	nodes: Vec<Option<Node>>,
+	free: Vec<usize>,
+	len: usize,
+	level: usize,
+}
+
+impl Skiplist {
+	pub fn new() -> Skiplist {
+		let head = Node {
+			member: Bytes::new(),
+			score: 0.0,
+			backward: None,
+			levels: vec![Level { forward: None, span: 0 }; MAX_LEVEL],
+		};
+		Skiplist {
+			nodes: vec![Some(head)],
+			free: Vec::new(),
+			len: 0,
+			level: 1,
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		self.len
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len == 0
+	}
+
+	fn node(&self, id: usize) -> &Node {
+		self.nodes[id].as_ref().expect("skiplist node was freed")
+	}
+
+	fn node_mut(&mut self, id: usize) -> &mut Node {
+		self.nodes[id].as_mut().expect("skiplist node was freed")
+	}
+
+	/// For every level, the last node before the element and the rank of that node.
+	fn predecessors(&self, score: f64, member: &[u8]) -> ([usize; MAX_LEVEL], [usize; MAX_LEVEL]) {
+		let mut update = [HEAD; MAX_LEVEL];
+		let mut rank = [0; MAX_LEVEL];
+		let mut x = HEAD;
+		for i in (0..self.level).rev() {
+			rank[i] = if i == self.level - 1 { 0 } else { rank[i + 1] };
+			while let Some(next) = self.node(x).levels[i].forward {
+				if !self.node(next).before(score, member) {
+					break;
+				}
+				rank[i] += self.node(x).levels[i].span;
+				x = next;
+			}
+			update[i] = x;
+		}
+		(update, rank)
+	}
+
+	/// Inserts an element that is not in the list yet.
+	pub fn insert(&mut self, score: f64, member: Bytes) {
+		let (mut update, mut rank) = self.predecessors(score, &member);
+		let level = random_level();
+		if level > self.level {
+			for i in self.level..level {
+				rank[i] = 0;
+				update[i] = HEAD;
+				self.node_mut(HEAD).levels[i].span = self.len;
+			}
+			self.level = level;
+		}
+		let node = Node {
+			member,
+			score,
+			backward: (update[0] != HEAD).then_some(update[0]),
+			levels: vec![Level { forward: None, span: 0 }; level],
+		};
+		let id = match self.free.pop() {
+			Some(id) => {
+				self.nodes[id] = Some(node);
+				id
+			},
+			None => {
+				self.nodes.push(Some(node));
+				self.nodes.len() - 1
+			},
+		};
+		for i in 0..level {
+			let previous = &self.node(update[i]).levels[i];
+			let (forward, span) = (previous.forward, previous.span);
+			self.node_mut(id).levels[i] = Level { forward, span: span - (rank[0] - rank[i]) };
+			self.node_mut(update[i]).levels[i] = Level { forward: Some(id), span: rank[0] - rank[i] + 1 };
+		}
+		for (i, &previous) in update.iter().enumerate().take(self.level).skip(level) {
+			self.node_mut(previous).levels[i].span += 1;
+		}
+		if let Some(next) = self.node(id).levels[0].forward {
+			self.node_mut(next).backward = Some(id);
+		}
+		self.len += 1;
+	}
+
+	/// Removes the element. Returns whether it was in the list.
+	pub fn remove(&mut self, score: f64, member: &[u8]) -> bool {
+		let (update, _) = self.predecessors(score, member);
+		let Some(id) = self.node(update[0]).levels[0].forward else {
+			return false;
+		};
+		let node = self.node(id);
+		if node.score != score || node.member != member {
+			return false;
+		}
+		for (i, &previous) in update.iter().enumerate().take(self.level) {
+			if self.node(previous).levels[i].forward == Some(id) {
+				let Level { forward, span } = self.node(id).levels[i].clone();
+				let previous = &mut self.node_mut(previous).levels[i];
+				previous.span += span;
+				previous.span -= 1;
+				previous.forward = forward;
+			} else {
+				self.node_mut(previous).levels[i].span -= 1;
+			}
+		}
+		let backward = self.node(id).backward;
+		if let Some(next) = self.node(id).levels[0].forward {
+			self.node_mut(next).backward = backward;
+		}
+		while self.level > 1 && self.node(HEAD).levels[self.level - 1].forward.is_none() {
+			self.level -= 1;
+		}
+		self.nodes[id] = None;
+		self.free.push(id);
+		self.len -= 1;
+		true
+	}
+
+	/// The 0-based rank of the element, if it is in the list.
+	pub fn rank(&self, score: f64, member: &[u8]) -> Option<usize> {
+		let mut rank = 0;
+		let mut x = HEAD;
+		for i in (0..self.level).rev() {
+			while let Some(next) = self.node(x).levels[i].forward {
+				if compare(self.node(next).score, &self.node(next).member, score, member) == Ordering::Greater {
+					break;
+				}
+				rank += self.node(x).levels[i].span;
+				x = next;
+			}
+			if x != HEAD && self.node(x).member == member {
+				return Some(rank - 1);
+			}
+		}
+		None
+	}
+
+	/// The number of leading elements for which `before` holds. `before` has to hold for a prefix of the list.
+	pub fn count_before(&self, before: impl Fn(f64, &[u8]) -> bool) -> usize {
+		let mut count = 0;
+		let mut x = HEAD;
+		for i in (0..self.level).rev() {
+			while let Some(next) = self.node(x).levels[i].forward {
+				if !before(self.node(next).score, &self.node(next).member) {
+					break;
+				}
+				count += self.node(x).levels[i].span;
+				x = next;
+			}
+		}
+		count
+	}
+
+	/// The node of the element at the 0-based `rank`.
+	fn by_rank(&self, rank: usize) -> Option<usize> {
+		let target = rank + 1;
+		let mut traversed = 0;
+		let mut x = HEAD;
+		for i in (0..self.level).rev() {
+			while let Some(next) = self.node(x).levels[i].forward {
+				if traversed + self.node(x).levels[i].span > target {
+					break;
+				}
+				traversed += self.node(x).levels[i].span;
+				x = next;
+			}
+			if traversed == target {
+				return Some(x);
+			}
+		}
+		None
+	}
+
+	/// Iterates over the elements from the one at the 0-based `rank`, towards the end or, if `reverse` is set, the start.
+	pub fn iter_from(&self, rank: usize, reverse: bool) -> SkiplistIter<'_> {
+		SkiplistIter {
+			list: self,
+			node: self.by_rank(rank),
+			reverse,
+		}
+	}
+
+	pub fn iter(&self) -> SkiplistIter<'_> {
+		self.iter_from(0, false)
+	}
+}
+
+pub struct SkiplistIter<'a> {
+	list: &'a Skiplist,
+	node: Option<usize>,
+	reverse: bool,
+}
+
+impl<'a> Iterator for SkiplistIter<'a> {
+	type Item = (&'a Bytes, f64);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let node = self.list.node(self.node?);
+		self.node = if self.reverse { node.backward } else { node.levels[0].forward };
+		Some((&node.member, node.score))
+	}
+}
+
+fn random_level() -> usize {
+	let mut rng = rand::thread_rng();
+	let mut level = 1;
+	while level < MAX_LEVEL && rng.gen_bool(LEVEL_PROBABILITY) {
+		level += 1;
+	}
+	level
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn last(list: &Skiplist) -> Option<(&Bytes, f64)> {
+		list.iter().last()
+	}
+
+	#[test]
+	fn tracks_length_and_last_element() {
+		let mut list = Skiplist::new();
+		assert!(list.is_empty());
+		assert_eq!(last(&list), None);
+		list.insert(2.0, Bytes::from("b"));
+		list.insert(1.0, Bytes::from("a"));
+		list.insert(3.0, Bytes::from("c"));
+		assert_eq!(list.len(), 3);
+		assert_eq!(last(&list), Some((&Bytes::from("c"), 3.0)));
+		assert!(list.remove(3.0, b"c"));
+		assert_eq!(last(&list), Some((&Bytes::from("b"), 2.0)));
+		assert!(list.remove(1.0, b"a"));
+		assert!(list.remove(2.0, b"b"));
+		assert!(list.is_empty());
+		assert_eq!(last(&list), None);
+	}
+
+	fn members(list: &Skiplist) -> Vec<(Bytes, f64)> {
+		list.iter().map(|(member, score)| (member.clone(), score)).collect()
+	}
+
+	#[test]
+	fn orders_ties_by_member() {
+		let mut list = Skiplist::new();
+		for member in ["c", "a", "b"] {
+			list.insert(1.0, Bytes::from(member));
+		}
+		list.insert(0.5, Bytes::from("z"));
+		let order: Vec<Bytes> = members(&list).into_iter().map(|(member, _)| member).collect();
+		assert_eq!(order, ["z", "a", "b", "c"]);
+	}
+
+	#[test]
+	fn ranks_and_iterates_from_a_rank() {
+		let mut list = Skiplist::new();
+		for i in (0..1000).rev() {
+			list.insert(i as f64, Bytes::from(format!("m{i}")));
+		}
+		for i in [0, 1, 499, 998, 999] {
+			assert_eq!(list.rank(i as f64, format!("m{i}").as_bytes()), Some(i));
+		}
+		assert_eq!(list.rank(5.0, b"m6"), None);
+		assert_eq!(list.rank(1000.0, b"m1000"), None);
+		let forward: Vec<f64> = list.iter_from(997, false).map(|(_, score)| score).collect();
+		assert_eq!(forward, [997.0, 998.0, 999.0]);
+		let backward: Vec<f64> = list.iter_from(2, true).map(|(_, score)| score).collect();
+		assert_eq!(backward, [2.0, 1.0, 0.0]);
+		assert_eq!(list.iter_from(1000, false).next(), None);
+		assert_eq!(list.count_before(|score, _| score < 250.0), 250);
+	}
+
+	#[test]
+	fn keeps_ranks_consistent_after_removals() {
+		let mut list = Skiplist::new();
+		for i in 0..200 {
+			list.insert(i as f64, Bytes::from(i.to_string()));
+		}
+		for i in (0..200).step_by(3) {
+			assert!(list.remove(i as f64, i.to_string().as_bytes()));
+		}
+		assert!(!list.remove(0.0, b"0"));
+		let remaining: Vec<i32> = (0..200).filter(|i| i % 3 != 0).collect();
+		assert_eq!(list.len(), remaining.len());
+		for (rank, i) in remaining.iter().enumerate() {
+			assert_eq!(list.rank(*i as f64, i.to_string().as_bytes()), Some(rank));
+			assert_eq!(list.iter_from(rank, false).next().map(|(_, score)| score), Some(*i as f64));
+		}
+		let backward: Vec<f64> = list.iter_from(remaining.len() - 1, true).map(|(_, score)| score).collect();
+		assert_eq!(backward, remaining.iter().rev().map(|i| *i as f64).collect::<Vec<_>>());
+	}
+}
@@ -0,0 +1,177 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use bytes::Bytes;
+
+/// The ID of a stream entry, the Unix time in milliseconds it was added at and a sequence number for entries added in the same millisecond.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StreamId {
+	pub ms: u64,
+	pub seq: u64,
+}
+
+impl StreamId {
+	/// Parses `<ms>-<seq>`. The sequence number is `default_seq` if it is left out.
+	pub fn parse(arg: &[u8], default_seq: u64) -> Option<StreamId> {
+		let arg = std::str::from_utf8(arg).ok()?;
+		let (ms, seq) = match arg.split_once('-') {
+			Some((ms, seq)) => (ms, Some(seq)),
+			None => (arg, None),
+		};
+		let number = |s: &str| s.bytes().all(|c| c.is_ascii_digit()).then(|| s.parse::<u64>().ok()).flatten();
+		Some(StreamId {
+			ms: number(ms)?,
+			seq: match seq {
+				Some(seq) => number(seq)?,
+				None => default_seq,
+			},
+		})
+	}
+
+	/// The smallest ID greater than this one.
+	pub fn next(&self) -> Option<StreamId> {
+		match self.seq.checked_add(1) {
+			Some(seq) => Some(StreamId { ms: self.ms, seq }),
+			None => Some(StreamId { ms: self.ms.checked_add(1)?, seq: 0 }),
+		}
+	}
+}
+
+impl Display for StreamId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}-{}", self.ms, self.seq)
+	}
+}
+
+/// An append only log of entries, each a list of field value pairs, ordered by their IDs.
+/// Unlike other containers, streams stay in the keyspace when they become empty, so the last ID is not forgotten.
+#[derive(Clone, Default)]
+pub struct Stream {
+	entries: BTreeMap<StreamId, Vec<(Bytes, Bytes)>>,
+	/// The greatest ID ever added, IDs of new entries have to be greater.
+	last_id: StreamId,
+}
+
+impl Stream {
+	pub fn new() -> Stream {
+		Stream::default()
+	}
+
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn encoding(&self) -> &'static str {
+		"stream"
+	}
+
+	pub fn free_effort(&self) -> usize {
+		self.entries.len()
+	}
+
+	pub fn last_id(&self) -> StreamId {
+		self.last_id
+	}
+
+	/// The ID for an entry added at the Unix time `now_ms`, `None` if the IDs are exhausted.
+	pub fn next_id(&self, now_ms: u64) -> Option<StreamId> {
+		match now_ms > self.last_id.ms {
+			true => Some(StreamId { ms: now_ms, seq: 0 }),
+			false => self.last_id.next(),
+		}
+	}
+
+	/// Appends an entry. `id` has to be greater than `last_id`.
+	pub fn add(&mut self, id: StreamId, fields: Vec<(Bytes, Bytes)>) {
+		debug_assert!(id > self.last_id);
+		self.entries.insert(id, fields);
+		self.last_id = id;
+	}
+
+	/// Removes the oldest entries until at most `maxlen` are left, but no more than `limit` of them.
+	/// Returns the number of removed entries.
+	pub fn trim_to_len(&mut self, maxlen: usize, limit: usize) -> usize {
+		let count = self.len().saturating_sub(maxlen).min(limit);
+		for _ in 0..count {
+			self.entries.pop_first();
+		}
+		count
+	}
+
+	/// Removes the entries with IDs smaller than `min_id`, but no more than `limit` of them.
+	/// Returns the number of removed entries.
+	pub fn trim_to_min_id(&mut self, min_id: StreamId, limit: usize) -> usize {
+		let mut count = 0;
+		while count < limit && self.entries.first_key_value().is_some_and(|(id, _)| *id < min_id) {
+			self.entries.pop_first();
+			count += 1;
+		}
+		count
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn id(ms: u64, seq: u64) -> StreamId {
+		StreamId { ms, seq }
+	}
+
+	const MAX: StreamId = StreamId { ms: u64::MAX, seq: u64::MAX };
+
+	fn stream_of(ids: &[StreamId]) -> Stream {
+		let mut stream = Stream::new();
+		for &id in ids {
+			stream.add(id, vec![(Bytes::from_static(b"field"), Bytes::from(id.to_string()))]);
+		}
+		stream
+	}
+
+	#[test]
+	fn parses_ids() {
+		assert_eq!(StreamId::parse(b"1526919030474-55", 0), Some(id(1526919030474, 55)));
+		assert_eq!(StreamId::parse(b"1526919030474", 7), Some(id(1526919030474, 7)));
+		assert_eq!(StreamId::parse(b"18446744073709551615-18446744073709551615", 0), Some(MAX));
+		for invalid in [&b""[..], b"-", b"1-", b"-1", b"1-2-3", b"+1", b"1--1", b"18446744073709551616", b"a-1"] {
+			assert_eq!(StreamId::parse(invalid, 0), None);
+		}
+		assert_eq!(id(5, 3).to_string(), "5-3");
+	}
+
+	#[test]
+	fn orders_ids_by_time_then_sequence() {
+		assert!(id(1, 9) < id(2, 0));
+		assert_eq!(id(1, 9).next(), Some(id(1, 10)));
+		assert_eq!(id(1, u64::MAX).next(), Some(id(2, 0)));
+		assert_eq!(MAX.next(), None);
+	}
+
+	#[test]
+	fn generates_ids_from_the_clock_or_the_last_id() {
+		let mut stream = Stream::new();
+		assert_eq!(stream.next_id(1000), Some(id(1000, 0)));
+		stream.add(id(1000, 0), Vec::new());
+		assert_eq!(stream.next_id(1000), Some(id(1000, 1)));
+		// A clock that went backwards does not produce smaller IDs.
+		assert_eq!(stream.next_id(900), Some(id(1000, 1)));
+		assert_eq!(stream.next_id(1001), Some(id(1001, 0)));
+		stream.add(MAX, Vec::new());
+		assert_eq!(stream.next_id(1001), None);
+	}
+
+	#[test]
+	fn trims_the_oldest_entries() {
+		let mut stream = stream_of(&[id(1, 0), id(2, 0), id(3, 0), id(4, 0), id(5, 0)]);
+		assert_eq!(stream.trim_to_len(3, 1), 1);
+		assert_eq!(stream.trim_to_len(3, usize::MAX), 1);
+		assert_eq!(stream.trim_to_len(3, usize::MAX), 0);
+		// The entries before 3-0 are gone.
+		assert_eq!(stream.trim_to_min_id(id(3, 0), usize::MAX), 0);
+		assert_eq!(stream.trim_to_min_id(id(5, 0), usize::MAX), 2);
+		assert_eq!(stream.len(), 1);
+		// Trimming everything keeps the last ID.
+		assert_eq!(stream.trim_to_len(0, usize::MAX), 1);
+		assert_eq!(stream.len(), 0);
+		assert_eq!(stream.last_id(), id(5, 0));
+	}
+}
@@ -0,0 +1,90 @@
+use bytes::Bytes;
+
+use crate::{commands::CommandError, util::parse_integer};
+
+/// Strings up to this length are stored inline instead of in a separate allocation.
+const EMBSTR_SIZE_LIMIT: usize = 44;
+/// Commands refuse to grow strings beyond this size, like `proto-max-bulk-len` in Redis.
+pub const STRING_MAX_SIZE: usize = 512 * 1024 * 1024;
+
+/// Fails if a string of `len` bytes would exceed `STRING_MAX_SIZE`.
+pub fn check_string_length(len: usize) -> Result<(), CommandError> {
+	if len > STRING_MAX_SIZE {
+		return Err(CommandError::Other(String::from("string exceeds maximum allowed size (proto-max-bulk-len)")));
+	}
+	Ok(())
+}
+
+/// A string value with the encodings of Redis.
+#[derive(Clone)]
+pub enum RedisString {
+	/// A string that is the canonical representation of a 64 bit integer.
+	Int(i64),
+	/// A short string stored inline.
+	Embstr { len: u8, data: [u8; EMBSTR_SIZE_LIMIT] },
+	Raw(Bytes),
+}
+
+impl RedisString {
+	/// Picks the most compact encoding for `value`.
+	pub fn new(value: Bytes) -> RedisString {
+		if value.len() <= 20 {
+			if let Ok(v) = parse_integer(&value) {
+				return RedisString::Int(v);
+			}
+		}
+		RedisString::plain(value)
+	}
+
+	/// Picks the embstr or raw encoding, even if `value` is an integer. Used for values computed by commands like `INCRBYFLOAT`.
+	pub fn plain(value: Bytes) -> RedisString {
+		if value.len() <= EMBSTR_SIZE_LIMIT {
+			let mut data = [0; EMBSTR_SIZE_LIMIT];
+			data[..value.len()].copy_from_slice(&value);
+			return RedisString::Embstr { len: value.len() as u8, data };
+		}
+		RedisString::Raw(value)
+	}
+
+	/// Stores `value` in a separate allocation. Used for strings that are likely to be modified in place.
+	pub fn raw(value: Bytes) -> RedisString {
+		RedisString::Raw(value)
+	}
+
+	pub fn from_int(value: i64) -> RedisString {
+		RedisString::Int(value)
+	}
+
+	pub fn to_bytes(&self) -> Bytes {
+		match self {
+			RedisString::Int(v) => Bytes::from(v.to_string()),
+			RedisString::Embstr { len, data } => Bytes::copy_from_slice(&data[..*len as usize]),
+			RedisString::Raw(v) => v.clone(),
+		}
+	}
+
+	/// The value as an integer, if the string is the representation of one.
+	pub fn as_int(&self) -> Option<i64> {
+		match self {
+			RedisString::Int(v) => Some(*v),
+			RedisString::Embstr { len, data } => parse_integer(&data[..*len as usize]).ok(),
+			RedisString::Raw(v) => parse_integer(v).ok(),
+		}
+	}
+
+	pub fn len(&self) -> usize {
+		match self {
+			RedisString::Int(v) => v.to_string().len(),
+			RedisString::Embstr { len, .. } => *len as usize,
+			RedisString::Raw(v) => v.len(),
+		}
+	}
+
+	pub fn encoding(&self) -> &'static str {
+		match self {
+			RedisString::Int(_) => "int",
+			RedisString::Embstr { .. } => "embstr",
+			RedisString::Raw(_) => "raw",
+		}
+	}
+}
@@ -0,0 +1,448 @@
+use bytes::Bytes;
+use rand::Rng;
+
+use crate::{commands::CommandError, dict::Dict, util::parse_float};
+
+use super::{listpack::{Listpack, ListpackEntry}, skiplist::{compare, Skiplist}, EncodingLimits};
+
+/// A sorted set. Small sorted sets are a listpack of alternating members and scores ordered by score,
+/// they are converted to a skiplist with a dict from members to scores once they have too many or too large members.
+#[derive(Clone)]
+pub enum SortedSet {
+	Listpack(Listpack),
+	Skiplist {
+		dict: Dict<Bytes, f64>,
+		list: Skiplist,
+	},
+}
+
+impl SortedSet {
+	/// Creates a sorted set with the encoding that fits about `size` members of up to `member_len` bytes.
+	pub fn new(size: usize, member_len: usize, limits: &EncodingLimits) -> SortedSet {
+		if size <= limits.zset_max_listpack_entries && member_len <= limits.zset_max_listpack_value {
+			SortedSet::Listpack(Listpack::new())
+		} else {
+			SortedSet::Skiplist {
+				dict: Dict::new(),
+				list: Skiplist::new(),
+			}
+		}
+	}
+
+	/// Creates a sorted set of `entries`, where later scores of the same member replace earlier ones.
+	pub fn from_entries(entries: &[(Bytes, f64)], limits: &EncodingLimits) -> SortedSet {
+		let member_len = entries.iter().map(|(member, _)| member.len()).max().unwrap_or(0);
+		let mut zset = SortedSet::new(entries.len(), member_len, limits);
+		for (member, score) in entries {
+			zset.insert(member, *score, limits);
+		}
+		zset
+	}
+
+	pub fn len(&self) -> usize {
+		match self {
+			SortedSet::Listpack(listpack) => listpack.len() / 2,
+			SortedSet::Skiplist { list, .. } => list.len(),
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		match self {
+			SortedSet::Listpack(listpack) => listpack.is_empty(),
+			SortedSet::Skiplist { list, .. } => list.is_empty(),
+		}
+	}
+
+	pub fn encoding(&self) -> &'static str {
+		match self {
+			SortedSet::Listpack(_) => "listpack",
+			SortedSet::Skiplist { .. } => "skiplist",
+		}
+	}
+
+	pub fn free_effort(&self) -> usize {
+		match self {
+			SortedSet::Listpack(_) => 1,
+			SortedSet::Skiplist { dict, .. } => dict.len(),
+		}
+	}
+
+	pub fn score(&self, member: &[u8]) -> Option<f64> {
+		match self {
+			SortedSet::Listpack(listpack) => {
+				let offset = find_member(listpack, member)?;
+				Some(entry_score(listpack.get(listpack.next(offset)?)))
+			},
+			SortedSet::Skiplist { dict, .. } => dict.get(member).copied(),
+		}
+	}
+
+	/// Sets the score of `member`. Returns whether the member is new.
+	pub fn insert(&mut self, member: &[u8], score: f64, limits: &EncodingLimits) -> bool {
+		// -0 and 0 are the same score, but would be ordered differently.
+		let score = if score == 0.0 { 0.0 } else { score };
+		if let SortedSet::Listpack(listpack) = self {
+			let current = find_member(listpack, member);
+			let too_many = current.is_none() && listpack.len() / 2 >= limits.zset_max_listpack_entries;
+			if too_many || member.len() > limits.zset_max_listpack_value {
+				self.convert();
+			} else {
+				if let Some(offset) = current {
+					listpack.delete_range(offset, 2);
+				}
+				insert_sorted(listpack, member, score);
+				return current.is_none();
+			}
+		}
+		let SortedSet::Skiplist { dict, list } = self else {
+			unreachable!();
+		};
+		match dict.get_mut(member) {
+			Some(current) => {
+				if *current != score {
+					list.remove(*current, member);
+					list.insert(score, Bytes::copy_from_slice(member));
+					*current = score;
+				}
+				false
+			},
+			None => {
+				let member = Bytes::copy_from_slice(member);
+				list.insert(score, member.clone());
+				dict.insert(member, score);
+				true
+			},
+		}
+	}
+
+	/// Returns whether `member` was removed.
+	pub fn remove(&mut self, member: &[u8]) -> bool {
+		match self {
+			SortedSet::Listpack(listpack) => match find_member(listpack, member) {
+				Some(offset) => {
+					listpack.delete_range(offset, 2);
+					true
+				},
+				None => false,
+			},
+			SortedSet::Skiplist { dict, list } => match dict.remove(member) {
+				Some(score) => list.remove(score, member),
+				None => false,
+			},
+		}
+	}
+
+	/// The 0-based rank of `member`, ordered by score.
+	pub fn rank(&self, member: &[u8]) -> Option<usize> {
+		match self {
+			SortedSet::Listpack(_) => self.iter().position(|(current, _)| current == member),
+			SortedSet::Skiplist { dict, list } => list.rank(*dict.get(member)?, member),
+		}
+	}
+
+	/// The number of leading members for which `before` holds. `before` has to hold for a prefix of the set.
+	pub fn count_before(&self, before: impl Fn(f64, &[u8]) -> bool) -> usize {
+		match self {
+			SortedSet::Listpack(_) => self.iter().take_while(|(member, score)| before(*score, member)).count(),
+			SortedSet::Skiplist { list, .. } => list.count_before(before),
+		}
+	}
+
+	/// Up to `count` members and their scores from the one at the 0-based rank `start` on,
+	/// towards the highest score or, if `reverse` is set, the lowest one.
+	pub fn range(&self, start: usize, count: usize, reverse: bool) -> Vec<(Bytes, f64)> {
+		if start >= self.len() {
+			return Vec::new();
+		}
+		match self {
+			SortedSet::Listpack(_) => {
+				let members: Vec<_> = self.iter().collect();
+				match reverse {
+					false => members.into_iter().skip(start).take(count).collect(),
+					true => members.into_iter().take(start + 1).rev().take(count).collect(),
+				}
+			},
+			SortedSet::Skiplist { list, .. } => list.iter_from(start, reverse).take(count).map(|(member, score)| (member.clone(), score)).collect(),
+		}
+	}
+
+	/// A random member and its score.
+	pub fn random(&self) -> Option<(Bytes, f64)> {
+		match self {
+			SortedSet::Listpack(_) => {
+				let index = rand::thread_rng().gen_range(0..self.len().max(1));
+				self.iter().nth(index)
+			},
+			SortedSet::Skiplist { dict, .. } => dict.random_entry().map(|(member, score)| (member.clone(), *score)),
+		}
+	}
+
+	/// Iterates over the members and their scores, ordered by score.
+	pub fn iter(&self) -> Box<dyn Iterator<Item = (Bytes, f64)> + '_> {
+		match self {
+			SortedSet::Listpack(listpack) => {
+				let mut entries = listpack.iter();
+				Box::new(std::iter::from_fn(move || Some((entries.next()?.to_bytes(), entry_score(entries.next()?)))))
+			},
+			SortedSet::Skiplist { list, .. } => Box::new(list.iter().map(|(member, score)| (member.clone(), score))),
+		}
+	}
+
+	/// Calls `f` with the members and scores at `cursor` and returns the cursor to continue with, see `Dict::scan`.
+	/// Listpacks are small, so all of their members are visited at once and the returned cursor is 0.
+	pub fn scan(&self, cursor: u64, mut f: impl FnMut(Bytes, f64)) -> u64 {
+		match self {
+			SortedSet::Listpack(_) => {
+				self.iter().for_each(|(member, score)| f(member, score));
+				0
+			},
+			SortedSet::Skiplist { dict, .. } => dict.scan(cursor, |member, score| f(member.clone(), *score)),
+		}
+	}
+
+	/// Converts a listpack to a skiplist.
+	fn convert(&mut self) {
+		if let SortedSet::Listpack(_) = self {
+			let mut dict = Dict::new();
+			let mut list = Skiplist::new();
+			for (member, score) in self.iter() {
+				list.insert(score, member.clone());
+				dict.insert(member, score);
+			}
+			*self = SortedSet::Skiplist { dict, list };
+		}
+	}
+}
+
+/// Offset of `member` in a listpack of alternating members and scores.
+fn find_member(listpack: &Listpack, member: &[u8]) -> Option<usize> {
+	listpack.find(listpack.first()?, member, 1)
+}
+
+fn entry_score(entry: ListpackEntry) -> f64 {
+	match entry {
+		ListpackEntry::Int(v) => v as f64,
+		ListpackEntry::Str(s) => std::str::from_utf8(s).ok().and_then(|s| s.parse().ok()).unwrap_or(0.0),
+	}
+}
+
+/// Inserts `member` before the first element that sorts after it.
+fn insert_sorted(listpack: &mut Listpack, member: &[u8], score: f64) {
+	let mut offset = listpack.first();
+	while let Some(current) = offset {
+		let entry = listpack.get(current);
+		let score_offset = listpack.next(current).unwrap();
+		if compare(score, member, entry_score(listpack.get(score_offset)), &entry.to_bytes()).is_lt() {
+			break;
+		}
+		offset = listpack.next(score_offset);
+	}
+	let offset = offset.unwrap_or(listpack.end());
+	// Scores are stored in their shortest representation that parses back to the same value.
+	listpack.insert(offset, &Bytes::from(score.to_string()));
+	listpack.insert(offset, member);
+}
+
+/// A range of scores like `(1 5` as given to `ZCOUNT` or `ZRANGE BYSCORE`. A `(` prefix excludes the bound.
+pub struct ScoreRange {
+	min: f64,
+	min_exclusive: bool,
+	max: f64,
+	max_exclusive: bool,
+}
+
+impl ScoreRange {
+	pub fn parse(min: &[u8], max: &[u8]) -> Result<ScoreRange, CommandError> {
+		let (min, min_exclusive) = parse_score_bound(min)?;
+		let (max, max_exclusive) = parse_score_bound(max)?;
+		Ok(ScoreRange { min, min_exclusive, max, max_exclusive })
+	}
+
+	/// Whether `score` sorts before the range.
+	pub fn below(&self, score: f64) -> bool {
+		if self.min_exclusive { score <= self.min } else { score < self.min }
+	}
+
+	/// Whether `score` does not sort after the range.
+	pub fn not_above(&self, score: f64) -> bool {
+		if self.max_exclusive { score < self.max } else { score <= self.max }
+	}
+}
+
+fn parse_score_bound(bound: &[u8]) -> Result<(f64, bool), CommandError> {
+	let (bound, exclusive) = match bound.strip_prefix(b"(") {
+		Some(bound) => (bound, true),
+		None => (bound, false),
+	};
+	let value = parse_float(bound).map_err(|_| CommandError::Other(String::from("min or max is not a float")))?;
+	Ok((value, exclusive))
+}
+
+/// A range of members like `[a (c` as given to `ZRANGE BYLEX`, for sorted sets whose members all have the same score.
+/// `-` and `+` are the lowest and the highest possible member.
+pub struct LexRange {
+	min: LexBound,
+	max: LexBound,
+}
+
+enum LexBound {
+	Inclusive(Bytes),
+	Exclusive(Bytes),
+	Lowest,
+	Highest,
+}
+
+impl LexRange {
+	pub fn parse(min: &[u8], max: &[u8]) -> Result<LexRange, CommandError> {
+		Ok(LexRange { min: parse_lex_bound(min)?, max: parse_lex_bound(max)? })
+	}
+
+	/// Whether `member` sorts before the range.
+	pub fn below(&self, member: &[u8]) -> bool {
+		match &self.min {
+			LexBound::Inclusive(min) => member < min.as_ref(),
+			LexBound::Exclusive(min) => member <= min.as_ref(),
+			LexBound::Lowest => false,
+			LexBound::Highest => true,
+		}
+	}
+
+	/// Whether `member` does not sort after the range.
+	pub fn not_above(&self, member: &[u8]) -> bool {
+		match &self.max {
+			LexBound::Inclusive(max) => member <= max.as_ref(),
+			LexBound::Exclusive(max) => member < max.as_ref(),
+			LexBound::Lowest => false,
+			LexBound::Highest => true,
+		}
+	}
+}
+
+fn parse_lex_bound(bound: &[u8]) -> Result<LexBound, CommandError> {
+	match bound {
+		b"-" => Ok(LexBound::Lowest),
+		b"+" => Ok(LexBound::Highest),
+		[b'[', rest @ ..] => Ok(LexBound::Inclusive(Bytes::copy_from_slice(rest))),
+		[b'(', rest @ ..] => Ok(LexBound::Exclusive(Bytes::copy_from_slice(rest))),
+		_ => Err(CommandError::Other(String::from("min or max not valid string range item"))),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn limits() -> EncodingLimits {
+		EncodingLimits {
+			zset_max_listpack_entries: 4,
+			zset_max_listpack_value: 8,
+			..EncodingLimits::default()
+		}
+	}
+
+	fn entries(zset: &SortedSet) -> Vec<(Bytes, f64)> {
+		zset.iter().collect()
+	}
+
+	/// A listpack and a skiplist encoded sorted set with the same members.
+	fn both_encodings(members: &[(&str, f64)]) -> [SortedSet; 2] {
+		let members: Vec<(Bytes, f64)> = members.iter().map(|(m, s)| (Bytes::copy_from_slice(m.as_bytes()), *s)).collect();
+		let listpack = SortedSet::from_entries(&members, &EncodingLimits::default());
+		let mut skiplist = SortedSet::new(usize::MAX, 0, &limits());
+		for (member, score) in &members {
+			skiplist.insert(member, *score, &limits());
+		}
+		assert_eq!((listpack.encoding(), skiplist.encoding()), ("listpack", "skiplist"));
+		[listpack, skiplist]
+	}
+
+	#[test]
+	fn converts_to_a_skiplist_beyond_the_entry_limit() {
+		let limits = limits();
+		let mut zset = SortedSet::new(1, 1, &limits);
+		for (i, member) in ["a", "b", "c", "d"].iter().enumerate() {
+			zset.insert(member.as_bytes(), i as f64, &limits);
+		}
+		assert_eq!(zset.encoding(), "listpack");
+		// Updating an existing member does not add an entry.
+		zset.insert(b"d", 10.0, &limits);
+		assert_eq!(zset.encoding(), "listpack");
+		zset.insert(b"e", 4.0, &limits);
+		assert_eq!(zset.encoding(), "skiplist");
+		assert_eq!(zset.len(), 5);
+		assert_eq!(zset.rank(b"d"), Some(4));
+	}
+
+	#[test]
+	fn converts_to_a_skiplist_for_long_members() {
+		let limits = limits();
+		let mut zset = SortedSet::new(1, 1, &limits);
+		zset.insert(b"12345678", 1.0, &limits);
+		assert_eq!(zset.encoding(), "listpack");
+		zset.insert(b"123456789", 2.0, &limits);
+		assert_eq!(zset.encoding(), "skiplist");
+		assert_eq!(SortedSet::from_entries(&[(Bytes::from("123456789"), 1.0)], &limits).encoding(), "skiplist");
+	}
+
+	#[test]
+	fn orders_by_score_then_member() {
+		for zset in both_encodings(&[("b", 1.0), ("a", 1.0), ("c", 0.5), ("d", -0.0), ("e", 0.0)]) {
+			let order: Vec<Bytes> = entries(&zset).into_iter().map(|(member, _)| member).collect();
+			assert_eq!(order, ["d", "e", "c", "a", "b"]);
+		}
+	}
+
+	#[test]
+	fn score_updates_move_members() {
+		for mut zset in both_encodings(&[("a", 1.0), ("b", 2.0), ("c", 3.0)]) {
+			assert!(!zset.insert(b"a", 5.0, &EncodingLimits::default()));
+			assert_eq!(zset.rank(b"a"), Some(2));
+			assert_eq!(zset.rank(b"b"), Some(0));
+			assert!(!zset.insert(b"c", 1.5, &EncodingLimits::default()));
+			assert_eq!(entries(&zset), [(Bytes::from("c"), 1.5), (Bytes::from("b"), 2.0), (Bytes::from("a"), 5.0)]);
+			assert_eq!(zset.score(b"c"), Some(1.5));
+			assert_eq!(zset.len(), 3);
+		}
+	}
+
+	#[test]
+	fn ranges_by_rank_in_both_directions() {
+		for zset in both_encodings(&[("a", 1.0), ("b", 2.0), ("c", 3.0), ("d", 4.0)]) {
+			let members = |start, count, reverse| zset.range(start, count, reverse).into_iter().map(|(m, _)| m).collect::<Vec<_>>();
+			assert_eq!(members(1, 2, false), ["b", "c"]);
+			assert_eq!(members(2, 10, true), ["c", "b", "a"]);
+			assert_eq!(members(3, 1, true), ["d"]);
+			assert!(members(4, 1, false).is_empty());
+			assert_eq!(zset.rank(b"missing"), None);
+		}
+	}
+
+	#[test]
+	fn counts_score_and_lex_ranges() {
+		for zset in both_encodings(&[("a", 1.0), ("b", 2.0), ("c", 2.0), ("d", 3.0)]) {
+			let range = ScoreRange::parse(b"(1", b"2").unwrap();
+			let start = zset.count_before(|score, _| range.below(score));
+			let end = zset.count_before(|score, _| range.not_above(score));
+			assert_eq!((start, end), (1, 3));
+		}
+		for zset in both_encodings(&[("a", 0.0), ("b", 0.0), ("c", 0.0), ("d", 0.0)]) {
+			let range = LexRange::parse(b"[b", b"(d").unwrap();
+			let start = zset.count_before(|_, member| range.below(member));
+			let end = zset.count_before(|_, member| range.not_above(member));
+			assert_eq!((start, end), (1, 3));
+		}
+		assert!(ScoreRange::parse(b"x", b"1").is_err());
+		assert!(LexRange::parse(b"b", b"+").is_err());
+	}
+
+	#[test]
+	fn removes_members() {
+		for mut zset in both_encodings(&[("a", 1.0), ("b", 2.0)]) {
+			assert!(zset.remove(b"a"));
+			assert!(!zset.remove(b"a"));
+			assert_eq!(zset.rank(b"b"), Some(0));
+			assert!(zset.remove(b"b"));
+			assert!(zset.is_empty());
+		}
+	}
+}
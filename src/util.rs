@@ -1,46 +1,81 @@
-use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::TcpStream};
-use rand::Rng;
-use crate::{resp::{null::RespNull, simple_string::RespSimpleString, RespObject, RespValues}, INPUT_BUFFER_SIZE};
+use std::time::{SystemTime, UNIX_EPOCH};
 
-pub async fn respond(stream: &mut TcpStream, response: RespValues) {
-	match stream.write_all(response.serialize().as_bytes()).await {
+use bytes::Bytes;
+use rand::{seq::SliceRandom, Rng};
+use crate::{commands::CommandError, connection::Connection, resp::{array::RespArray, bulk_string::RespBulkString, double::RespDouble, integer::RespInteger, map::RespMap, null::RespNull, set::RespSet, simple_error::RespSimpleError, simple_string::RespSimpleString, RespValues}};
+
+pub async fn respond(connection: &mut Connection, response: RespValues) {
+	match connection.write_frame(&response).await {
 		Ok(_) => (),
 		Err(e) => eprintln!("{}", e)
 	};
 }
 
-pub async fn request(stream: &mut TcpStream, request: RespValues) {
-	match stream.write_all(request.serialize().as_bytes()).await {
+pub async fn request(connection: &mut Connection, request: RespValues) {
+	match connection.write_frame(&request).await {
 		Ok(_) => (),
 		Err(e) => eprintln!("{}", e)
 	};
 }
 
-pub async fn null_reply(stream: &mut TcpStream) {
-	let response = RespValues::Null(RespNull {});
-	respond(stream, response).await;
+pub async fn null_reply(connection: &mut Connection) {
+	respond(connection, null_response()).await;
+}
+
+pub async fn error_reply(connection: &mut Connection, error: CommandError) {
+	let response = RespValues::Error(RespSimpleError::from_str(&error.to_string()));
+	respond(connection, response).await;
 }
 
 pub fn ok_response() -> RespValues {
 	RespValues::SimpleString(RespSimpleString::from_str("OK"))
 }
 
-pub async fn ok_reply(stream: &mut TcpStream) {
-	respond(stream, ok_response()).await;
+pub async fn ok_reply(connection: &mut Connection) {
+	respond(connection, ok_response()).await;
+}
+
+pub fn null_response() -> RespValues {
+	RespValues::Null(RespNull { array: false })
+}
+
+/// A null reply in place of an array, which RESP2 encodes differently than other null replies.
+pub fn null_array_response() -> RespValues {
+	RespValues::Null(RespNull { array: true })
+}
+
+pub fn simple_string_response(value: &str) -> RespValues {
+	RespValues::SimpleString(RespSimpleString::from_str(value))
+}
+
+pub fn bulk_string_response(value: impl Into<Bytes>) -> RespValues {
+	RespValues::BulkString(RespBulkString::from_raw(value))
+}
+
+pub fn integer_response(value: i64) -> RespValues {
+	RespValues::Integer(RespInteger::from_raw(value))
+}
+
+pub fn double_response(value: f64) -> RespValues {
+	RespValues::Double(RespDouble::from_raw(value))
+}
+
+pub fn array_response(values: Vec<RespValues>) -> RespValues {
+	RespValues::Array(RespArray::from_raw(values))
+}
+
+pub fn set_response(values: Vec<RespValues>) -> RespValues {
+	RespValues::Set(RespSet::from_raw(values))
+}
+
+pub fn map_response(values: Vec<(RespValues, RespValues)>) -> RespValues {
+	RespValues::Map(RespMap::from_raw(values))
 }
 
-pub async fn await_response(stream: &mut TcpStream) -> Option<RespValues> {
-	let mut input_buffer = [0; INPUT_BUFFER_SIZE];
-	match stream.read(&mut input_buffer).await {
-		Ok(n) => {
-			if n == 0 {
-				return None;
-			}
-			let raw_response = std::str::from_utf8(&input_buffer[0..n]).expect("RESP should always contain valid ASCII");
-			let (_, response) = RespValues::deserialize(raw_response);
-			Some(response)
-		},
-		Err(e) => panic!("Terminating connection. Error when reading into input buffer: {e}"),
+pub async fn await_response(connection: &mut Connection) -> Option<RespValues> {
+	match connection.read_frame().await {
+		Ok(response) => response,
+		Err(e) => panic!("Terminating connection. Error when reading response: {e}"),
 	}
 }
 
@@ -61,4 +96,161 @@ pub fn generate_master_replid() -> String {
 
 pub fn ping_response() -> RespValues {
 	RespValues::SimpleString(RespSimpleString::from_str("PONG"))
-}
\ No newline at end of file
+}
+
+/// Parses an integer argument the same way Redis does: an optional minus sign followed by digits without leading zeros.
+pub fn parse_integer(arg: &[u8]) -> Result<i64, CommandError> {
+	let digits = arg.strip_prefix(b"-").unwrap_or(arg);
+	let well_formed = !digits.is_empty()
+		&& digits.iter().all(|c| c.is_ascii_digit())
+		&& (digits[0] != b'0' || arg == b"0");
+	if !well_formed {
+		return Err(CommandError::NotAnInteger);
+	}
+	match std::str::from_utf8(arg).ok().and_then(|s| s.parse::<i64>().ok()) {
+		Some(v) => Ok(v),
+		None => Err(CommandError::NotAnInteger),
+	}
+}
+
+/// Parses a floating point argument. Like Redis, this accepts exponents and `inf`, but neither `nan` nor surrounding spaces.
+/// Values out of range, like `1e400`, are rejected instead of becoming infinite.
+pub fn parse_float(arg: &[u8]) -> Result<f64, CommandError> {
+	match std::str::from_utf8(arg).ok().and_then(|s| s.parse::<f64>().ok()) {
+		Some(v) if v.is_infinite() && !matches!(arg.to_ascii_lowercase().as_slice(), b"inf" | b"+inf" | b"-inf") => Err(CommandError::NotAFloat),
+		Some(v) if !v.is_nan() => Ok(v),
+		_ => Err(CommandError::NotAFloat),
+	}
+}
+
+/// Formats a float like Redis formats the long doubles of `INCRBYFLOAT`: without an exponent,
+/// with at most 17 decimals and without trailing zeros.
+pub fn format_long_double(value: f64) -> String {
+	// Display already gives the shortest representation that round-trips, without an exponent.
+	let mut formatted = value.to_string();
+	if formatted.split_once('.').is_some_and(|(_, decimals)| decimals.len() > 17) {
+		formatted = format!("{value:.17}");
+		formatted.truncate(formatted.trim_end_matches('0').trim_end_matches('.').len());
+	}
+	if formatted == "-0" {
+		formatted = String::from("0");
+	}
+	formatted
+}
+
+/// Resolves the inclusive range `start` to `end` of a sequence of `len` elements, where negative indexes count from the end,
+/// to the index of the first element and the number of elements. `None` if the range is empty.
+pub fn normalize_range(start: i64, end: i64, len: usize) -> Option<(usize, usize)> {
+	let len = len as i64;
+	let start = if start < 0 { len + start } else { start }.max(0);
+	let end = if end < 0 { len + end } else { end }.min(len - 1);
+	if start > end || start >= len {
+		return None;
+	}
+	Some((start as usize, (end - start + 1) as usize))
+}
+
+/// Random elements for the negative counts of the RANDMEMBER commands, which may repeat elements.
+/// Only what is needed to pick them is copied while the database is locked, the reply is built by `draw` after releasing it.
+pub enum RandomSample<T> {
+	Picked(Vec<T>),
+	/// More elements than the collection has, drawn from a copy of all of them.
+	Repeated { pool: Vec<T>, count: usize },
+}
+
+impl<T: Clone> RandomSample<T> {
+	/// Picks `count` elements of a collection with `len` elements, with `random` if there are fewer than `len` of them, from `all` otherwise.
+	pub fn new(count: usize, len: usize, mut random: impl FnMut() -> Option<T>, all: impl FnOnce() -> Vec<T>) -> RandomSample<T> {
+		match count > len {
+			true => RandomSample::Repeated { pool: all(), count },
+			false => RandomSample::Picked((0..count).filter_map(|_| random()).collect()),
+		}
+	}
+
+	pub fn draw(self) -> Vec<T> {
+		match self {
+			RandomSample::Picked(elements) => elements,
+			RandomSample::Repeated { pool, count } => {
+				let mut rng = rand::thread_rng();
+				(0..count).filter_map(|_| pool.choose(&mut rng).cloned()).collect()
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+thread_local! {
+	static FROZEN_TIME_MS: std::cell::Cell<Option<i64>> = const { std::cell::Cell::new(None) };
+}
+
+/// The current time as a Unix timestamp in milliseconds, the unit expiry times are stored in.
+pub fn unix_time_ms() -> i64 {
+	#[cfg(test)]
+	if let Some(now) = FROZEN_TIME_MS.get() {
+		return now;
+	}
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as i64)
+}
+
+/// Makes `unix_time_ms` return `now` on the current thread, so tests control when keys expire.
+/// `None` goes back to the system clock.
+#[cfg(test)]
+pub fn freeze_time_ms(now: Option<i64>) {
+	FROZEN_TIME_MS.set(now);
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn copies_the_collection_only_for_more_elements_than_it_has() {
+		let all = || vec![1, 2, 3];
+		let sample = RandomSample::new(2, 3, || Some(2), || unreachable!());
+		assert!(matches!(sample, RandomSample::Picked(ref elements) if elements.len() == 2));
+		let sample = RandomSample::new(5, 3, || unreachable!(), all);
+		assert!(matches!(sample, RandomSample::Repeated { ref pool, count: 5 } if pool.len() == 3));
+		let drawn = sample.draw();
+		assert_eq!(drawn.len(), 5);
+		assert!(drawn.iter().all(|element| all().contains(element)));
+		assert!(RandomSample::new(5, 0, || None, Vec::<i32>::new).draw().is_empty());
+	}
+
+	#[test]
+	fn parses_floats() {
+		assert_eq!(parse_float(b"1.5e3"), Ok(1500.0));
+		assert_eq!(parse_float(b"-inf"), Ok(f64::NEG_INFINITY));
+		assert_eq!(parse_float(b"+INF"), Ok(f64::INFINITY));
+		for invalid in [&b"nan"[..], b" 1", b"1e400", b"-1e400", b"infinity", b"abc"] {
+			assert_eq!(parse_float(invalid), Err(CommandError::NotAFloat));
+		}
+	}
+
+	#[test]
+	fn formats_the_shortest_round_trip_representation() {
+		assert_eq!(format_long_double(0.1 + 0.2), "0.30000000000000004");
+		assert_eq!(format_long_double(10.5), "10.5");
+		assert_eq!(format_long_double(3.0), "3");
+		assert_eq!(format_long_double("0.123456789012345678".parse().unwrap()), "0.12345678901234568");
+	}
+
+	#[test]
+	fn formats_large_integers_without_exponent() {
+		assert_eq!(format_long_double(12345678901234567.0), "12345678901234568");
+		assert_eq!(format_long_double(9007199254740993.0), "9007199254740992");
+		assert_eq!(format_long_double(1e20), "100000000000000000000");
+	}
+
+	#[test]
+	fn formats_negative_zero_as_zero() {
+		assert_eq!(format_long_double(-0.0), "0");
+	}
+
+	#[test]
+	fn rounds_to_17_decimals() {
+		assert_eq!(format_long_double(1.5e-10), "0.00000000015");
+		assert_eq!(format_long_double(1.23456789e-15), "0.00000000000000123");
+		assert_eq!(format_long_double(1e-20), "0");
+		assert_eq!(format_long_double(-1e-20), "0");
+	}
+}